@@ -1,85 +1,210 @@
+pub mod app_volume;
 pub mod backlight;
 pub mod battery;
+pub mod binding_mode;
 pub mod bluetooth;
+pub mod card_profile;
+pub mod cgroup_cpu;
+pub mod charge_threshold;
+pub mod ci;
+pub mod command_output;
 pub mod cpu;
+pub mod cpu_vulnerabilities;
 pub mod custom;
 pub mod custom_dbus;
+pub mod dbus_property;
+pub mod departures;
 pub mod disk_space;
+pub mod display_profile;
+pub mod dns;
 pub mod docker;
+pub mod drive_temp;
+pub mod failed_units;
+pub mod fan;
+pub mod file_count;
+pub mod focused_process;
 pub mod focused_window;
+pub mod git;
 pub mod github;
+pub mod group;
 pub mod ibus;
+pub mod idle_inhibitor;
+pub mod imap;
+pub mod kbd_backlight;
 pub mod kdeconnect;
+pub mod kernel;
+pub mod key_repeat;
 pub mod keyboard_layout;
 pub mod load;
+pub mod lock_keys;
+pub mod log_watch;
+pub mod logind;
 pub mod maildir;
 pub mod memory;
+pub mod mpd;
 pub mod music;
 pub mod net;
 pub mod networkmanager;
+pub mod nextcloud;
+pub mod night_light;
+pub mod nm_vpn;
+pub mod notify;
 #[cfg(feature = "notmuch")]
 pub mod notmuch;
 pub mod nvidia_gpu;
+pub mod obs;
 pub mod pacman;
+pub mod ping;
+pub mod platform_profile;
 pub mod pomodoro;
+pub mod power_profile;
+pub mod privacy;
+pub mod process;
+pub mod rapl;
+pub mod reboot;
+pub mod recording_space;
+pub mod resolution;
+pub mod sample_format;
+pub mod screen_layout;
+pub mod smart;
 pub mod sound;
 pub mod speedtest;
+pub mod split_layout;
+pub mod ssh_sessions;
+pub mod sun;
 pub mod taskwarrior;
 pub mod temperature;
 pub mod template;
+pub mod throttle;
 pub mod time;
+pub mod timers;
 pub mod toggle;
+pub mod transmission;
+pub mod updates;
 pub mod uptime;
+pub mod vpn_leak;
+#[cfg(feature = "pulseaudio")]
+pub mod vu_meter;
 pub mod watson;
 pub mod weather;
+pub mod window_count;
+pub mod window_geometry;
+pub mod window_title;
+pub mod wireguard;
 pub mod xrandr;
+pub mod xruns;
+pub mod zram;
 
+use self::app_volume::*;
 use self::backlight::*;
 use self::battery::*;
+use self::binding_mode::*;
 use self::bluetooth::*;
+use self::card_profile::*;
+use self::cgroup_cpu::*;
+use self::charge_threshold::*;
+use self::ci::*;
+use self::command_output::*;
 use self::cpu::*;
+use self::cpu_vulnerabilities::*;
 use self::custom::*;
 use self::custom_dbus::*;
+use self::dbus_property::*;
+use self::departures::*;
 use self::disk_space::*;
+use self::display_profile::*;
+use self::dns::*;
 use self::docker::*;
+use self::drive_temp::*;
+use self::failed_units::*;
+use self::fan::*;
+use self::file_count::*;
+use self::focused_process::*;
 use self::focused_window::*;
+use self::git::*;
 use self::github::*;
+use self::group::*;
 use self::ibus::*;
+use self::idle_inhibitor::*;
+use self::imap::*;
+use self::kbd_backlight::*;
 use self::kdeconnect::*;
+use self::kernel::*;
+use self::key_repeat::*;
 use self::keyboard_layout::*;
 use self::load::*;
+use self::lock_keys::*;
+use self::log_watch::*;
+use self::logind::*;
 use self::maildir::*;
 use self::memory::*;
+use self::mpd::*;
 use self::music::*;
 use self::net::*;
 use self::networkmanager::*;
+use self::nextcloud::*;
+use self::night_light::*;
+use self::nm_vpn::*;
+use self::notify::*;
 #[cfg(feature = "notmuch")]
 use self::notmuch::*;
 use self::nvidia_gpu::*;
+use self::obs::*;
 use self::pacman::*;
+use self::ping::*;
+use self::platform_profile::*;
 use self::pomodoro::*;
+use self::power_profile::*;
+use self::privacy::*;
+use self::process::*;
+use self::rapl::*;
+use self::reboot::*;
+use self::recording_space::*;
+use self::resolution::*;
+use self::sample_format::*;
+use self::screen_layout::*;
+use self::smart::*;
 use self::sound::*;
 use self::speedtest::*;
+use self::split_layout::*;
+use self::ssh_sessions::*;
+use self::sun::*;
 use self::taskwarrior::*;
 use self::temperature::*;
 use self::template::*;
+use self::throttle::*;
 use self::time::*;
+use self::timers::*;
 use self::toggle::*;
+use self::transmission::*;
+use self::updates::*;
 use self::uptime::*;
+use self::vpn_leak::*;
+#[cfg(feature = "pulseaudio")]
+use self::vu_meter::*;
 use self::watson::*;
 use self::weather::*;
+use self::window_count::*;
+use self::window_geometry::*;
+use self::window_title::*;
+use self::wireguard::*;
 use self::xrandr::*;
+use self::xruns::*;
+use self::zram::*;
 
+use std::process::Command;
 use std::time::Duration;
 
 use crossbeam_channel::Sender;
 use serde::de::Deserialize;
 use toml::value::Value;
 
-use crate::config::Config;
+use crate::config::{Config, IconPosition};
+use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::I3BarEvent;
 use crate::scheduler::Task;
+use crate::themes::ThemeOverrides;
 use crate::widget::I3BarWidget;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -117,6 +242,12 @@ pub trait Block {
     fn click(&mut self, _event: &I3BarEvent) -> Result<()> {
         Ok(())
     }
+
+    /// Whether the block is updated right after one of its widgets was clicked, so that
+    /// the effect of the click shows immediately rather than on the next update.
+    fn update_on_click(&self) -> bool {
+        false
+    }
 }
 
 pub trait ConfigBlock: Block {
@@ -141,49 +272,328 @@ macro_rules! block {
     }};
 }
 
+/// Wraps a block that is only shown while its `if_command` exits successfully.
+struct IfCommand {
+    inner: Box<dyn Block>,
+    command: String,
+    visible: bool,
+    /// The last update interval requested by the inner block
+    interval: Option<Update>,
+    /// How often the command is run when the inner block has not requested an interval,
+    /// e.g. because it is updated by events only
+    recheck_interval: Duration,
+}
+
+impl IfCommand {
+    fn check(&self) -> bool {
+        Command::new("sh")
+            .args(["-c", &self.command])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+}
+
+impl Block for IfCommand {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.visible {
+            self.inner.view()
+        } else {
+            Vec::new()
+        }
+    }
+
+    // The command is only run when the block updates, so its result is
+    // reused for the block's interval.
+    fn update(&mut self) -> Result<Option<Update>> {
+        self.visible = self.check();
+        if !self.visible {
+            return Ok(Some(
+                self.interval
+                    .clone()
+                    .unwrap_or(Update::Every(self.recheck_interval)),
+            ));
+        }
+        let update = self.inner.update()?;
+        if let Some(Update::Every(_)) = update {
+            self.interval = update.clone();
+        }
+        // Otherwise a block that is only updated by events would never be hidden again
+        Ok(update.or(Some(Update::Every(self.recheck_interval))))
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if self.visible {
+            self.inner.click(event)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn update_on_click(&self) -> bool {
+        self.inner.update_on_click()
+    }
+}
+
+/// Wraps a block whose `update_on_click` is overridden in its config.
+struct UpdateOnClick {
+    inner: Box<dyn Block>,
+    enabled: bool,
+}
+
+impl Block for UpdateOnClick {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        self.inner.view()
+    }
+
+    fn update(&mut self) -> Result<Option<Update>> {
+        self.inner.update()
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        self.inner.click(event)
+    }
+
+    fn update_on_click(&self) -> bool {
+        self.enabled
+    }
+}
+
 pub fn create_block(
     name: &str,
-    block_config: Value,
-    config: Config,
+    mut block_config: Value,
+    mut config: Config,
     update_request: Sender<Task>,
 ) -> Result<Box<dyn Block>> {
-    match name {
+    // `if_command`, `if_command_interval`, `theme_overrides`, `icon_position` and
+    // `update_on_click` are common to all blocks, so they have to be removed before the
+    // block specific config is deserialized.
+    let (if_command, if_command_interval, theme_overrides, icon_position, update_on_click) =
+        match block_config.as_table_mut() {
+            Some(table) => (
+                table.remove("if_command"),
+                table.remove("if_command_interval"),
+                table.remove("theme_overrides"),
+                table.remove("icon_position"),
+                table.remove("update_on_click"),
+            ),
+            None => (None, None, None, None, None),
+        };
+
+    if let Some(icon_position) = icon_position {
+        config.icon_position = IconPosition::deserialize(icon_position)
+            .configuration_error("Failed to deserialize icon_position.")?;
+    }
+
+    if let Some(theme_overrides) = theme_overrides {
+        let theme_overrides = ThemeOverrides::deserialize(theme_overrides)
+            .configuration_error("Failed to deserialize theme_overrides.")?;
+        config.theme = theme_overrides.apply(config.theme);
+    }
+
+    let block = match name {
         // Please keep these in alphabetical order.
+        "app_volume" => block!(AppVolume, block_config, config, update_request),
         "backlight" => block!(Backlight, block_config, config, update_request),
         "battery" => block!(Battery, block_config, config, update_request),
+        "binding_mode" => block!(BindingMode, block_config, config, update_request),
         "bluetooth" => block!(Bluetooth, block_config, config, update_request),
+        "card_profile" => block!(CardProfile, block_config, config, update_request),
+        "cgroup_cpu" => block!(CgroupCpu, block_config, config, update_request),
+        "charge_threshold" => block!(ChargeThreshold, block_config, config, update_request),
+        "ci" => block!(Ci, block_config, config, update_request),
+        "command_output" => block!(CommandOutput, block_config, config, update_request),
         "cpu" => block!(Cpu, block_config, config, update_request),
+        "cpu_vulnerabilities" => block!(CpuVulnerabilities, block_config, config, update_request),
         "custom" => block!(Custom, block_config, config, update_request),
         "custom_dbus" => block!(CustomDBus, block_config, config, update_request),
+        "dbus_property" => block!(DbusProperty, block_config, config, update_request),
+        "departures" => block!(Departures, block_config, config, update_request),
         "disk_space" => block!(DiskSpace, block_config, config, update_request),
+        "display_profile" => block!(DisplayProfile, block_config, config, update_request),
+        "dns" => block!(Dns, block_config, config, update_request),
         "docker" => block!(Docker, block_config, config, update_request),
+        "drive_temp" => block!(DriveTemp, block_config, config, update_request),
+        "failed_units" => block!(FailedUnits, block_config, config, update_request),
+        "fan" => block!(Fan, block_config, config, update_request),
+        "file_count" => block!(FileCount, block_config, config, update_request),
+        "focused_process" => block!(FocusedProcess, block_config, config, update_request),
         "focused_window" => block!(FocusedWindow, block_config, config, update_request),
+        "git" => block!(Git, block_config, config, update_request),
         "github" => block!(Github, block_config, config, update_request),
+        "group" => block!(Group, block_config, config, update_request),
         "ibus" => block!(IBus, block_config, config, update_request),
+        "idle_inhibitor" => block!(IdleInhibitor, block_config, config, update_request),
+        "imap" => block!(Imap, block_config, config, update_request),
+        "kbd_backlight" => block!(KbdBacklight, block_config, config, update_request),
         "kdeconnect" => block!(KDEConnect, block_config, config, update_request),
+        "kernel" => block!(Kernel, block_config, config, update_request),
+        "key_repeat" => block!(KeyRepeat, block_config, config, update_request),
         "keyboard_layout" => block!(KeyboardLayout, block_config, config, update_request),
         "load" => block!(Load, block_config, config, update_request),
+        "lock_keys" => block!(LockKeys, block_config, config, update_request),
+        "log_watch" => block!(LogWatch, block_config, config, update_request),
+        "logind" => block!(Logind, block_config, config, update_request),
         "maildir" => block!(Maildir, block_config, config, update_request),
         "memory" => block!(Memory, block_config, config, update_request),
+        "mpd" => block!(Mpd, block_config, config, update_request),
         "music" => block!(Music, block_config, config, update_request),
         "net" => block!(Net, block_config, config, update_request),
         "networkmanager" => block!(NetworkManager, block_config, config, update_request),
+        "nextcloud" => block!(Nextcloud, block_config, config, update_request),
+        "night_light" => block!(NightLight, block_config, config, update_request),
+        "nm_vpn" => block!(NmVpn, block_config, config, update_request),
+        "notify" => block!(Notify, block_config, config, update_request),
         #[cfg(feature = "notmuch")]
         "notmuch" => block!(Notmuch, block_config, config, update_request),
         "nvidia_gpu" => block!(NvidiaGpu, block_config, config, update_request),
+        "obs" => block!(Obs, block_config, config, update_request),
         "pacman" => block!(Pacman, block_config, config, update_request),
+        "ping" => block!(Ping, block_config, config, update_request),
+        "platform_profile" => block!(PlatformProfile, block_config, config, update_request),
         "pomodoro" => block!(Pomodoro, block_config, config, update_request),
+        "power_profile" => block!(PowerProfile, block_config, config, update_request),
+        "privacy" => block!(Privacy, block_config, config, update_request),
+        "process" => block!(Process, block_config, config, update_request),
+        "rapl" => block!(Rapl, block_config, config, update_request),
+        "reboot" => block!(Reboot, block_config, config, update_request),
+        "recording_space" => block!(RecordingSpace, block_config, config, update_request),
+        "resolution" => block!(Resolution, block_config, config, update_request),
+        "sample_format" => block!(SampleFormat, block_config, config, update_request),
+        "screen_layout" => block!(ScreenLayout, block_config, config, update_request),
+        "smart" => block!(Smart, block_config, config, update_request),
         "sound" => block!(Sound, block_config, config, update_request),
         "speedtest" => block!(SpeedTest, block_config, config, update_request),
+        "split_layout" => block!(SplitLayout, block_config, config, update_request),
+        "ssh_sessions" => block!(SshSessions, block_config, config, update_request),
+        "sun" => block!(Sun, block_config, config, update_request),
         "taskwarrior" => block!(Taskwarrior, block_config, config, update_request),
         "temperature" => block!(Temperature, block_config, config, update_request),
         "template" => block!(Template, block_config, config, update_request),
+        "throttle" => block!(Throttle, block_config, config, update_request),
         "time" => block!(Time, block_config, config, update_request),
+        "timers" => block!(Timers, block_config, config, update_request),
         "toggle" => block!(Toggle, block_config, config, update_request),
+        "transmission" => block!(Transmission, block_config, config, update_request),
+        "updates" => block!(Updates, block_config, config, update_request),
         "uptime" => block!(Uptime, block_config, config, update_request),
+        "vpn_leak" => block!(VpnLeak, block_config, config, update_request),
+        #[cfg(feature = "pulseaudio")]
+        "vu_meter" => block!(VuMeter, block_config, config, update_request),
         "watson" => block!(Watson, block_config, config, update_request),
         "weather" => block!(Weather, block_config, config, update_request),
+        "window_count" => block!(WindowCount, block_config, config, update_request),
+        "window_geometry" => block!(WindowGeometry, block_config, config, update_request),
+        "window_title" => block!(WindowTitle, block_config, config, update_request),
+        "wireguard" => block!(Wireguard, block_config, config, update_request),
         "xrandr" => block!(Xrandr, block_config, config, update_request),
+        "xruns" => block!(Xruns, block_config, config, update_request),
+        "zram" => block!(Zram, block_config, config, update_request),
         other => Err(BlockError(other.to_string(), "Unknown block!".to_string())),
+    }?;
+
+    let block = match update_on_click {
+        Some(Value::Boolean(enabled)) => Box::new(UpdateOnClick {
+            inner: block,
+            enabled,
+        }),
+        Some(_) => {
+            return Err(BlockError(
+                name.to_string(),
+                "update_on_click must be a boolean".to_string(),
+            ))
+        }
+        None => block,
+    };
+
+    let recheck_interval = match if_command_interval {
+        Some(interval) => deserialize_duration(interval)
+            .configuration_error("Failed to deserialize if_command_interval.")?,
+        None => Duration::from_secs(10),
+    };
+
+    match if_command {
+        Some(Value::String(command)) => Ok(Box::new(IfCommand {
+            inner: block,
+            command,
+            visible: false,
+            interval: None,
+            recheck_interval,
+        })),
+        Some(_) => Err(BlockError(
+            name.to_string(),
+            "if_command must be a string".to_string(),
+        )),
+        None => Ok(block),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileTouch, PathChild};
+    use assert_fs::TempDir;
+
+    /// A block that is only updated by events, like most push-based blocks.
+    struct EventBlock {
+        updates: usize,
+    }
+
+    impl Block for EventBlock {
+        fn id(&self) -> &str {
+            "event"
+        }
+
+        fn view(&self) -> Vec<&dyn I3BarWidget> {
+            Vec::new()
+        }
+
+        fn update(&mut self) -> Result<Option<Update>> {
+            self.updates += 1;
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_if_command_recheck() {
+        let temp_dir = TempDir::new().unwrap();
+        let gate = temp_dir.child("gate");
+        let mut block = IfCommand {
+            inner: Box::new(EventBlock { updates: 0 }),
+            command: format!("test -e {}", gate.path().display()),
+            visible: false,
+            interval: None,
+            recheck_interval: Duration::from_secs(10),
+        };
+
+        // Failing before the inner block was ever updated
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(10)))
+        );
+        assert!(!block.visible);
+
+        gate.touch().unwrap();
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(10)))
+        );
+        assert!(block.visible);
+
+        // The visible block is re-checked although the inner one waits for events
+        std::fs::remove_file(gate.path()).unwrap();
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(10)))
+        );
+        assert!(!block.visible);
     }
 }
@@ -0,0 +1,295 @@
+use std::fs::read_to_string;
+use std::process::Command;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::{Config, LogicalDirection, Scrolling};
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::ipc::{self, FocusedWindow};
+use crate::pactl;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// An application's stream to a sound card, as listed by `pactl list sink-inputs`.
+#[derive(Debug, Default, PartialEq)]
+struct SinkInput {
+    index: u32,
+    app: String,
+    pid: Option<i32>,
+    volume: u32,
+    muted: bool,
+}
+
+pub struct AppVolume {
+    text: ButtonWidget,
+    id: String,
+    format: FormatTemplate,
+    step_width: u32,
+    scrolling: Scrolling,
+    /// The focused window and its process, if it has one
+    focused: FocusedWindow,
+    /// The stream of the focused window's process, once one was found
+    stream: Option<SinkInput>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AppVolumeConfig {
+    /// The percentage the volume is changed by per scroll step
+    #[serde(default = "AppVolumeConfig::default_step_width")]
+    pub step_width: u32,
+
+    /// Format override
+    #[serde(default = "AppVolumeConfig::default_format")]
+    pub format: String,
+}
+
+impl AppVolumeConfig {
+    fn default_step_width() -> u32 {
+        5
+    }
+
+    fn default_format() -> String {
+        "{volume}%".to_owned()
+    }
+}
+
+/// Parses the output of `pactl list sink-inputs` in the C locale.
+fn parse_sink_inputs(output: &str) -> Vec<SinkInput> {
+    let mut inputs: Vec<SinkInput> = Vec::new();
+    for line in output.lines() {
+        if let Some(index) = line.strip_prefix("Sink Input #") {
+            if let Ok(index) = index.trim().parse() {
+                inputs.push(SinkInput {
+                    index,
+                    ..SinkInput::default()
+                });
+            }
+            continue;
+        }
+        let input = match inputs.last_mut() {
+            Some(input) => input,
+            None => continue,
+        };
+        let line = line.trim();
+        // e.g. `Volume: front-left: 42598 /  65% / -11.23 dB,   front-right: ...`
+        if let Some(volume) = line.strip_prefix("Volume:") {
+            input.volume = volume
+                .split('/')
+                .nth(1)
+                .and_then(|percent| percent.trim().trim_end_matches('%').parse().ok())
+                .unwrap_or(0);
+        } else if let Some(muted) = line.strip_prefix("Mute:") {
+            input.muted = muted.trim() == "yes";
+        } else if let Some((key, value)) = line.split_once(" = ") {
+            let value = value.trim_matches('"');
+            match key {
+                "application.name" => input.app = value.to_owned(),
+                "application.process.id" => input.pid = value.parse().ok(),
+                _ => (),
+            }
+        }
+    }
+    inputs
+}
+
+/// Extracts the parent's PID from `/proc/<pid>/stat`.
+fn parse_parent_pid(stat: &str) -> Option<i32> {
+    // The command name may contain spaces, the state and the parent's PID follow it
+    let mut fields = stat.get(stat.rfind(')')? + 1..)?.split_whitespace();
+    fields.nth(1)?.parse().ok()
+}
+
+/// Whether `pid` is `ancestor` or one of its descendants, as e.g. browsers play sound
+/// from a child process of the one owning the window.
+fn is_descendant(pid: i32, ancestor: i32) -> bool {
+    let mut pid = pid;
+    // Bounded, in case the chain of parents is not read consistently
+    for _ in 0..64 {
+        if pid == ancestor {
+            return true;
+        }
+        match read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|stat| parse_parent_pid(&stat))
+        {
+            Some(parent) if parent > 1 => pid = parent,
+            _ => return false,
+        }
+    }
+    false
+}
+
+fn pactl(args: &[&str]) -> Result<String> {
+    let output = Command::new("pactl")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .block_error("app_volume", "failed to run pactl")?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ConfigBlock for AppVolume {
+    type Config = AppVolumeConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // The streams change without the focus changing, e.g. when a video starts playing
+        pactl::subscribe("app_volume", id.clone(), tx.clone(), |line| {
+            // e.g. `Event 'change' on sink-input #42`
+            line.contains(" on sink-input ")
+        });
+
+        let focused = ipc::watch_focused_pid("app_volume", id.clone(), tx)?;
+
+        Ok(AppVolume {
+            scrolling: config.scrolling,
+            text: ButtonWidget::new(config, &id),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("app_volume", "Invalid format specified")?,
+            step_width: block_config.step_width,
+            focused,
+            stream: None,
+        })
+    }
+}
+
+impl Block for AppVolume {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let pid = match *self
+            .focused
+            .lock()
+            .block_error("app_volume", "failed to acquire lock")?
+        {
+            Some((_, pid)) => pid,
+            None => {
+                self.stream = None;
+                return Ok(None);
+            }
+        };
+
+        self.stream = parse_sink_inputs(&pactl(&["list", "sink-inputs"])?)
+            .into_iter()
+            .find(|input| match input.pid {
+                Some(input_pid) => is_descendant(input_pid, pid),
+                None => false,
+            });
+
+        if let Some(ref stream) = self.stream {
+            let values = map!(
+                "{app}" => stream.app.clone(),
+                "{volume}" => stream.volume.to_string()
+            );
+            self.text.set_text(self.format.render_static_str(&values)?);
+            if stream.muted {
+                self.text.set_icon("volume_muted");
+                self.text.set_state(State::Warning);
+            } else {
+                self.text.set_icon(match stream.volume {
+                    0..=20 => "volume_empty",
+                    21..=70 => "volume_half",
+                    _ => "volume_full",
+                });
+                self.text.set_state(State::Idle);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        match self.stream {
+            Some(_) => vec![&self.text],
+            None => vec![],
+        }
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) {
+            return Ok(());
+        }
+        let index = match self.stream {
+            Some(ref stream) => stream.index.to_string(),
+            None => return Ok(()),
+        };
+
+        let step = |sign: &str| format!("{}{}%", sign, self.step_width);
+        match event.button {
+            MouseButton::Left => {
+                pactl(&["set-sink-input-mute", &index, "toggle"])?;
+            }
+            button => match self.scrolling.to_logical_direction(button) {
+                Some(LogicalDirection::Up) => {
+                    pactl(&["set-sink-input-volume", &index, &step("+")])?;
+                }
+                Some(LogicalDirection::Down) => {
+                    pactl(&["set-sink-input-volume", &index, &step("-")])?;
+                }
+                None => return Ok(()),
+            },
+        }
+        self.update()?;
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sink_inputs() {
+        let output = "\
+Sink Input #42
+\tDriver: protocol-native.c
+\tSink: 1
+\tMute: no
+\tVolume: front-left: 42598 /  65% / -11.23 dB,   front-right: 42598 /  65% / -11.23 dB
+\t        balance 0.00
+\tProperties:
+\t\tmedia.name = \"Playback\"
+\t\tapplication.name = \"Firefox\"
+\t\tapplication.process.id = \"4321\"
+Sink Input #43
+\tMute: yes
+\tVolume: mono: 65536 / 100% / 0.00 dB
+\tProperties:
+\t\tapplication.name = \"mpv\"
+";
+        assert_eq!(
+            parse_sink_inputs(output),
+            vec![
+                SinkInput {
+                    index: 42,
+                    app: "Firefox".to_owned(),
+                    pid: Some(4321),
+                    volume: 65,
+                    muted: false,
+                },
+                SinkInput {
+                    index: 43,
+                    app: "mpv".to_owned(),
+                    pid: None,
+                    volume: 100,
+                    muted: true,
+                },
+            ]
+        );
+
+        let stat = "4242 (Web Content (x)) S 1234 4242 4242 0 -1 4194560";
+        assert_eq!(parse_parent_pid(stat), Some(1234));
+        assert_eq!(parse_parent_pid("garbage"), None);
+    }
+}
@@ -22,9 +22,10 @@ use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
 use crate::config::{Config, LogicalDirection, Scrolling};
 use crate::errors::*;
-use crate::input::I3BarEvent;
+use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
-use crate::widget::I3BarWidget;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, MinWidth};
 use crate::widgets::button::ButtonWidget;
 
 /// Read a brightness value from the given path.
@@ -47,6 +48,9 @@ fn read_brightness(device_file: &Path) -> Result<u64> {
 pub struct BacklitDevice {
     max_brightness: u64,
     device_path: PathBuf,
+    /// The class of the device in `/sys/class`, `backlight` for screens and `leds` for
+    /// keyboards
+    subsystem: &'static str,
 }
 
 impl BacklitDevice {
@@ -75,6 +79,7 @@ impl BacklitDevice {
         Ok(BacklitDevice {
             max_brightness,
             device_path: first_device.path(),
+            subsystem: "backlight",
         })
     }
 
@@ -97,9 +102,52 @@ impl BacklitDevice {
         Ok(BacklitDevice {
             max_brightness,
             device_path,
+            subsystem: "backlight",
         })
     }
 
+    /// Use the keyboard backlight `device` in `/sys/class/leds`, or the first one found
+    /// there if not set.
+    pub fn keyboard(device: Option<String>) -> Result<Self> {
+        let device_path = match device {
+            Some(device) => Path::new("/sys/class/leds").join(device),
+            None => Path::new("/sys/class/leds")
+                .read_dir()
+                .block_error("kbd_backlight", "Failed to read leds device directory")?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .find(|path| path.to_string_lossy().contains("kbd_backlight"))
+                .block_error("kbd_backlight", "No keyboard backlight found")?,
+        };
+        if !device_path.exists() {
+            return Err(BlockError(
+                "kbd_backlight".to_string(),
+                format!(
+                    "Keyboard backlight '{}' does not exist",
+                    device_path.to_string_lossy()
+                ),
+            ));
+        }
+
+        let max_brightness = read_brightness(&device_path.join("max_brightness"))?;
+
+        Ok(BacklitDevice {
+            max_brightness,
+            device_path,
+            subsystem: "leds",
+        })
+    }
+
+    /// The highest raw brightness level of this device.
+    pub fn max_brightness(&self) -> u64 {
+        self.max_brightness
+    }
+
+    /// Query the raw brightness level of this device, from 0 to `max_brightness`.
+    pub fn raw_brightness(&self) -> Result<u64> {
+        read_brightness(&self.brightness_file())
+    }
+
     /// Query the brightness value for this backlit device, as a percent.
     pub fn brightness(&self) -> Result<u64> {
         let raw = read_brightness(&self.brightness_file())?;
@@ -117,7 +165,12 @@ impl BacklitDevice {
             _ => 100,
         };
         let raw = (((safe_value as f64) / 100.0) * (self.max_brightness as f64)).round() as u64;
+        self.set_raw_brightness(raw)
+    }
 
+    /// Set the raw brightness level of this device, from 0 to `max_brightness`.
+    pub fn set_raw_brightness(&self, raw: u64) -> Result<()> {
+        let raw = raw.min(self.max_brightness);
         let file = OpenOptions::new()
             .write(true)
             .open(self.device_path.join("brightness"));
@@ -151,7 +204,7 @@ impl BacklitDevice {
             "SetBrightness",
         )
         .block_error("backlight", "Failed to create D-Bus message")?
-        .append2("backlight", device_name)
+        .append2(self.subsystem, device_name)
         .append1(raw_value as u32);
 
         con.send_with_reply_and_block(msg, 1000)
@@ -159,11 +212,20 @@ impl BacklitDevice {
             .map(|_| ())
     }
 
+    /// The name of this device in `/sys/class/backlight` or `/sys/class/leds`.
+    pub fn name(&self) -> &str {
+        self.device_path
+            .file_name()
+            .and_then(|x| x.to_str())
+            .unwrap_or_default()
+    }
+
     /// The brightness file itself.
     // amdgpu drivers set the actual_brightness in a different scale than [0, max_brightness],
     // so we have to use the 'brightness' file instead. This may be fixed in the new 5.7 kernel?
     pub fn brightness_file(&self) -> PathBuf {
-        if self.device_path.ends_with("amdgpu_bl0") {
+        // LEDs have no actual_brightness
+        if self.device_path.ends_with("amdgpu_bl0") || self.subsystem == "leds" {
             self.device_path.join("brightness")
         } else {
             self.device_path.join("actual_brightness")
@@ -175,7 +237,9 @@ impl BacklitDevice {
 pub struct Backlight {
     id: String,
     output: ButtonWidget,
-    device: BacklitDevice,
+    devices: Vec<BacklitDevice>,
+    current: usize,
+    format: FormatTemplate,
     step_width: u64,
     scrolling: Scrolling,
 }
@@ -188,6 +252,19 @@ pub struct BacklightConfig {
     #[serde(default = "BacklightConfig::default_device")]
     pub device: Option<String>,
 
+    /// Several backlight devices to switch between by clicking the block. Takes precedence over
+    /// `device`.
+    #[serde(default = "BacklightConfig::default_devices")]
+    pub devices: Option<Vec<String>>,
+
+    /// Format override
+    #[serde(default = "BacklightConfig::default_format")]
+    pub format: String,
+
+    /// Minimum width of the block, as a number of characters or a sample text
+    #[serde(default = "BacklightConfig::default_min_width")]
+    pub min_width: Option<MinWidth>,
+
     /// The steps brightness is in/decreased for the selected screen (When greater than 50 it gets limited to 50)
     #[serde(default = "BacklightConfig::default_step_width")]
     pub step_width: u64,
@@ -198,6 +275,18 @@ impl BacklightConfig {
         None
     }
 
+    fn default_devices() -> Option<Vec<String>> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{brightness}%".to_owned()
+    }
+
+    fn default_min_width() -> Option<MinWidth> {
+        None
+    }
+
     fn default_step_width() -> u64 {
         5
     }
@@ -211,32 +300,42 @@ impl ConfigBlock for Backlight {
         config: Config,
         tx_update_request: Sender<Task>,
     ) -> Result<Self> {
-        let device = match block_config.device {
-            Some(path) => BacklitDevice::from_device(path),
-            None => BacklitDevice::default(),
-        }?;
+        let devices = match (block_config.devices, block_config.device) {
+            (Some(devices), _) if !devices.is_empty() => devices
+                .into_iter()
+                .map(BacklitDevice::from_device)
+                .collect::<Result<Vec<_>>>()?,
+            (_, Some(path)) => vec![BacklitDevice::from_device(path)?],
+            _ => vec![BacklitDevice::default()?],
+        };
 
         let id = Uuid::new_v4().to_simple().to_string();
-        let brightness_file = device.brightness_file();
+        let brightness_files: Vec<PathBuf> =
+            devices.iter().map(BacklitDevice::brightness_file).collect();
 
         let scrolling = config.scrolling;
         let backlight = Backlight {
-            output: ButtonWidget::new(config, &id),
+            output: ButtonWidget::new(config, &id).with_min_width(block_config.min_width),
             id: id.clone(),
-            device,
+            devices,
+            current: 0,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("backlight", "Invalid format specified")?,
             step_width: block_config.step_width,
             scrolling,
         };
 
-        // Spin up a thread to watch for changes to the brightness file for the
-        // device, and schedule an update if needed.
+        // Spin up a thread to watch for changes to the brightness files of the
+        // devices, and schedule an update if needed.
         thread::Builder::new()
             .name("backlight".into())
             .spawn(move || {
                 let mut notify = Inotify::init().expect("Failed to start inotify");
-                notify
-                    .add_watch(brightness_file, WatchMask::MODIFY)
-                    .expect("Failed to watch brightness file");
+                for brightness_file in brightness_files {
+                    notify
+                        .add_watch(brightness_file, WatchMask::MODIFY)
+                        .expect("Failed to watch brightness file");
+                }
 
                 let mut buffer = [0; 1024];
                 loop {
@@ -265,8 +364,14 @@ impl ConfigBlock for Backlight {
 
 impl Block for Backlight {
     fn update(&mut self) -> Result<Option<Update>> {
-        let brightness = self.device.brightness()?;
-        self.output.set_text(format!("{}%", brightness));
+        let device = &self.devices[self.current];
+        let brightness = device.brightness()?;
+        let values = map!(
+            "{brightness}" => brightness.to_string(),
+            "{display}" => device.name().to_owned()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
         match brightness {
             0..=19 => self.output.set_icon("backlight_empty"),
             20..=39 => self.output.set_icon("backlight_partial1"),
@@ -284,17 +389,25 @@ impl Block for Backlight {
     fn click(&mut self, event: &I3BarEvent) -> Result<()> {
         if let Some(ref name) = event.name {
             if name.as_str() == self.id {
-                let brightness = self.device.brightness()?;
+                if event.button == MouseButton::Left {
+                    // Select the next device, scrolling only affects the selected one
+                    self.current = (self.current + 1) % self.devices.len();
+                    self.update()?;
+                    return Ok(());
+                }
+
+                let device = &self.devices[self.current];
+                let brightness = device.brightness()?;
                 use LogicalDirection::*;
                 match self.scrolling.to_logical_direction(event.button) {
                     Some(Up) => {
                         if brightness < 100 {
-                            self.device.set_brightness(brightness + self.step_width)?;
+                            device.set_brightness(brightness + self.step_width)?;
                         }
                     }
                     Some(Down) => {
                         if brightness > self.step_width {
-                            self.device.set_brightness(brightness - self.step_width)?;
+                            device.set_brightness(brightness - self.step_width)?;
                         }
                     }
                     None => {}
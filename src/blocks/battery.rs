@@ -20,7 +20,7 @@ use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::scheduler::Task;
-use crate::util::{battery_level_to_icon, format_percent_bar, read_file, FormatTemplate};
+use crate::util::{battery_level_to_icon, format_percent_bar, read_file, FormatTemplate, History};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::text::TextWidget;
 
@@ -40,6 +40,13 @@ pub trait BatteryDevice {
 
     /// Query the current power consumption, in uW.
     fn power_consumption(&self) -> Result<u64>;
+
+    /// Query the battery health, i.e. the full capacity as a percent of the
+    /// design capacity.
+    fn health(&self) -> Result<u64>;
+
+    /// Query the number of charge cycles the battery went through.
+    fn cycle_count(&self) -> Result<u64>;
 }
 
 /// Represents a physical power supply device, as known to sysfs.
@@ -272,6 +279,35 @@ impl BatteryDevice for PowerSupplyDevice {
             ))
         }
     }
+
+    fn health(&self) -> Result<u64> {
+        // Batteries report either energy in µWh or charge in µAh
+        for unit in &["energy", "charge"] {
+            let full_path = self.device_path.join(format!("{}_full", unit));
+            let design_path = self.device_path.join(format!("{}_full_design", unit));
+            if full_path.exists() && design_path.exists() {
+                let full = read_file("battery", &full_path)?
+                    .parse::<u64>()
+                    .block_error("battery", &format!("failed to parse {}_full", unit))?;
+                let design = read_file("battery", &design_path)?
+                    .parse::<u64>()
+                    .block_error("battery", &format!("failed to parse {}_full_design", unit))?;
+                if design > 0 {
+                    return Ok(((full as f64 / design as f64) * 100.0).round() as u64);
+                }
+            }
+        }
+        Err(BlockError(
+            "battery".to_string(),
+            "Device does not report its design capacity".to_string(),
+        ))
+    }
+
+    fn cycle_count(&self) -> Result<u64> {
+        read_file("battery", &self.device_path.join("cycle_count"))?
+            .parse::<u64>()
+            .block_error("battery", "failed to parse cycle_count")
+    }
 }
 
 /// Represents a battery known to UPower.
@@ -432,6 +468,43 @@ impl BatteryDevice for UpowerDevice {
         // FIXME: Might want to make the interface send Watts instead.
         Ok((energy_rate * 1_000_000.0) as u64)
     }
+
+    fn health(&self) -> Result<u64> {
+        let capacity: f64 = self
+            .con
+            .with_path("org.freedesktop.UPower", &self.device_path, 1000)
+            .get("org.freedesktop.UPower.Device", "Capacity")
+            .block_error("battery", "Failed to read UPower Capacity property.")?;
+        Ok(capacity.round() as u64)
+    }
+
+    fn cycle_count(&self) -> Result<u64> {
+        // Only available since UPower 0.99.14, older versions lack the property
+        let cycles: i32 = self
+            .con
+            .with_path("org.freedesktop.UPower", &self.device_path, 1000)
+            .get("org.freedesktop.UPower.Device", "ChargeCycles")
+            .block_error("battery", "Failed to read UPower ChargeCycles property.")?;
+        match cycles {
+            // -1 means unknown
+            -1 => Err(BlockError(
+                "battery".to_string(),
+                "UPower does not know the charge cycles".to_string(),
+            )),
+            _ => Ok(cycles as u64),
+        }
+    }
+}
+
+/// How far back the charge rate is estimated from.
+const RATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// The rate at which the capacity changes in percent per hour, fitted to samples of the
+/// time and capacity. Negative while discharging.
+fn charge_rate(history: &History) -> Option<f64> {
+    let rate = history.rate()? * 3600.0;
+    // Avoid showing -0.0
+    Some(if rate.abs() < 0.05 { 0.0 } else { rate })
 }
 
 /// A block for displaying information about an internal power supply.
@@ -447,6 +520,9 @@ pub struct Battery {
     info: u64,
     warning: u64,
     critical: u64,
+    /// The capacity since the status last changed, for estimating the charge rate
+    history: History,
+    last_status: String,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -483,12 +559,12 @@ pub struct BatteryConfig {
     pub show: Option<String>,
 
     /// Format string for displaying battery information.
-    /// placeholders: {percentage}, {bar}, {time} and {power}
+    /// placeholders: {percentage}, {bar}, {time}, {power}, {rate}, {health} and {cycles}
     #[serde(default = "BatteryConfig::default_format")]
     pub format: String,
 
     /// Format string for displaying battery information when battery is full.
-    /// placeholders: {percentage}, {bar}, {time} and {power}
+    /// placeholders: {percentage}, {bar}, {time}, {power}, {rate}, {health} and {cycles}
     #[serde(default = "BatteryConfig::default_full_format")]
     pub full_format: String,
 
@@ -595,15 +671,18 @@ impl ConfigBlock for Battery {
         Ok(Battery {
             id,
             update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&format)?.with_placeholder(&config.placeholder),
+            full_format: FormatTemplate::from_string(&block_config.full_format)?
+                .with_placeholder(&config.placeholder),
             output: TextWidget::new(config),
             device,
-            format: FormatTemplate::from_string(&format)?,
-            full_format: FormatTemplate::from_string(&block_config.full_format)?,
             driver,
             good: block_config.good,
             info: block_config.info,
             warning: block_config.warning,
             critical: block_config.critical,
+            history: History::within(RATE_WINDOW),
+            last_status: String::new(),
         })
     }
 }
@@ -614,39 +693,65 @@ impl Block for Battery {
 
         let status = self.device.status()?;
         let capacity = self.device.capacity();
-        let percentage = match capacity {
-            Ok(capacity) => format!("{}", capacity),
-            Err(_) => "×".into(),
-        };
-        let bar = match capacity {
-            Ok(capacity) => format_percent_bar(capacity as f32),
-            Err(_) => "×".into(),
-        };
-        let time = match self.device.time_remaining() {
-            Ok(time) => match time {
-                0 => "".into(),
-                _ => format!("{}:{:02}", time / 60, time % 60),
-            },
-            Err(_) => "×".into(),
-        };
+        let percentage = capacity
+            .as_ref()
+            .ok()
+            .map(|capacity| format!("{}", capacity));
+        let bar = capacity
+            .as_ref()
+            .ok()
+            .map(|capacity| format_percent_bar(*capacity as f32));
+        let time = self.device.time_remaining().ok().map(|time| match time {
+            0 => "".into(),
+            _ => format!("{}:{:02}", time / 60, time % 60),
+        });
         // convert µW to W for display
-        let power = match self.device.power_consumption() {
-            Ok(power) => format!("{:.2}", power as f64 / 1000.0 / 1000.0),
-            Err(_) => "×".into(),
+        let power = self
+            .device
+            .power_consumption()
+            .ok()
+            .map(|power| format!("{:.2}", power as f64 / 1000.0 / 1000.0));
+        // Not every firmware exposes these, so they are optional
+        let health = self
+            .device
+            .health()
+            .ok()
+            .map(|health| format!("{}", health));
+        let cycles = self
+            .device
+            .cycle_count()
+            .ok()
+            .map(|cycles| format!("{}", cycles));
+
+        // Samples from before the status changed would distort the rate
+        if status != self.last_status {
+            self.history.clear();
+            self.last_status = status.clone();
+        }
+        if let Ok(capacity) = capacity {
+            self.history.push(Instant::now(), capacity);
+        }
+        let rate = if status == "Full" || status == "Not charging" {
+            Some(0.0)
+        } else {
+            charge_rate(&self.history)
         };
+        let rate = rate.map(|rate| format!("{:+.1}", rate));
         let values = map!("{percentage}" => percentage,
                             "{bar}" => bar,
                             "{time}" => time,
-                            "{power}" => power);
+                            "{power}" => power,
+                            "{rate}" => rate,
+                            "{health}" => health,
+                            "{cycles}" => cycles);
 
         if status == "Full" || status == "Not charging" {
             self.output.set_icon("bat_full");
             self.output
-                .set_text(self.full_format.render_static_str(&values)?);
+                .set_text(self.full_format.render_optional(&values)?);
             self.output.set_state(State::Good);
         } else {
-            self.output
-                .set_text(self.format.render_static_str(&values)?);
+            self.output.set_text(self.format.render_optional(&values)?);
 
             // Check if the battery is in charging mode and change the state to Good.
             // Otherwise, adjust the state depeding the power percentance.
@@ -695,3 +800,27 @@ impl Block for Battery {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charge_rate() {
+        let start = Instant::now();
+        // Discharging 1% every 6 minutes
+        let mut history = History::new();
+        for i in 0..4 {
+            history.push(start + Duration::from_secs(i * 360), 80 - i);
+        }
+        assert!((charge_rate(&history).unwrap() + 10.0).abs() < 0.001);
+
+        let mut idle = History::new();
+        idle.push(start, 100);
+        assert_eq!(charge_rate(&idle), None);
+        for i in 1..4 {
+            idle.push(start + Duration::from_secs(i * 10), 100);
+        }
+        assert_eq!(charge_rate(&idle), Some(0.0));
+    }
+}
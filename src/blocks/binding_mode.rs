@@ -0,0 +1,98 @@
+use std::sync::{Arc, Mutex};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::reply::Event;
+use swayipc::EventType;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::ipc;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct BindingMode {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    /// The name of the active binding mode
+    mode: Arc<Mutex<String>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BindingModeConfig {
+    /// Format override
+    #[serde(default = "BindingModeConfig::default_format")]
+    pub format: String,
+}
+
+impl BindingModeConfig {
+    fn default_format() -> String {
+        "{mode}".to_owned()
+    }
+}
+
+impl ConfigBlock for BindingMode {
+    type Config = BindingModeConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // The current mode can't be queried, but on startup it is the default one
+        let mode = Arc::new(Mutex::new("default".to_owned()));
+        let mode_original = mode.clone();
+
+        ipc::subscribe(
+            "binding_mode",
+            id.clone(),
+            &[EventType::Mode],
+            tx,
+            move |event| match event {
+                Event::Mode(e) => {
+                    *mode_original.lock().unwrap() = e.change;
+                    true
+                }
+                _ => unreachable!(),
+            },
+        )?;
+
+        Ok(BindingMode {
+            id,
+            text: TextWidget::new(config).with_state(State::Warning),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("binding_mode", "Invalid format specified")?,
+            mode,
+        })
+    }
+}
+
+impl Block for BindingMode {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mode = self
+            .mode
+            .lock()
+            .block_error("binding_mode", "failed to acquire lock")?
+            .clone();
+        let values = map!("{mode}" => mode);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if *self.mode.lock().unwrap() == "default" {
+            vec![]
+        } else {
+            vec![&self.text]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
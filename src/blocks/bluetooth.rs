@@ -227,6 +227,10 @@ impl Block for Bluetooth {
         Ok(())
     }
 
+    fn update_on_click(&self) -> bool {
+        true
+    }
+
     fn view(&self) -> Vec<&dyn I3BarWidget> {
         vec![&self.output]
     }
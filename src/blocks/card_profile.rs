@@ -0,0 +1,247 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+#[derive(Debug, Default, PartialEq)]
+struct Profile {
+    name: String,
+    description: String,
+    available: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Card {
+    name: String,
+    profiles: Vec<Profile>,
+    active_profile: String,
+}
+
+impl Card {
+    /// The profile after the active one, skipping profiles that are unavailable or turn
+    /// the card off.
+    fn next_profile(&self) -> Option<&Profile> {
+        let active = self
+            .profiles
+            .iter()
+            .position(|p| p.name == self.active_profile)
+            .unwrap_or(0);
+        self.profiles
+            .iter()
+            .cycle()
+            .skip(active + 1)
+            .take(self.profiles.len())
+            .find(|p| p.available && p.name != "off")
+    }
+}
+
+pub struct CardProfile {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    card: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CardProfileConfig {
+    /// Name of the card, defaults to the first card
+    #[serde(default = "CardProfileConfig::default_card")]
+    pub card: Option<String>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "CardProfileConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "CardProfileConfig::default_format")]
+    pub format: String,
+}
+
+impl CardProfileConfig {
+    fn default_card() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{profile}".to_owned()
+    }
+}
+
+/// Parses the output of `pactl list cards`.
+fn parse_cards(output: &str) -> Vec<Card> {
+    let mut cards: Vec<Card> = Vec::new();
+    let mut in_profiles = false;
+
+    for line in output.lines() {
+        let depth = line.chars().take_while(|c| *c == '\t').count();
+        let line = line.trim();
+        if line.starts_with("Card #") {
+            cards.push(Card::default());
+            in_profiles = false;
+            continue;
+        }
+        let card = match cards.last_mut() {
+            Some(card) => card,
+            None => continue,
+        };
+
+        match depth {
+            1 => {
+                in_profiles = line == "Profiles:";
+                if let Some(name) = line.strip_prefix("Name: ") {
+                    card.name = name.to_owned();
+                } else if let Some(profile) = line.strip_prefix("Active Profile: ") {
+                    card.active_profile = profile.to_owned();
+                }
+            }
+            // e.g. "output:analog-stereo: Analog Stereo Output (sinks: 1, ..., available: yes)"
+            2 if in_profiles => {
+                if let Some(separator) = line.find(": ") {
+                    let description = &line[separator + 2..];
+                    let (description, details) = match description.rfind(" (") {
+                        Some(i) => (&description[..i], &description[i..]),
+                        None => (description, ""),
+                    };
+                    card.profiles.push(Profile {
+                        name: line[..separator].to_owned(),
+                        description: description.to_owned(),
+                        available: !details.contains("available: no"),
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    cards
+}
+
+impl CardProfile {
+    fn get_card(&self) -> Result<Card> {
+        let output = Command::new("pactl")
+            .args(["list", "cards"])
+            .env("LC_ALL", "C")
+            .output()
+            .block_error("card_profile", "failed to run pactl")?;
+        let cards = parse_cards(&String::from_utf8_lossy(&output.stdout));
+        let card = match self.card {
+            Some(ref name) => cards.into_iter().find(|card| &card.name == name),
+            None => cards.into_iter().next(),
+        };
+        card.block_error("card_profile", "sound card not found")
+    }
+}
+
+impl ConfigBlock for CardProfile {
+    type Config = CardProfileConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(CardProfile {
+            output: ButtonWidget::new(config, &id).with_icon("card_profile"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("card_profile", "Invalid format specified")?,
+            card: block_config.card,
+        })
+    }
+}
+
+impl Block for CardProfile {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let card = self.get_card()?;
+        let profile = card
+            .profiles
+            .iter()
+            .find(|p| p.name == card.active_profile)
+            .map(|p| p.description.clone())
+            .unwrap_or_else(|| card.active_profile.clone());
+
+        let values = map!("{profile}" => profile, "{name}" => card.active_profile.clone());
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            let card = self.get_card()?;
+            if let Some(profile) = card.next_profile() {
+                Command::new("pactl")
+                    .args(["set-card-profile", &card.name, &profile.name])
+                    .output()
+                    .block_error("card_profile", "failed to set card profile")?;
+            }
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cards() {
+        let output = "\
+Card #42
+\tName: alsa_card.usb-DAC
+\tDriver: module-alsa-card.c
+\tProperties:
+\t\tdevice.description = \"USB DAC\"
+\tProfiles:
+\t\tinput:mono-fallback: Mono Input (sinks: 0, sources: 1, priority: 1, available: no)
+\t\toutput:analog-stereo: Analog Stereo Output (sinks: 1, sources: 0, priority: 6500, available: yes)
+\t\toutput:iec958-stereo: Digital Stereo (IEC958) Output (sinks: 1, sources: 0, priority: 5500, available: yes)
+\t\toff: Off (sinks: 0, sources: 0, priority: 0, available: yes)
+\tActive Profile: output:iec958-stereo
+\tPorts:
+\t\tanalog-output: Analog Output (type: Line, priority: 9900, available)
+";
+        let cards = parse_cards(output);
+        assert_eq!(cards.len(), 1);
+        let card = &cards[0];
+        assert_eq!(card.name, "alsa_card.usb-DAC");
+        assert_eq!(card.active_profile, "output:iec958-stereo");
+        assert_eq!(card.profiles.len(), 4);
+        assert_eq!(
+            card.profiles[2].description,
+            "Digital Stereo (IEC958) Output"
+        );
+        assert!(!card.profiles[0].available);
+        // Wraps around, skipping "off" and the unavailable input profile
+        assert_eq!(card.next_profile().unwrap().name, "output:analog-stereo");
+    }
+}
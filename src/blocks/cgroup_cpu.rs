@@ -0,0 +1,139 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct CgroupCpu {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    cpu_stat: PathBuf,
+    /// The CPU time used by the cgroup in microseconds, at the time it was read
+    last_usage: Option<(u64, Instant)>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CgroupCpuConfig {
+    /// The path of the cgroup below /sys/fs/cgroup, e.g. `system.slice/nginx.service`
+    pub cgroup: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "CgroupCpuConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "CgroupCpuConfig::default_format")]
+    pub format: String,
+}
+
+impl CgroupCpuConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{usage}".to_owned()
+    }
+}
+
+/// Extracts the used CPU time in microseconds from `cpu.stat`, which holds lines like
+/// `usage_usec 123456`.
+fn parse_usage_usec(cpu_stat: &str) -> Option<u64> {
+    cpu_stat
+        .lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn read_usage(path: &Path) -> Result<u64> {
+    let cpu_stat = read_to_string(path)
+        .block_error("cgroup_cpu", &format!("failed to read {}", path.display()))?;
+    parse_usage_usec(&cpu_stat)
+        .block_error("cgroup_cpu", &format!("failed to parse {}", path.display()))
+}
+
+impl ConfigBlock for CgroupCpu {
+    type Config = CgroupCpuConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        // Only the unified hierarchy of cgroups v2 has cpu.stat for every cgroup
+        let cpu_stat = Path::new("/sys/fs/cgroup")
+            .join(block_config.cgroup.trim_start_matches('/'))
+            .join("cpu.stat");
+        read_usage(&cpu_stat)?;
+
+        Ok(CgroupCpu {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("cpu"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("cgroup_cpu", "Invalid format specified")?,
+            cpu_stat,
+            last_usage: None,
+        })
+    }
+}
+
+impl Block for CgroupCpu {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let usage = read_usage(&self.cpu_stat)?;
+        let now = Instant::now();
+
+        // The usage is only known from the second reading on. It is relative to a single
+        // CPU, so a cgroup keeping two CPUs busy uses 200%.
+        let percent = match self.last_usage {
+            Some((last, time)) => {
+                let used = usage.saturating_sub(last) as f64 / 1_000_000.0;
+                let seconds = now.duration_since(time).as_secs_f64().max(0.001);
+                format!("{:.0}%", 100.0 * used / seconds)
+            }
+            None => "-".to_owned(),
+        };
+        self.last_usage = Some((usage, now));
+
+        let values = map!("{usage}" => percent);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_usage_usec() {
+        let cpu_stat = "usage_usec 8520310\nuser_usec 6012000\nsystem_usec 2508310\n\
+                        nr_periods 0\nnr_throttled 0\nthrottled_usec 0\n";
+        assert_eq!(parse_usage_usec(cpu_stat), Some(8_520_310));
+        assert_eq!(parse_usage_usec("nr_periods 0\n"), None);
+    }
+}
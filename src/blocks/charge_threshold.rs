@@ -0,0 +1,151 @@
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::{cycle_next, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct ChargeThreshold {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    path: PathBuf,
+    presets: Vec<u64>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ChargeThresholdConfig {
+    /// The battery device, as named in /sys/class/power_supply
+    #[serde(default = "ChargeThresholdConfig::default_device")]
+    pub device: String,
+
+    /// The thresholds to cycle through on click, in percent
+    #[serde(default = "ChargeThresholdConfig::default_presets")]
+    pub presets: Vec<u64>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "ChargeThresholdConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ChargeThresholdConfig::default_format")]
+    pub format: String,
+}
+
+impl ChargeThresholdConfig {
+    fn default_device() -> String {
+        "BAT0".to_owned()
+    }
+
+    fn default_presets() -> Vec<u64> {
+        vec![60, 80, 100]
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn default_format() -> String {
+        "{threshold}%".to_owned()
+    }
+}
+
+fn read_threshold(path: &Path) -> Result<u64> {
+    read_to_string(path)
+        .block_error(
+            "charge_threshold",
+            &format!("failed to read {}", path.display()),
+        )?
+        .trim()
+        .parse()
+        .block_error("charge_threshold", "failed to parse charge threshold")
+}
+
+impl ConfigBlock for ChargeThreshold {
+    type Config = ChargeThresholdConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        if block_config.presets.is_empty() {
+            return Err(BlockError(
+                "charge_threshold".to_owned(),
+                "presets must not be empty".to_owned(),
+            ));
+        }
+
+        // Older ThinkPad kernels use a different name for the same file
+        let device = Path::new("/sys/class/power_supply").join(&block_config.device);
+        let path = ["charge_control_end_threshold", "charge_stop_threshold"]
+            .iter()
+            .map(|file| device.join(file))
+            .find(|path| path.exists())
+            .block_error(
+                "charge_threshold",
+                &format!("{} has no charge threshold", block_config.device),
+            )?;
+
+        Ok(ChargeThreshold {
+            output: ButtonWidget::new(config, &id).with_icon("charge_threshold"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("charge_threshold", "Invalid format specified")?,
+            path,
+            presets: block_config.presets,
+        })
+    }
+}
+
+impl Block for ChargeThreshold {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let threshold = read_threshold(&self.path)?;
+        let values = map!("{threshold}" => threshold.to_string());
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        self.output.set_state(State::Idle);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            let current = read_threshold(&self.path)?;
+            if let Some(&next) = cycle_next(&self.presets, Some(&current)) {
+                // Writing usually needs root, e.g. a udev rule that makes the file writable.
+                // The failure is shown until the next update instead of stopping the bar.
+                if let Err(error) = write(&self.path, next.to_string()) {
+                    self.output
+                        .set_text(format!("failed to set {}%: {}", next, error));
+                    self.output.set_state(State::Critical);
+                    return Ok(());
+                }
+            }
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
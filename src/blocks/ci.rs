@@ -0,0 +1,321 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use serde_json::value::Value;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::{Backoff, Task};
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CiProvider {
+    /// GitHub Actions
+    Github,
+    /// GitLab CI/CD pipelines
+    Gitlab,
+}
+
+/// The status of the latest CI run on a branch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CiStatus {
+    Passing,
+    Failing,
+    Running,
+    /// Nothing ran on the branch yet
+    Unknown,
+}
+
+impl CiProvider {
+    fn default_api_server(self) -> &'static str {
+        match self {
+            CiProvider::Github => "https://api.github.com",
+            CiProvider::Gitlab => "https://gitlab.com",
+        }
+    }
+
+    /// The environment variable the token is read from if it is not configured.
+    fn token_env(self) -> &'static str {
+        match self {
+            CiProvider::Github => "I3RS_GITHUB_TOKEN",
+            CiProvider::Gitlab => "I3RS_GITLAB_TOKEN",
+        }
+    }
+
+    fn url(self, api_server: &str, repo: &str, branch: &str) -> String {
+        let api_server = api_server.trim_end_matches('/');
+        match self {
+            // The runs of all workflows, so that those of the latest commit can be combined
+            CiProvider::Github => format!(
+                "{}/repos/{}/actions/runs?branch={}&per_page=20",
+                api_server, repo, branch
+            ),
+            CiProvider::Gitlab => format!(
+                "{}/api/v4/projects/{}/pipelines?ref={}&per_page=1",
+                api_server,
+                repo.replace('/', "%2F"),
+                branch
+            ),
+        }
+    }
+
+    fn auth_header(self, token: &str) -> String {
+        match self {
+            CiProvider::Github => format!("Authorization: Bearer {}", token),
+            CiProvider::Gitlab => format!("PRIVATE-TOKEN: {}", token),
+        }
+    }
+
+    /// Extracts the status of the latest commit from a response.
+    fn parse(self, json: &Value) -> Result<CiStatus> {
+        match self {
+            CiProvider::Github => {
+                let runs = json
+                    .get("workflow_runs")
+                    .and_then(Value::as_array)
+                    .block_error("ci", "workflow runs not found in the response")?;
+                let head_sha = match runs.first() {
+                    Some(run) => run.get("head_sha"),
+                    None => return Ok(CiStatus::Unknown),
+                };
+                // Every workflow of the latest commit has to pass
+                let mut status = CiStatus::Passing;
+                for run in runs.iter().filter(|run| run.get("head_sha") == head_sha) {
+                    if run.get("status").and_then(Value::as_str) != Some("completed") {
+                        status = CiStatus::Running;
+                        continue;
+                    }
+                    match run.get("conclusion").and_then(Value::as_str) {
+                        Some("success") | Some("skipped") | Some("neutral") => (),
+                        _ => return Ok(CiStatus::Failing),
+                    }
+                }
+                Ok(status)
+            }
+            CiProvider::Gitlab => {
+                let pipelines = json
+                    .as_array()
+                    .block_error("ci", "the pipelines are not a list")?;
+                let status = match pipelines.first() {
+                    Some(pipeline) => pipeline.get("status").and_then(Value::as_str),
+                    None => return Ok(CiStatus::Unknown),
+                };
+                Ok(match status {
+                    Some("success") | Some("skipped") => CiStatus::Passing,
+                    Some("created")
+                    | Some("waiting_for_resource")
+                    | Some("preparing")
+                    | Some("pending")
+                    | Some("running")
+                    | Some("scheduled") => CiStatus::Running,
+                    _ => CiStatus::Failing,
+                })
+            }
+        }
+    }
+}
+
+pub struct Ci {
+    id: String,
+    text: ButtonWidget,
+    format: FormatTemplate,
+    provider: CiProvider,
+    url: String,
+    token: Option<String>,
+    backoff: Backoff,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CiConfig {
+    pub provider: CiProvider,
+
+    /// The repository, like `owner/name` on GitHub or `group/project` on GitLab
+    pub repo: String,
+
+    /// The branch to show the status of
+    #[serde(default = "CiConfig::default_branch")]
+    pub branch: String,
+
+    /// Token for the API, needed for private repositories
+    #[serde(default = "CiConfig::default_token")]
+    pub token: Option<String>,
+
+    /// The API server, for self-hosted instances
+    #[serde(default = "CiConfig::default_api_server")]
+    pub api_server: Option<String>,
+
+    /// Time between two requests in seconds
+    #[serde(
+        default = "CiConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "CiConfig::default_format")]
+    pub format: String,
+}
+
+impl CiConfig {
+    fn default_branch() -> String {
+        "main".to_owned()
+    }
+
+    fn default_token() -> Option<String> {
+        None
+    }
+
+    fn default_api_server() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(120)
+    }
+
+    fn default_format() -> String {
+        "{status}".to_owned()
+    }
+}
+
+impl Ci {
+    /// Requests the status, or returns `None` if the API cannot be reached or the
+    /// token is not accepted.
+    fn request(&self) -> Result<Option<CiStatus>> {
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--fail", "--max-time", "5", &self.url]);
+        if let Some(ref token) = self.token {
+            command.args(["--header", &self.provider.auth_header(token)]);
+        }
+        let output = command
+            .output()
+            .block_error("ci", "failed to execute curl")?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let json: Value = serde_json::from_slice(&output.stdout)
+            .block_error("ci", "failed to parse JSON response")?;
+        self.provider.parse(&json).map(Some)
+    }
+}
+
+impl ConfigBlock for Ci {
+    type Config = CiConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let provider = block_config.provider;
+        let api_server = block_config
+            .api_server
+            .unwrap_or_else(|| provider.default_api_server().to_owned());
+        Ok(Ci {
+            text: ButtonWidget::new(config, &id).with_icon("ci"),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("ci", "Invalid format specified")?,
+            provider,
+            url: provider.url(&api_server, &block_config.repo, &block_config.branch),
+            token: block_config
+                .token
+                .or_else(|| std::env::var(provider.token_env()).ok()),
+            backoff: Backoff::new(block_config.interval),
+        })
+    }
+}
+
+impl Block for Ci {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let status = match self.request()? {
+            Some(status) => status,
+            None => {
+                // Not knowing is never shown as passing
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+                return Ok(Some(self.backoff.failure().into()));
+            }
+        };
+
+        let (name, state) = match status {
+            CiStatus::Passing => ("passing", State::Good),
+            CiStatus::Failing => ("failing", State::Critical),
+            CiStatus::Running => ("running", State::Info),
+            CiStatus::Unknown => ("none", State::Idle),
+        };
+        let values = map!("{status}" => name.to_owned());
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(state);
+
+        Ok(Some(self.backoff.success().into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github() {
+        let json: Value = serde_json::from_str(
+            r#"{"workflow_runs": [
+                {"head_sha": "b", "status": "completed", "conclusion": "success"},
+                {"head_sha": "b", "status": "in_progress", "conclusion": null},
+                {"head_sha": "a", "status": "completed", "conclusion": "failure"}
+            ]}"#,
+        )
+        .unwrap();
+        // The failure of an older commit does not count
+        assert_eq!(CiProvider::Github.parse(&json).unwrap(), CiStatus::Running);
+
+        let json: Value = serde_json::from_str(
+            r#"{"workflow_runs": [
+                {"head_sha": "b", "status": "in_progress", "conclusion": null},
+                {"head_sha": "b", "status": "completed", "conclusion": "timed_out"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(CiProvider::Github.parse(&json).unwrap(), CiStatus::Failing);
+
+        let json: Value = serde_json::from_str(r#"{"workflow_runs": []}"#).unwrap();
+        assert_eq!(CiProvider::Github.parse(&json).unwrap(), CiStatus::Unknown);
+        let json: Value = serde_json::from_str(r#"{"message": "Not Found"}"#).unwrap();
+        assert!(CiProvider::Github.parse(&json).is_err());
+    }
+
+    #[test]
+    fn test_parse_gitlab() {
+        let json: Value =
+            serde_json::from_str(r#"[{"id": 2, "status": "success", "ref": "main"}]"#).unwrap();
+        assert_eq!(CiProvider::Gitlab.parse(&json).unwrap(), CiStatus::Passing);
+        let json: Value = serde_json::from_str(r#"[{"id": 3, "status": "canceled"}]"#).unwrap();
+        assert_eq!(CiProvider::Gitlab.parse(&json).unwrap(), CiStatus::Failing);
+        assert_eq!(
+            CiProvider::Gitlab.url("https://gitlab.com/", "group/project", "main"),
+            "https://gitlab.com/api/v4/projects/group%2Fproject/pipelines?ref=main&per_page=1"
+        );
+    }
+}
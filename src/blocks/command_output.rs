@@ -0,0 +1,165 @@
+use std::env;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::{Backoff, Task};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct CommandOutput {
+    output: ButtonWidget,
+    id: String,
+    backoff: Backoff,
+    command: String,
+    state_command: Option<String>,
+    /// The output of the last successful run, which stays visible if the command fails
+    cached: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CommandOutputConfig {
+    /// Shell command whose first line of output is shown, and whose second line, if any,
+    /// is the short text
+    pub command: String,
+
+    /// Shell command whose output, e.g. `warning`, sets the state of the block
+    #[serde(default = "CommandOutputConfig::default_state_command")]
+    pub state_command: Option<String>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "CommandOutputConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Whether the output is interpreted as pango markup
+    #[serde(default = "CommandOutputConfig::default_markup")]
+    pub markup: bool,
+}
+
+impl CommandOutputConfig {
+    fn default_state_command() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_markup() -> bool {
+        false
+    }
+}
+
+/// Runs `command` in the user's shell and returns its trimmed standard output.
+fn run(command: &str) -> Result<String> {
+    let output = Command::new(env::var("SHELL").unwrap_or_else(|_| "sh".to_owned()))
+        .args(["-c", command])
+        .output()
+        .block_error("command_output", "failed to run command")?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "command_output".to_owned(),
+            format!("`{}` failed with {}", command, output.status),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Maps the output of the state command to a state, ignoring case. Anything unknown is idle.
+fn parse_state(output: &str) -> State {
+    // The names of the states are capitalized, e.g. `Critical`
+    let lowercase = output.trim().to_lowercase();
+    let mut chars = lowercase.chars();
+    let name: String = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+    name.parse().unwrap_or_default()
+}
+
+impl CommandOutput {
+    fn refresh(&mut self) -> Result<()> {
+        let output = run(&self.command)?;
+        let mut lines = output.lines();
+        let text = lines.next().unwrap_or("").to_owned();
+        let short_text = lines.next().map(String::from);
+        let state = match self.state_command {
+            Some(ref state_command) => parse_state(&run(state_command)?),
+            None => State::Idle,
+        };
+
+        // Output that did not change does not need to be rendered again
+        if self.cached.as_ref() != Some(&output) {
+            self.output.set_text(text);
+            self.output.set_short_text(short_text);
+            self.cached = Some(output);
+        }
+        self.output.set_state(state);
+        Ok(())
+    }
+}
+
+impl ConfigBlock for CommandOutput {
+    type Config = CommandOutputConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(CommandOutput {
+            output: ButtonWidget::new(config, &id).with_markup(block_config.markup),
+            id,
+            backoff: Backoff::new(block_config.interval),
+            command: block_config.command,
+            state_command: block_config.state_command,
+            cached: None,
+        })
+    }
+}
+
+impl Block for CommandOutput {
+    fn update(&mut self) -> Result<Option<Update>> {
+        match self.refresh() {
+            Ok(()) => Ok(Some(self.backoff.success().into())),
+            Err(BlockError(_, error)) => {
+                if self.cached.is_none() {
+                    self.output.set_text(error);
+                }
+                self.output.set_state(State::Critical);
+                Ok(Some(self.backoff.failure().into()))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state() {
+        assert!(matches!(parse_state("Critical\n"), State::Critical));
+        assert!(matches!(parse_state("warning"), State::Warning));
+        assert!(matches!(parse_state("GOOD"), State::Good));
+        assert!(matches!(parse_state("unknown"), State::Idle));
+        assert!(matches!(parse_state(""), State::Idle));
+    }
+}
@@ -13,8 +13,8 @@ use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::scheduler::Task;
-use crate::util::{format_percent_bar, FormatTemplate};
-use crate::widget::{I3BarWidget, State};
+use crate::util::{color_gradient, format_percent_bar, FormatTemplate};
+use crate::widget::{ColorRange, I3BarWidget, MinWidth, State};
 use crate::widgets::text::TextWidget;
 
 /// Maximum number of CPUs we support.
@@ -24,15 +24,22 @@ pub struct Cpu {
     output: TextWidget,
     prev_idles: [u64; MAX_CPUS],
     prev_non_idles: [u64; MAX_CPUS],
+    /// The total iowait and steal time of all CPUs at the last update
+    prev_iowait: u64,
+    prev_steal: u64,
     id: String,
     update_interval: Duration,
     minimum_info: u64,
     minimum_warning: u64,
     minimum_critical: u64,
+    iowait_warning: Option<u64>,
+    steal_warning: Option<u64>,
     format: FormatTemplate,
     has_barchart: bool,
     has_frequency: bool,
     per_core: bool,
+    color_overrides: Vec<ColorRange>,
+    gradient: Option<(String, String)>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -57,6 +64,14 @@ pub struct CpuConfig {
     #[serde(default = "CpuConfig::default_critical")]
     pub critical: u64,
 
+    /// Minimum share of time waiting for IO, where state is set to at least warning
+    #[serde(default = "CpuConfig::default_iowait_warning")]
+    pub iowait_warning: Option<u64>,
+
+    /// Minimum share of time lost to the hypervisor, where state is set to at least warning
+    #[serde(default = "CpuConfig::default_steal_warning")]
+    pub steal_warning: Option<u64>,
+
     /// Display frequency
     #[serde(default = "CpuConfig::default_frequency")]
     pub frequency: bool,
@@ -68,6 +83,18 @@ pub struct CpuConfig {
     /// Compute the metrics (utilization and frequency) per core.
     #[serde(default)]
     pub per_core: bool,
+
+    /// Minimum width of the block, as a number of characters or a sample text
+    #[serde(default = "CpuConfig::default_min_width")]
+    pub min_width: Option<MinWidth>,
+
+    /// Foreground colors for ranges of the average utilization
+    #[serde(default = "CpuConfig::default_color_overrides")]
+    pub color_overrides: Vec<ColorRange>,
+
+    /// Foreground colors at 0% and 100% utilization, interpolated in between
+    #[serde(default = "CpuConfig::default_gradient")]
+    pub gradient: Option<(String, String)>,
 }
 
 impl CpuConfig {
@@ -91,9 +118,29 @@ impl CpuConfig {
         90
     }
 
+    fn default_iowait_warning() -> Option<u64> {
+        None
+    }
+
+    fn default_steal_warning() -> Option<u64> {
+        None
+    }
+
     fn default_frequency() -> bool {
         false
     }
+
+    fn default_min_width() -> Option<MinWidth> {
+        None
+    }
+
+    fn default_color_overrides() -> Vec<ColorRange> {
+        Vec::new()
+    }
+
+    fn default_gradient() -> Option<(String, String)> {
+        None
+    }
 }
 
 impl ConfigBlock for Cpu {
@@ -112,20 +159,32 @@ impl ConfigBlock for Cpu {
             block_config.format
         };
 
+        if let Some((ref start, ref end)) = block_config.gradient {
+            color_gradient(start, end, 0.0).configuration_error("invalid cpu gradient color")?;
+        }
+
         Ok(Cpu {
             id: Uuid::new_v4().to_simple().to_string(),
             update_interval: block_config.interval,
-            output: TextWidget::new(config).with_icon("cpu"),
+            output: TextWidget::new(config)
+                .with_icon("cpu")
+                .with_min_width(block_config.min_width),
             prev_idles: [0; MAX_CPUS],
             prev_non_idles: [0; MAX_CPUS],
+            prev_iowait: 0,
+            prev_steal: 0,
             minimum_info: block_config.info,
             minimum_warning: block_config.warning,
             minimum_critical: block_config.critical,
+            iowait_warning: block_config.iowait_warning,
+            steal_warning: block_config.steal_warning,
             format: FormatTemplate::from_string(&format)
                 .block_error("cpu", "Invalid format specified for cpu")?,
             has_frequency: format.contains("{frequency}"),
             has_barchart: format.contains("{barchart}"),
             per_core: block_config.per_core,
+            color_overrides: block_config.color_overrides,
+            gradient: block_config.gradient,
         })
     }
 }
@@ -163,6 +222,8 @@ impl Block for Cpu {
 
         let mut cpu_utilizations: [f64; MAX_CPUS] = [0.0; MAX_CPUS];
         let mut cpu_i = 0;
+        let mut iowait = 0.0;
+        let mut steal = 0.0;
         for line in f.lines().scan((), |_, x| x.ok()) {
             if line.starts_with("cpu") {
                 let data: Vec<u64> = (&line)
@@ -197,6 +258,18 @@ impl Block for Cpu {
 
                 cpu_utilizations[cpu_i] = (total_delta - idle_delta) as f64 / total_delta as f64;
 
+                // The breakdown is only shown for all CPUs together
+                if cpu_i == 0 {
+                    let share = |prev: u64, current: u64| match current.checked_sub(prev) {
+                        Some(delta) if prev_total < total => delta as f64 / total_delta as f64,
+                        _ => 0.0,
+                    };
+                    iowait = share(self.prev_iowait, data[4]);
+                    steal = share(self.prev_steal, data[7]);
+                    self.prev_iowait = data[4];
+                    self.prev_steal = data[7];
+                }
+
                 self.prev_idles[cpu_i] = idle;
                 self.prev_non_idles[cpu_i] = non_idle;
                 cpu_i += 1;
@@ -208,12 +281,24 @@ impl Block for Cpu {
 
         let avg_utilization = (100.0 * cpu_utilizations[0]) as u64;
 
+        let above = |share: f64, threshold: Option<u64>| match threshold {
+            Some(threshold) => (100.0 * share) as u64 > threshold,
+            None => false,
+        };
+        let waiting = above(iowait, self.iowait_warning) || above(steal, self.steal_warning);
         self.output.set_state(match avg_utilization {
             x if x > self.minimum_critical => State::Critical,
+            _ if waiting => State::Warning,
             x if x > self.minimum_warning => State::Warning,
             x if x > self.minimum_info => State::Info,
             _ => State::Idle,
         });
+        let color = ColorRange::find(&self.color_overrides, avg_utilization as f64).or_else(|| {
+            self.gradient
+                .as_ref()
+                .and_then(|(start, end)| color_gradient(start, end, avg_utilization as f64).ok())
+        });
+        self.output.set_color(color);
 
         let mut barchart = String::new();
 
@@ -232,7 +317,9 @@ impl Block for Cpu {
         let values = map!("{frequency}" => format_frequency(&cpu_freqs, n_cpu, self.per_core),
                           "{barchart}" => barchart,
                           "{utilization}" => format_utilization(&cpu_utilizations, cpu_i, self.per_core),
-                          "{utilizationbar}" => format_percent_bar(avg_utilization as f32));
+                          "{utilizationbar}" => format_percent_bar(avg_utilization as f32),
+                          "{iowait}" => format!("{:02.0}", 100.0 * iowait),
+                          "{steal}" => format!("{:02.0}", 100.0 * steal));
 
         self.output
             .set_text(self.format.render_static_str(&values)?);
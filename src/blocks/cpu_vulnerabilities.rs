@@ -0,0 +1,173 @@
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+const VULNERABILITIES_PATH: &str = "/sys/devices/system/cpu/vulnerabilities";
+
+pub struct CpuVulnerabilities {
+    text: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CpuVulnerabilitiesConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "CpuVulnerabilitiesConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "CpuVulnerabilitiesConfig::default_format")]
+    pub format: String,
+}
+
+impl CpuVulnerabilitiesConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(3600)
+    }
+
+    fn default_format() -> String {
+        "{status}".to_owned()
+    }
+}
+
+/// The names of the vulnerabilities in `dir` that the kernel reports as unmitigated, sorted.
+/// Partial mitigations, like `Mitigation: ...; SMT vulnerable`, do not count.
+fn read_vulnerable(dir: &Path) -> Result<Vec<String>> {
+    let mut vulnerable = Vec::new();
+    for entry in read_dir(dir).block_error(
+        "cpu_vulnerabilities",
+        "failed to read the CPU vulnerabilities",
+    )? {
+        let entry = entry.block_error(
+            "cpu_vulnerabilities",
+            "failed to read the CPU vulnerabilities",
+        )?;
+        let status = read_to_string(entry.path()).block_error(
+            "cpu_vulnerabilities",
+            &format!("failed to read {}", entry.path().display()),
+        )?;
+        if status.contains("Vulnerable") {
+            vulnerable.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    vulnerable.sort();
+    Ok(vulnerable)
+}
+
+/// The microcode revision of the first CPU in `/proc/cpuinfo`, if the CPU reports one.
+fn parse_microcode(cpuinfo: &str) -> Option<String> {
+    let line = cpuinfo.lines().find(|line| line.starts_with("microcode"))?;
+    Some(line.split(':').nth(1)?.trim().to_owned())
+}
+
+impl ConfigBlock for CpuVulnerabilities {
+    type Config = CpuVulnerabilitiesConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(CpuVulnerabilities {
+            text: ButtonWidget::new(config, &id).with_icon("cpu_vulnerabilities"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("cpu_vulnerabilities", "Invalid format specified")?,
+        })
+    }
+}
+
+impl Block for CpuVulnerabilities {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let vulnerable = read_vulnerable(Path::new(VULNERABILITIES_PATH))?;
+        let microcode = read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|cpuinfo| parse_microcode(&cpuinfo))
+            .unwrap_or_default();
+
+        let status = match vulnerable.len() {
+            0 => "mitigated".to_owned(),
+            count => format!("{} vulnerable", count),
+        };
+        self.text.set_state(if vulnerable.is_empty() {
+            State::Idle
+        } else {
+            State::Warning
+        });
+        // The short text tells which ones are vulnerable
+        self.text.set_short_text(if vulnerable.is_empty() {
+            None
+        } else {
+            Some(vulnerable.join(" "))
+        });
+        let values = map!(
+            "{status}" => status,
+            "{vulnerable}" => vulnerable.len().to_string(),
+            "{microcode}" => microcode
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_read_vulnerable() {
+        let dir = TempDir::new().unwrap();
+        dir.child("meltdown").write_str("Not affected\n").unwrap();
+        dir.child("spectre_v2")
+            .write_str("Mitigation: Retpolines; STIBP: disabled\n")
+            .unwrap();
+        dir.child("mds")
+            .write_str("Vulnerable: Clear CPU buffers attempted, no microcode\n")
+            .unwrap();
+        dir.child("l1tf")
+            .write_str("Mitigation: PTE Inversion; VMX: SMT vulnerable\n")
+            .unwrap();
+        dir.child("itlb_multihit")
+            .write_str("KVM: Vulnerable\n")
+            .unwrap();
+        assert_eq!(
+            read_vulnerable(dir.path()).unwrap(),
+            vec!["itlb_multihit".to_owned(), "mds".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_parse_microcode() {
+        let cpuinfo = "processor\t: 0\nmodel name\t: Some CPU\nmicrocode\t: 0xf0\n";
+        assert_eq!(parse_microcode(cpuinfo), Some("0xf0".to_owned()));
+        assert_eq!(parse_microcode("processor\t: 0\n"), None);
+    }
+}
@@ -13,7 +13,7 @@ use crate::config::Config;
 use crate::de::deserialize_update;
 use crate::errors::*;
 use crate::input::I3BarEvent;
-use crate::scheduler::Task;
+use crate::scheduler::{Backoff, Task};
 use crate::subprocess::spawn_child_async;
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
@@ -21,6 +21,7 @@ use crate::widgets::button::ButtonWidget;
 pub struct Custom {
     id: String,
     update_interval: Update,
+    backoff: Option<Backoff>,
     output: ButtonWidget,
     command: Option<String>,
     on_click: Option<String>,
@@ -67,9 +68,14 @@ impl ConfigBlock for Custom {
     type Config = CustomConfig;
 
     fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let backoff = match block_config.interval {
+            Update::Every(interval) => Some(Backoff::new(interval)),
+            Update::Once => None,
+        };
         let mut custom = Custom {
             id: Uuid::new_v4().to_simple().to_string(),
             update_interval: block_config.interval,
+            backoff,
             output: ButtonWidget::new(config.clone(), ""),
             command: None,
             on_click: None,
@@ -113,6 +119,16 @@ struct Output {
     text: String,
 }
 
+impl Custom {
+    /// The delay until the next update after the command failed.
+    fn failure(&mut self) -> Update {
+        match self.backoff {
+            Some(ref mut backoff) => backoff.failure().into(),
+            None => self.update_interval.clone(),
+        }
+    }
+}
+
 impl Block for Custom {
     fn update(&mut self) -> Result<Option<Update>> {
         let command_str = self
@@ -122,19 +138,36 @@ impl Block for Custom {
             .or_else(|| self.command.clone())
             .unwrap_or_else(|| "".to_owned());
 
-        let raw_output = Command::new(env::var("SHELL").unwrap_or_else(|_| "sh".to_owned()))
+        let raw_output = match Command::new(env::var("SHELL").unwrap_or_else(|_| "sh".to_owned()))
             .args(&["-c", &command_str])
             .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
-            .unwrap_or_else(|e| e.to_string());
+        {
+            Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).trim().to_owned(),
+            Ok(o) => {
+                // Show what the command printed about its failure, if anything
+                let stdout = String::from_utf8_lossy(&o.stdout).trim().to_owned();
+                let text = if stdout.is_empty() {
+                    String::from_utf8_lossy(&o.stderr).trim().to_owned()
+                } else {
+                    stdout
+                };
+                self.output.set_text(text);
+                self.output.set_state(State::Critical);
+                return Ok(Some(self.failure()));
+            }
+            Err(e) => {
+                self.output.set_text(e.to_string());
+                self.output.set_state(State::Critical);
+                return Ok(Some(self.failure()));
+            }
+        };
 
         if self.json {
             let output: Output = match serde_json::from_str(&*raw_output) {
                 Err(e) => {
-                    return Err(BlockError(
-                        "custom".to_string(),
-                        format!("Error parsing JSON: {}", e),
-                    ));
+                    self.output.set_text(format!("Error parsing JSON: {}", e));
+                    self.output.set_state(State::Critical);
+                    return Ok(Some(self.failure()));
                 }
                 Ok(s) => s,
             };
@@ -142,9 +175,13 @@ impl Block for Custom {
             self.output.set_state(output.state);
             self.output.set_text(output.text);
         } else {
+            self.output.set_state(State::Idle);
             self.output.set_text(raw_output);
         }
 
+        if let Some(ref mut backoff) = self.backoff {
+            backoff.success();
+        }
         Ok(Some(self.update_interval.clone()))
     }
 
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use dbus::arg::{ArgType, RefArg, Variant};
+use dbus::blocking::stdintf::org_freedesktop_dbus::{Properties, PropertiesPropertiesChanged};
+use dbus::blocking::Connection;
+use dbus::Message;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DbusBusType {
+    #[default]
+    Session,
+    System,
+}
+
+pub struct DbusProperty {
+    id: String,
+    text: TextWidget,
+    format: FormatTemplate,
+    mappings: Option<BTreeMap<String, String>>,
+    /// The rendered value of the property, or `None` while it is unavailable
+    value: Arc<Mutex<Option<String>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DbusPropertyConfig {
+    /// The bus the service is on, "session" or "system"
+    #[serde(default)]
+    pub bus_type: DbusBusType,
+
+    /// The bus name of the service, e.g. `org.freedesktop.UPower`
+    pub service: String,
+
+    /// The object path, e.g. `/org/freedesktop/UPower/devices/DisplayDevice`
+    pub path: String,
+
+    /// The interface the property belongs to
+    pub interface: String,
+
+    /// The name of the property
+    pub property: String,
+
+    /// Replacements for values, e.g. to name the numbers of an enumeration
+    #[serde(default = "DbusPropertyConfig::default_mappings")]
+    pub mappings: Option<BTreeMap<String, String>>,
+
+    /// Format override
+    #[serde(default = "DbusPropertyConfig::default_format")]
+    pub format: String,
+}
+
+impl DbusPropertyConfig {
+    fn default_mappings() -> Option<BTreeMap<String, String>> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{value}".to_owned()
+    }
+}
+
+/// Renders a value of any type. Containers, like arrays and structs, are rendered as
+/// their elements separated by spaces.
+fn format_value(value: &dyn RefArg) -> String {
+    match value.arg_type() {
+        ArgType::Boolean => (value.as_i64() != Some(0)).to_string(),
+        ArgType::Double => value.as_f64().map(|v| v.to_string()).unwrap_or_default(),
+        ArgType::String | ArgType::ObjectPath | ArgType::Signature => {
+            value.as_str().unwrap_or_default().to_owned()
+        }
+        ArgType::Variant | ArgType::Array | ArgType::Struct | ArgType::DictEntry => {
+            match value.as_iter() {
+                Some(values) => values.map(format_value).collect::<Vec<_>>().join(" "),
+                None => String::new(),
+            }
+        }
+        _ => match value.as_i64() {
+            Some(v) => v.to_string(),
+            // Only a u64 does not fit
+            None => value.as_u64().map(|v| v.to_string()).unwrap_or_default(),
+        },
+    }
+}
+
+/// Asks for the property, which is unavailable e.g. while the service is not running.
+fn get_property(connection: &Connection, config: &DbusPropertyConfig) -> Option<String> {
+    let proxy = connection.with_proxy(&config.service, &config.path, Duration::from_secs(5));
+    let value: Variant<Box<dyn RefArg>> = proxy.get(&config.interface, &config.property).ok()?;
+    Some(format_value(&value))
+}
+
+impl ConfigBlock for DbusProperty {
+    type Config = DbusPropertyConfig;
+
+    fn new(block_config: Self::Config, config: Config, send: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let connection = match block_config.bus_type {
+            DbusBusType::Session => Connection::new_session(),
+            DbusBusType::System => Connection::new_system(),
+        }
+        .block_error("dbus_property", "Failed to establish D-Bus connection")?;
+        let value = Arc::new(Mutex::new(get_property(&connection, &block_config)));
+
+        let value_copy = value.clone();
+        let id_copy = id.clone();
+        let property_config = block_config.clone();
+        thread::Builder::new()
+            .name("dbus_property".into())
+            .spawn(move || {
+                let proxy = connection.with_proxy(
+                    property_config.service.clone(),
+                    property_config.path.clone(),
+                    Duration::from_secs(5),
+                );
+                let _handler = proxy.match_signal(
+                    move |s: PropertiesPropertiesChanged, c: &Connection, _: &Message| {
+                        if s.interface_name != property_config.interface {
+                            return true;
+                        }
+                        let new_value = match s.changed_properties.get(&property_config.property) {
+                            Some(changed) => Some(format_value(changed)),
+                            // Services may only tell that the value changed
+                            None if s
+                                .invalidated_properties
+                                .contains(&property_config.property) =>
+                            {
+                                get_property(c, &property_config)
+                            }
+                            None => return true,
+                        };
+                        *value_copy.lock().unwrap() = new_value;
+                        send.send(Task {
+                            id: id_copy.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                        true
+                    },
+                );
+                loop {
+                    connection.process(Duration::from_secs(3600)).unwrap();
+                }
+            })
+            .unwrap();
+
+        Ok(DbusProperty {
+            id,
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("dbus_property", "Invalid format specified")?,
+            mappings: block_config.mappings,
+            value,
+        })
+    }
+}
+
+impl Block for DbusProperty {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let value = self
+            .value
+            .lock()
+            .block_error("dbus_property", "failed to acquire lock")?
+            .clone()
+            .unwrap_or_default();
+        let value = match self.mappings.as_ref().and_then(|m| m.get(&value)) {
+            Some(mapping) => mapping.clone(),
+            None => value,
+        };
+
+        let values = map!("{value}" => value);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        // Updated whenever the property changes
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(&"charging".to_owned()), "charging");
+        assert_eq!(format_value(&42u32), "42");
+        assert_eq!(format_value(&-7i16), "-7");
+        assert_eq!(format_value(&u64::MAX), "18446744073709551615");
+        assert_eq!(format_value(&87.5f64), "87.5");
+        assert_eq!(format_value(&true), "true");
+        assert_eq!(format_value(&false), "false");
+        assert_eq!(
+            format_value(&Variant(Box::new(3u8) as Box<dyn RefArg>)),
+            "3"
+        );
+        assert_eq!(
+            format_value(&vec!["eth0".to_owned(), "wlan0".to_owned()]),
+            "eth0 wlan0"
+        );
+    }
+}
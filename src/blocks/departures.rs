@@ -0,0 +1,352 @@
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, TimeZone, Utc};
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use serde_json::value::Value;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::{Backoff, Task};
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// How often the minutes until the departure are recomputed between two requests
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum DeparturesProvider {
+    /// A REST API in front of HAFAS, like the one of transport.rest
+    Hafas {
+        #[serde(default = "DeparturesProvider::default_hafas_url")]
+        url: String,
+    },
+    /// Any endpoint returning JSON, with the fields located by JSON pointers
+    Json {
+        /// The URL to request, in which `{stop_id}` and `{api_key}` are replaced
+        url: String,
+        #[serde(default = "DeparturesProvider::default_json_departures")]
+        departures: String,
+        #[serde(default = "DeparturesProvider::default_json_line")]
+        line: String,
+        #[serde(default = "DeparturesProvider::default_json_direction")]
+        direction: String,
+        #[serde(default = "DeparturesProvider::default_json_time")]
+        time: String,
+    },
+}
+
+impl DeparturesProvider {
+    fn default_hafas_url() -> String {
+        "https://v6.db.transport.rest".to_owned()
+    }
+
+    fn default_json_departures() -> String {
+        "".to_owned()
+    }
+
+    fn default_json_line() -> String {
+        "/line".to_owned()
+    }
+
+    fn default_json_direction() -> String {
+        "/direction".to_owned()
+    }
+
+    fn default_json_time() -> String {
+        "/time".to_owned()
+    }
+
+    fn url(&self, stop_id: &str, api_key: &str) -> String {
+        match self {
+            DeparturesProvider::Hafas { url } => format!(
+                "{}/stops/{}/departures?duration=120&results=20",
+                url.trim_end_matches('/'),
+                stop_id
+            ),
+            DeparturesProvider::Json { url, .. } => url
+                .replace("{stop_id}", stop_id)
+                .replace("{api_key}", api_key),
+        }
+    }
+
+    /// Extracts the departures from a response, in any order.
+    fn parse(&self, json: &Value) -> Result<Vec<Departure>> {
+        let (departures, line, direction, time) = match self {
+            // Newer versions of the API wrap the departures in an object
+            DeparturesProvider::Hafas { .. } => (
+                json.get("departures").unwrap_or(json),
+                "/line/name",
+                "/direction",
+                "/when",
+            ),
+            DeparturesProvider::Json {
+                departures,
+                line,
+                direction,
+                time,
+                ..
+            } => (
+                json.pointer(departures)
+                    .block_error("departures", "departures not found in the response")?,
+                line.as_str(),
+                direction.as_str(),
+                time.as_str(),
+            ),
+        };
+        let departures = departures
+            .as_array()
+            .block_error("departures", "the departures are not a list")?;
+
+        Ok(departures
+            .iter()
+            .filter_map(|departure| {
+                // Cancelled departures have no time
+                Some(Departure {
+                    time: parse_time(departure.pointer(time)?)?,
+                    line: text(departure.pointer(line)),
+                    direction: text(departure.pointer(direction)),
+                })
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Departure {
+    time: DateTime<Utc>,
+    line: String,
+    direction: String,
+}
+
+/// Parses a time given as RFC 3339 string or as seconds since the epoch.
+fn parse_time(time: &Value) -> Option<DateTime<Utc>> {
+    match time {
+        Value::String(time) => DateTime::parse_from_rfc3339(time)
+            .ok()
+            .map(|time| time.with_timezone(&Utc)),
+        Value::Number(time) => Utc.timestamp_opt(time.as_i64()?, 0).single(),
+        _ => None,
+    }
+}
+
+fn text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// The first departure that has not left yet.
+fn next_departure(departures: &[Departure], now: DateTime<Utc>) -> Option<&Departure> {
+    departures
+        .iter()
+        .filter(|departure| departure.time >= now)
+        .min_by_key(|departure| departure.time)
+}
+
+pub struct Departures {
+    id: String,
+    text: ButtonWidget,
+    format: FormatTemplate,
+    provider: DeparturesProvider,
+    stop_id: String,
+    api_key: String,
+    backoff: Backoff,
+    /// The departures of the last successful request
+    departures: Vec<Departure>,
+    next_request: Instant,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DeparturesConfig {
+    pub provider: DeparturesProvider,
+
+    /// The ID of the stop to show the departures of
+    pub stop_id: String,
+
+    /// Key for the API, if it needs one
+    #[serde(default = "DeparturesConfig::default_api_key")]
+    pub api_key: Option<String>,
+
+    /// Time between two requests in seconds
+    #[serde(
+        default = "DeparturesConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "DeparturesConfig::default_format")]
+    pub format: String,
+}
+
+impl DeparturesConfig {
+    fn default_api_key() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(120)
+    }
+
+    fn default_format() -> String {
+        "{line} {minutes}min".to_owned()
+    }
+}
+
+impl Departures {
+    /// Requests the departures, or returns `None` if the API cannot be reached.
+    fn request(&self) -> Result<Option<Vec<Departure>>> {
+        let output = Command::new("curl")
+            .args([
+                "--silent",
+                "--fail",
+                "--max-time",
+                "5",
+                &self.provider.url(&self.stop_id, &self.api_key),
+            ])
+            .output()
+            .block_error("departures", "failed to execute curl")?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let json: Value = serde_json::from_slice(&output.stdout)
+            .block_error("departures", "failed to parse JSON response")?;
+        self.provider.parse(&json).map(Some)
+    }
+}
+
+impl ConfigBlock for Departures {
+    type Config = DeparturesConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(Departures {
+            text: ButtonWidget::new(config, &id).with_icon("departures"),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("departures", "Invalid format specified")?,
+            provider: block_config.provider,
+            stop_id: block_config.stop_id,
+            api_key: block_config.api_key.unwrap_or_default(),
+            backoff: Backoff::new(block_config.interval),
+            departures: Vec::new(),
+            next_request: Instant::now(),
+        })
+    }
+}
+
+impl Block for Departures {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let now = Instant::now();
+        if self.next_request <= now {
+            let interval = match self.request()? {
+                Some(departures) => {
+                    self.departures = departures;
+                    self.backoff.success()
+                }
+                None => self.backoff.failure(),
+            };
+            self.next_request = now + interval;
+        }
+
+        // The last departures stay useful while the API cannot be reached
+        match next_departure(&self.departures, Utc::now()) {
+            Some(departure) => {
+                let minutes = (departure.time - Utc::now()).num_minutes();
+                let values = map!(
+                    "{line}" => departure.line.clone(),
+                    "{direction}" => departure.direction.clone(),
+                    "{minutes}" => minutes.to_string()
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(State::Idle);
+            }
+            None => {
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+            }
+        }
+
+        let until_request = self.next_request.saturating_duration_since(now);
+        Ok(Some(until_request.min(REFRESH_INTERVAL).into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            self.next_request = Instant::now();
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_departures() {
+        let hafas = DeparturesProvider::Hafas {
+            url: DeparturesProvider::default_hafas_url(),
+        };
+        let json: Value = serde_json::from_str(
+            r#"{"departures": [
+                {"when": "2026-10-14T12:34:00+02:00", "line": {"name": "S 1"}, "direction": "Wannsee"},
+                {"when": null, "line": {"name": "S 2"}, "direction": "Bernau", "cancelled": true},
+                {"when": "2026-10-14T12:30:00+02:00", "line": {"name": "U 6"}, "direction": "Tegel"}
+            ]}"#,
+        )
+        .unwrap();
+        let departures = hafas.parse(&json).unwrap();
+        assert_eq!(departures.len(), 2);
+
+        let now = Utc.ymd(2026, 10, 14).and_hms(10, 29, 0);
+        assert_eq!(next_departure(&departures, now).unwrap().line, "U 6");
+        // Departures that have left already are skipped
+        let now = Utc.ymd(2026, 10, 14).and_hms(10, 31, 0);
+        assert_eq!(next_departure(&departures, now).unwrap().line, "S 1");
+        let now = Utc.ymd(2026, 10, 14).and_hms(10, 35, 0);
+        assert_eq!(next_departure(&departures, now), None);
+
+        let generic = DeparturesProvider::Json {
+            url: "https://example.com/{stop_id}?key={api_key}".to_owned(),
+            departures: "/data".to_owned(),
+            line: "/route".to_owned(),
+            direction: DeparturesProvider::default_json_direction(),
+            time: DeparturesProvider::default_json_time(),
+        };
+        assert_eq!(
+            generic.url("42", "secret"),
+            "https://example.com/42?key=secret"
+        );
+        let json: Value =
+            serde_json::from_str(r#"{"data": [{"route": 7, "time": 1791974040}]}"#).unwrap();
+        assert_eq!(
+            generic.parse(&json).unwrap(),
+            vec![Departure {
+                time: Utc.timestamp(1791974040, 0),
+                line: "7".to_owned(),
+                direction: "".to_owned(),
+            }]
+        );
+    }
+}
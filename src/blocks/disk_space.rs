@@ -1,5 +1,5 @@
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 use nix::sys::statvfs::statvfs;
@@ -12,7 +12,7 @@ use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::scheduler::Task;
-use crate::util::{format_percent_bar, FormatTemplate};
+use crate::util::{color_gradient, format_duration, format_percent_bar, FormatTemplate, History};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::text::TextWidget;
 
@@ -64,6 +64,9 @@ pub struct DiskSpace {
     show_bar: bool,
     format: FormatTemplate,
     icon: String,
+    gradient: Option<(String, String)>,
+    /// The available space at the last updates, oldest first
+    history: History,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -85,7 +88,7 @@ pub struct DiskSpaceConfig {
 
     /// Format string for output
     /// placeholders: {percentage}, {bar}, {path}, {alias}, {available}, {free}, {total}, {used},
-    ///               {unit}
+    ///               {unit}, {eta}
     #[serde(default = "DiskSpaceConfig::default_format")]
     pub format: String,
 
@@ -115,6 +118,10 @@ pub struct DiskSpaceConfig {
     /// Show percentage bar - deprecated for format string, kept for previous configs
     #[serde(default = "DiskSpaceConfig::default_show_bar")]
     pub show_bar: bool,
+
+    /// Foreground colors at 0% and 100% of the disk used, interpolated in between
+    #[serde(default = "DiskSpaceConfig::default_gradient")]
+    pub gradient: Option<(String, String)>,
 }
 
 impl DiskSpaceConfig {
@@ -159,6 +166,10 @@ impl DiskSpaceConfig {
     fn default_show_bar() -> bool {
         false
     }
+
+    fn default_gradient() -> Option<(String, String)> {
+        None
+    }
 }
 
 enum AlertType {
@@ -205,6 +216,11 @@ impl ConfigBlock for DiskSpace {
             .cloned()
             .expect("Could not find disk drive icon");
 
+        if let Some((ref start, ref end)) = block_config.gradient {
+            color_gradient(start, end, 0.0)
+                .configuration_error("invalid disk_space gradient color")?;
+        }
+
         Ok(DiskSpace {
             id: Uuid::new_v4().to_simple().to_string(),
             update_interval: block_config.interval,
@@ -219,6 +235,8 @@ impl ConfigBlock for DiskSpace {
             show_percentage: block_config.show_percentage,
             show_bar: block_config.show_bar,
             icon,
+            gradient: block_config.gradient,
+            history: History::new(),
         })
     }
 }
@@ -235,6 +253,12 @@ impl Block for DiskSpace {
         let available = (statvfs.blocks_available() as u64) * (statvfs.block_size() as u64);
         let free = (statvfs.blocks_free() as u64) * (statvfs.block_size() as u64);
 
+        self.history.push(Instant::now(), available);
+        let eta = match self.history.seconds_left() {
+            Some(seconds) => format_duration(seconds),
+            None => "stable".to_owned(),
+        };
+
         let alert_type;
         match self.info_type {
             InfoType::Available => {
@@ -276,6 +300,7 @@ impl Block for DiskSpace {
         "{available}" => format!("{:.2}", Unit::bytes_in_unit(self.unit, available)),
         "{free}" => format!("{:.2}", Unit::bytes_in_unit(self.unit, free)),
         "{icon}" => self.icon.to_string(),
+        "{eta}" => eta,
         "{result}" => format!("{:.2}", result)
         );
         self.disk_space
@@ -294,6 +319,13 @@ impl Block for DiskSpace {
         );
         self.disk_space.set_state(state);
 
+        if let Some((ref start, ref end)) = self.gradient {
+            // The severity is always the used share, whatever `info_type` displays
+            let used_percent = used as f64 / total as f64 * 100.0;
+            self.disk_space
+                .set_color(color_gradient(start, end, used_percent).ok());
+        }
+
         Ok(Some(self.update_interval.into()))
     }
 
@@ -0,0 +1,146 @@
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::screen_layout::watch_hotplug;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::{cycle_next, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+pub struct DisplayProfile {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    profiles: Option<Vec<String>>,
+    current: Option<String>,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayProfileConfig {
+    /// Update interval in seconds, in addition to the updates on hotplug events
+    #[serde(
+        default = "DisplayProfileConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "DisplayProfileConfig::default_format")]
+    pub format: String,
+
+    /// The profiles to cycle through on clicks, or all of them if not set
+    #[serde(default = "DisplayProfileConfig::default_profiles")]
+    pub profiles: Option<Vec<String>>,
+}
+
+impl DisplayProfileConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{profile}".to_owned()
+    }
+
+    fn default_profiles() -> Option<Vec<String>> {
+        None
+    }
+}
+
+/// Runs autorandr and returns the profile names it prints, one per line.
+fn autorandr(arg: &str) -> Result<Vec<String>> {
+    let output = Command::new("autorandr")
+        .arg(arg)
+        .output()
+        .block_error("display_profile", "failed to run autorandr")?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+impl ConfigBlock for DisplayProfile {
+    type Config = DisplayProfileConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        // autorandr picks a new profile on hotplug events by itself, if it is set up to
+        watch_hotplug("display_profile", id.clone(), tx.clone());
+
+        Ok(DisplayProfile {
+            output: ButtonWidget::new(config, &id).with_icon("display_profile"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("display_profile", "Invalid format specified")?,
+            profiles: block_config.profiles,
+            current: None,
+            tx_update_request: tx,
+        })
+    }
+}
+
+impl Block for DisplayProfile {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Several profiles can match the connected monitors, the first one is shown
+        self.current = autorandr("--current")?.into_iter().next();
+
+        let values = map!(
+            "{profile}" => self.current.clone().unwrap_or_else(|| "-".to_owned())
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            let profiles = match self.profiles {
+                Some(ref profiles) => profiles.clone(),
+                None => autorandr("--list")?,
+            };
+            if let Some(next) = cycle_next(&profiles, self.current.as_deref()) {
+                // Switching takes a moment, the block is updated once it is done
+                let next = next.clone();
+                let id = self.id.clone();
+                let tx = self.tx_update_request.clone();
+                thread::Builder::new()
+                    .name("display_profile".into())
+                    .spawn(move || {
+                        let _ = Command::new("autorandr").args(["--load", &next]).status();
+                        tx.send(Task {
+                            id,
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                    })
+                    .unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
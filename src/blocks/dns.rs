@@ -0,0 +1,192 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use dbus::blocking::{stdintf::org_freedesktop_dbus::Properties, Connection};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+const RESOLVED_SERVICE: &str = "org.freedesktop.resolve1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.resolve1.Manager";
+const LINK_INTERFACE: &str = "org.freedesktop.resolve1.Link";
+
+/// The `AF_INET` and `AF_INET6` address families
+const FAMILY_INET: i32 = 2;
+const FAMILY_INET6: i32 = 10;
+
+pub struct Dns {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    dbus_conn: Connection,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DnsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "DnsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "DnsConfig::default_format")]
+    pub format: String,
+}
+
+impl DnsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{servers}".to_owned()
+    }
+}
+
+/// Formats an address like systemd-resolved reports it, as its family and its bytes.
+fn format_address(family: i32, address: &[u8]) -> Option<String> {
+    let address: IpAddr = match family {
+        FAMILY_INET if address.len() == 4 => {
+            Ipv4Addr::new(address[0], address[1], address[2], address[3]).into()
+        }
+        FAMILY_INET6 if address.len() == 16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(address);
+            Ipv6Addr::from(octets).into()
+        }
+        _ => return None,
+    };
+    Some(address.to_string())
+}
+
+impl Dns {
+    /// The servers systemd-resolved currently sends queries to: the global one and those of
+    /// all links that are used for any domain, which leaves out links that only resolve
+    /// their own domains, like many VPNs with split DNS.
+    fn current_servers(&self) -> Result<Vec<String>> {
+        let manager = self.dbus_conn.with_proxy(
+            RESOLVED_SERVICE,
+            "/org/freedesktop/resolve1",
+            Duration::from_millis(1000),
+        );
+        let mut servers = Vec::new();
+
+        let (family, address): (i32, Vec<u8>) = manager
+            .get::<(i32, i32, Vec<u8>)>(MANAGER_INTERFACE, "CurrentDNSServer")
+            .map(|(_, family, address)| (family, address))
+            .block_error("dns", "failed to get the DNS server from systemd-resolved")?;
+        servers.extend(format_address(family, &address));
+
+        // All configured servers, with the link they are configured for or 0 if global
+        let configured: Vec<(i32, i32, Vec<u8>)> = manager
+            .get(MANAGER_INTERFACE, "DNS")
+            .block_error("dns", "failed to get the DNS servers from systemd-resolved")?;
+        let mut links: Vec<i32> = configured
+            .iter()
+            .map(|&(link, _, _)| link)
+            .filter(|&link| link != 0)
+            .collect();
+        links.sort_unstable();
+        links.dedup();
+
+        for link in links {
+            let (path,): (dbus::Path<'static>,) = manager
+                .method_call(MANAGER_INTERFACE, "GetLink", (link,))
+                .block_error("dns", "failed to get a link from systemd-resolved")?;
+            let link =
+                self.dbus_conn
+                    .with_proxy(RESOLVED_SERVICE, path, Duration::from_millis(1000));
+            // Older versions of systemd-resolved do not have the property
+            let default_route: bool = link.get(LINK_INTERFACE, "DefaultRoute").unwrap_or(true);
+            if !default_route {
+                continue;
+            }
+            let (family, address): (i32, Vec<u8>) = link
+                .get(LINK_INTERFACE, "CurrentDNSServer")
+                .block_error("dns", "failed to get the DNS server of a link")?;
+            servers.extend(format_address(family, &address));
+        }
+
+        // A link may use the same server as the global configuration
+        let mut unique = Vec::new();
+        for server in servers {
+            if !unique.contains(&server) {
+                unique.push(server);
+            }
+        }
+        Ok(unique)
+    }
+}
+
+impl ConfigBlock for Dns {
+    type Config = DnsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Dns {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("dns"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("dns", "Invalid format specified")?,
+            dbus_conn: Connection::new_system()
+                .block_error("dns", "failed to establish D-Bus connection")?,
+        })
+    }
+}
+
+impl Block for Dns {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let servers = self.current_servers()?;
+        let values = map!("{servers}" => servers.join(" "));
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_address() {
+        assert_eq!(
+            format_address(FAMILY_INET, &[192, 168, 1, 1]),
+            Some("192.168.1.1".to_owned())
+        );
+        let mut address = [0; 16];
+        address[0] = 0x20;
+        address[1] = 0x01;
+        address[2] = 0x0d;
+        address[3] = 0xb8;
+        address[15] = 0x53;
+        assert_eq!(
+            format_address(FAMILY_INET6, &address),
+            Some("2001:db8::53".to_owned())
+        );
+        // Unset servers are reported without an address
+        assert_eq!(format_address(0, &[]), None);
+        assert_eq!(format_address(FAMILY_INET, &[1, 2]), None);
+    }
+}
@@ -0,0 +1,229 @@
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::smart::smart_temperature;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct DriveTemp {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    warning: u64,
+    critical: u64,
+    /// The last temperature in °C, once it was read
+    temperature: Arc<Mutex<Option<Result<u64>>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DriveTempConfig {
+    /// The drive to show the temperature of
+    #[serde(default = "DriveTempConfig::default_device")]
+    pub device: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "DriveTempConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Temperature in °C from which the block is in the warning state
+    #[serde(default = "DriveTempConfig::default_warning")]
+    pub warning: u64,
+
+    /// Temperature in °C from which the block is in the critical state
+    #[serde(default = "DriveTempConfig::default_critical")]
+    pub critical: u64,
+
+    /// Whether to run smartctl via `sudo -n`, for drives without a hwmon sensor
+    #[serde(default = "DriveTempConfig::default_use_sudo")]
+    pub use_sudo: bool,
+
+    /// Format override
+    #[serde(default = "DriveTempConfig::default_format")]
+    pub format: String,
+}
+
+impl DriveTempConfig {
+    fn default_device() -> String {
+        "/dev/nvme0n1".to_owned()
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(120)
+    }
+
+    fn default_warning() -> u64 {
+        50
+    }
+
+    fn default_critical() -> u64 {
+        60
+    }
+
+    fn default_use_sudo() -> bool {
+        false
+    }
+
+    fn default_format() -> String {
+        "{temp}°".to_owned()
+    }
+}
+
+/// Reads the temperature in °C from the hwmon sensor of a block device in `/sys/block`.
+/// NVMe drives have one, as do SATA drives with the `drivetemp` module loaded.
+fn hwmon_temperature(block: &Path) -> Option<u64> {
+    let device = block.join("device");
+    // NVMe controllers have the hwmon directly, SCSI devices in a `hwmon` class directory
+    let entries = |dir: PathBuf| -> Vec<PathBuf> {
+        read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_name().to_string_lossy().starts_with("hwmon"))
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    entries(device.clone())
+        .into_iter()
+        .chain(entries(device.join("hwmon")))
+        .filter_map(|hwmon| read_to_string(hwmon.join("temp1_input")).ok())
+        .filter_map(|millidegrees| millidegrees.trim().parse::<u64>().ok())
+        .map(|millidegrees| millidegrees / 1000)
+        .next()
+}
+
+/// The temperature of `device` from hwmon, or from its SMART attributes if it has no sensor.
+fn read_temperature(device: &str, use_sudo: bool) -> Result<u64> {
+    let name = Path::new(device)
+        .file_name()
+        .block_error("drive_temp", &format!("invalid device {}", device))?;
+    if let Some(temperature) = hwmon_temperature(&Path::new("/sys/block").join(name)) {
+        return Ok(temperature);
+    }
+    smart_temperature(device, use_sudo)?.block_error(
+        "drive_temp",
+        &format!("could not read the temperature of {}", device),
+    )
+}
+
+impl ConfigBlock for DriveTemp {
+    type Config = DriveTempConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let temperature = Arc::new(Mutex::new(None));
+
+        // smartctl can take several seconds, so the drive is queried off the main thread
+        {
+            let id = id.clone();
+            let temperature = temperature.clone();
+            let device = block_config.device;
+            let use_sudo = block_config.use_sudo;
+            let interval = block_config.interval;
+            thread::Builder::new()
+                .name("drive_temp".into())
+                .spawn(move || loop {
+                    let result = read_temperature(&device, use_sudo);
+                    *temperature.lock().unwrap() = Some(result);
+                    tx.send(Task {
+                        id: id.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+                    thread::sleep(interval);
+                })
+                .unwrap();
+        }
+
+        Ok(DriveTemp {
+            id,
+            text: TextWidget::new(config)
+                .with_icon("disk_drive")
+                .with_text("..."),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("drive_temp", "Invalid format specified")?,
+            warning: block_config.warning,
+            critical: block_config.critical,
+            temperature,
+        })
+    }
+}
+
+impl Block for DriveTemp {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let temperature = self
+            .temperature
+            .lock()
+            .block_error("drive_temp", "failed to acquire lock")?;
+
+        match *temperature {
+            Some(Ok(temp)) => {
+                let values = map!("{temp}" => temp.to_string());
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(if temp >= self.critical {
+                    State::Critical
+                } else if temp >= self.warning {
+                    State::Warning
+                } else {
+                    State::Idle
+                });
+            }
+            Some(Err(ref e)) => {
+                self.text.set_text(format!("{}", e));
+                self.text.set_state(State::Critical);
+            }
+            None => (),
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_hwmon_temperature() {
+        let nvme = TempDir::new().unwrap();
+        assert_eq!(hwmon_temperature(nvme.path()), None);
+        nvme.child("device/hwmon1/temp1_input")
+            .write_str("41850\n")
+            .unwrap();
+        assert_eq!(hwmon_temperature(nvme.path()), Some(41));
+
+        let sata = TempDir::new().unwrap();
+        sata.child("device/hwmon/hwmon3/temp1_input")
+            .write_str("35000\n")
+            .unwrap();
+        assert_eq!(hwmon_temperature(sata.path()), Some(35));
+    }
+}
@@ -0,0 +1,149 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct FailedUnits {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    user: bool,
+    max_units: usize,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FailedUnitsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "FailedUnitsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "FailedUnitsConfig::default_format")]
+    pub format: String,
+
+    /// Whether to check the units of the user's service manager instead of the system's
+    #[serde(default = "FailedUnitsConfig::default_user")]
+    pub user: bool,
+
+    /// How many unit names `{units}` lists at most
+    #[serde(default = "FailedUnitsConfig::default_max_units")]
+    pub max_units: usize,
+}
+
+impl FailedUnitsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+
+    fn default_user() -> bool {
+        false
+    }
+
+    fn default_max_units() -> usize {
+        3
+    }
+}
+
+/// Extracts the unit names of the output of `systemctl --failed --plain --no-legend`.
+fn parse_failed_units(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect()
+}
+
+impl ConfigBlock for FailedUnits {
+    type Config = FailedUnitsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(FailedUnits {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("failed_units"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("failed_units", "Invalid format specified")?,
+            user: block_config.user,
+            max_units: block_config.max_units,
+        })
+    }
+}
+
+impl Block for FailedUnits {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mut cmd = Command::new("systemctl");
+        if self.user {
+            cmd.arg("--user");
+        }
+        let output = cmd
+            .args(["--failed", "--plain", "--no-legend"])
+            .output()
+            .block_error("failed_units", "failed to run systemctl")?;
+        let output = String::from_utf8_lossy(&output.stdout);
+        let units = parse_failed_units(&output);
+
+        let mut names = units
+            .iter()
+            .take(self.max_units)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if units.len() > self.max_units {
+            names.push_str(" …");
+        }
+        let values = map!("{count}" => units.len().to_string(), "{units}" => names);
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(if units.is_empty() {
+            State::Good
+        } else {
+            State::Critical
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_failed_units() {
+        let output = "\
+backup.service loaded failed failed Nightly backup
+mnt-nas.mount  loaded failed failed /mnt/nas
+";
+        assert_eq!(
+            parse_failed_units(output),
+            vec!["backup.service", "mnt-nas.mount"]
+        );
+        assert!(parse_failed_units("").is_empty());
+    }
+}
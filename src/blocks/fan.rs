@@ -0,0 +1,207 @@
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{read_file, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+/// A `fanN_input` file of a hwmon device.
+struct FanInput {
+    label: String,
+    path: PathBuf,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FanMode {
+    /// Show the speed of the fastest fan
+    #[default]
+    Max,
+    /// Show the speed of every fan
+    Individual,
+}
+
+pub struct Fan {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    mode: FanMode,
+    fans: Vec<FanInput>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FanConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "FanConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "FanConfig::default_format")]
+    pub format: String,
+
+    /// Name of the hwmon chip to read from
+    #[serde(default = "FanConfig::default_chip")]
+    pub chip: Option<String>,
+
+    /// Fan labels whitelist
+    #[serde(default = "FanConfig::default_inputs")]
+    pub inputs: Option<Vec<String>>,
+
+    /// Whether to show the fastest fan or every fan
+    #[serde(default = "FanMode::default")]
+    pub mode: FanMode,
+}
+
+impl FanConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{rpm}".to_owned()
+    }
+
+    fn default_chip() -> Option<String> {
+        None
+    }
+
+    fn default_inputs() -> Option<Vec<String>> {
+        None
+    }
+}
+
+/// Finds the fan inputs of all hwmon devices. Fans without a `fanN_label` file are labelled
+/// `fanN`.
+fn find_fans(chip: &Option<String>, inputs: &Option<Vec<String>>) -> Result<Vec<FanInput>> {
+    let mut fans = Vec::new();
+
+    let devices =
+        read_dir("/sys/class/hwmon").block_error("fan", "failed to read /sys/class/hwmon")?;
+    for device in devices {
+        let device = device
+            .block_error("fan", "failed to read hwmon device")?
+            .path();
+        if let Some(ref chip) = chip {
+            match read_to_string(device.join("name")) {
+                Ok(ref name) if name.trim() == chip => (),
+                _ => continue,
+            }
+        }
+
+        let files = device
+            .read_dir()
+            .block_error("fan", "failed to read hwmon device")?;
+        for file in files {
+            let path = file
+                .block_error("fan", "failed to read hwmon device")?
+                .path();
+            let input = match path.file_name().and_then(|name| name.to_str()) {
+                Some(name) if name.starts_with("fan") && name.ends_with("_input") => {
+                    name.trim_end_matches("_input").to_owned()
+                }
+                _ => continue,
+            };
+
+            let label = read_to_string(device.join(format!("{}_label", input)))
+                .map(|label| label.trim().to_owned())
+                .unwrap_or(input);
+            if let Some(ref whitelist) = inputs {
+                if !whitelist.contains(&label) {
+                    continue;
+                }
+            }
+            fans.push(FanInput { label, path });
+        }
+    }
+
+    fans.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(fans)
+}
+
+fn read_rpm(path: &Path) -> Result<u64> {
+    read_file("fan", path)?
+        .parse()
+        .block_error("fan", "failed to parse fan speed")
+}
+
+impl ConfigBlock for Fan {
+    type Config = FanConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let fans = find_fans(&block_config.chip, &block_config.inputs)?;
+        if fans.is_empty() {
+            return Err(BlockError("fan".to_owned(), "no fans found".to_owned()));
+        }
+
+        Ok(Fan {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("fan"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("fan", "Invalid format specified")?,
+            mode: block_config.mode,
+            fans,
+        })
+    }
+}
+
+impl Fan {
+    fn render(&self, label: &str, rpm: u64) -> Result<String> {
+        // A stopped fan is easy to miss among the numbers
+        if rpm == 0 {
+            return Ok("off".to_owned());
+        }
+        let values = map!("{rpm}" => rpm.to_string(), "{label}" => label.to_owned());
+        self.format.render_static_str(&values)
+    }
+}
+
+impl Block for Fan {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mut speeds = Vec::with_capacity(self.fans.len());
+        for fan in &self.fans {
+            speeds.push((fan.label.as_str(), read_rpm(&fan.path)?));
+        }
+
+        let text = match self.mode {
+            FanMode::Max => {
+                let (label, rpm) = speeds
+                    .iter()
+                    .max_by_key(|(_, rpm)| *rpm)
+                    .block_error("fan", "no fans found")?;
+                self.render(label, *rpm)?
+            }
+            FanMode::Individual => speeds
+                .iter()
+                .map(|(label, rpm)| self.render(label, *rpm))
+                .collect::<Result<Vec<String>>>()?
+                .join(" "),
+        };
+        self.text.set_text(text);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use inotify::{Inotify, WatchMask};
+use regex::Regex;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct FileCount {
+    id: String,
+    text: TextWidget,
+    format: FormatTemplate,
+    path: PathBuf,
+    pattern: Option<Regex>,
+    warning: Option<u64>,
+    critical: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FileCountConfig {
+    /// The directory whose files are counted
+    pub path: String,
+
+    /// The regex the names of the counted files have to match, e.g. `\.pid$`
+    #[serde(default = "FileCountConfig::default_pattern")]
+    pub pattern: Option<String>,
+
+    /// Minimum number of files, where state is set to warning
+    #[serde(default = "FileCountConfig::default_warning")]
+    pub warning: Option<u64>,
+
+    /// Minimum number of files, where state is set to critical
+    #[serde(default = "FileCountConfig::default_critical")]
+    pub critical: Option<u64>,
+
+    /// Format override
+    #[serde(default = "FileCountConfig::default_format")]
+    pub format: String,
+}
+
+impl FileCountConfig {
+    fn default_pattern() -> Option<String> {
+        None
+    }
+
+    fn default_warning() -> Option<u64> {
+        None
+    }
+
+    fn default_critical() -> Option<u64> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+}
+
+/// Counts the entries of `dir` whose names match `pattern`. Hidden files are skipped, as
+/// they are usually temporary files of the programs writing to the directory.
+fn count_files(dir: &Path, pattern: Option<&Regex>) -> Result<u64> {
+    let entries = fs::read_dir(dir)
+        .block_error("file_count", &format!("failed to read {}", dir.display()))?;
+    let count = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && pattern.is_none_or(|pattern| pattern.is_match(&name))
+        })
+        .count();
+    Ok(count as u64)
+}
+
+impl ConfigBlock for FileCount {
+    type Config = FileCountConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let path = PathBuf::from(block_config.path);
+        let pattern = match block_config.pattern {
+            Some(pattern) => {
+                Some(Regex::new(&pattern).block_error("file_count", "invalid regex specified")?)
+            }
+            None => None,
+        };
+
+        let mut notify = Inotify::init().block_error("file_count", "failed to start inotify")?;
+        notify
+            .add_watch(
+                &path,
+                WatchMask::CREATE | WatchMask::DELETE | WatchMask::MOVED_TO | WatchMask::MOVED_FROM,
+            )
+            .block_error("file_count", &format!("failed to watch {}", path.display()))?;
+
+        let id_copy = id.clone();
+        thread::Builder::new()
+            .name("file_count".into())
+            .spawn(move || {
+                let mut buffer = [0; 1024];
+                loop {
+                    notify
+                        .read_events_blocking(&mut buffer)
+                        .expect("Error while reading inotify events");
+                    tx.send(Task {
+                        id: id_copy.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+                }
+            })
+            .unwrap();
+
+        Ok(FileCount {
+            id,
+            text: TextWidget::new(config).with_icon("file_count"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("file_count", "Invalid format specified")?,
+            path,
+            pattern,
+            warning: block_config.warning,
+            critical: block_config.critical,
+        })
+    }
+}
+
+impl Block for FileCount {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let count = count_files(&self.path, self.pattern.as_ref())?;
+
+        let values = map!("{count}" => count);
+        self.text.set_text(self.format.render_static_str(&values)?);
+        let reached = |threshold: Option<u64>| match threshold {
+            Some(threshold) => count >= threshold,
+            None => false,
+        };
+        self.text.set_state(if reached(self.critical) {
+            State::Critical
+        } else if reached(self.warning) {
+            State::Warning
+        } else {
+            State::Idle
+        });
+
+        // Updated whenever a file is added to or removed from the directory
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_count_files() {
+        let dir = TempDir::new().unwrap();
+        dir.child("build-1.pid").write_str("1234\n").unwrap();
+        dir.child("build-2.pid").write_str("1235\n").unwrap();
+        dir.child("build-2.log").write_str("").unwrap();
+        dir.child(".build-3.pid.tmp").write_str("").unwrap();
+
+        assert_eq!(count_files(dir.path(), None).unwrap(), 3);
+        let pattern = Regex::new(r"\.pid$").unwrap();
+        assert_eq!(count_files(dir.path(), Some(&pattern)).unwrap(), 2);
+        assert!(count_files(&dir.path().join("missing"), None).is_err());
+    }
+}
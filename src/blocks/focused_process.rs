@@ -0,0 +1,186 @@
+use std::fs::read_to_string;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use nix::unistd::{sysconf, SysconfVar};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::ipc::{self, FocusedWindow};
+use crate::scheduler::Task;
+use crate::util::{format_speed, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+/// The CPU time a process has used so far, at the time it was sampled.
+struct CpuSample {
+    pid: i32,
+    ticks: u64,
+    time: Instant,
+}
+
+pub struct FocusedProcess {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    /// The focused window and its process, if it has one
+    focused: FocusedWindow,
+    last_sample: Option<CpuSample>,
+    ticks_per_second: f64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FocusedProcessConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "FocusedProcessConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "FocusedProcessConfig::default_format")]
+    pub format: String,
+}
+
+impl FocusedProcessConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn default_format() -> String {
+        "{cpu} {mem}".to_owned()
+    }
+}
+
+/// Extracts the command name and used CPU time in clock ticks from `/proc/<pid>/stat`.
+fn parse_stat(stat: &str) -> Option<(String, u64)> {
+    // The command name is in parentheses and may contain spaces and parentheses itself
+    let name_start = stat.find('(')?;
+    let name_end = stat.rfind(')')?;
+    let name = stat.get(name_start + 1..name_end)?.to_owned();
+    // The fields after the name start with the state, so utime and stime are the 12th and 13th
+    let mut fields = stat.get(name_end + 1..)?.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some((name, utime + stime))
+}
+
+/// Extracts the resident memory in bytes from `/proc/<pid>/status`.
+pub fn parse_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+impl ConfigBlock for FocusedProcess {
+    type Config = FocusedProcessConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        let focused = ipc::watch_focused_pid("focused_process", id.clone(), tx)?;
+
+        Ok(FocusedProcess {
+            id,
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("focused_process"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("focused_process", "Invalid format specified")?,
+            focused,
+            last_sample: None,
+            ticks_per_second: sysconf(SysconfVar::CLK_TCK).ok().flatten().unwrap_or(100) as f64,
+        })
+    }
+}
+
+impl Block for FocusedProcess {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let pid = match *self
+            .focused
+            .lock()
+            .block_error("focused_process", "failed to acquire lock")?
+        {
+            Some((_, pid)) => pid,
+            None => {
+                self.last_sample = None;
+                return Ok(Some(self.update_interval.into()));
+            }
+        };
+
+        // The process may have exited since it was focused
+        let stat = read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+        let status = read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+        let (name, ticks) = match parse_stat(&stat) {
+            Some(stat) => stat,
+            None => {
+                self.last_sample = None;
+                self.text.set_text("×".to_owned());
+                return Ok(Some(self.update_interval.into()));
+            }
+        };
+
+        // The usage is only known from the second sample of the same process on
+        let now = Instant::now();
+        let cpu = match self.last_sample {
+            Some(ref last) if last.pid == pid => {
+                let seconds = now.duration_since(last.time).as_secs_f64();
+                let used = ticks.saturating_sub(last.ticks) as f64 / self.ticks_per_second;
+                format!("{:.0}%", 100.0 * used / seconds.max(0.001))
+            }
+            _ => "-".to_owned(),
+        };
+        self.last_sample = Some(CpuSample {
+            pid,
+            ticks,
+            time: now,
+        });
+        let mem = match parse_rss(&status) {
+            Some(bytes) => format_speed(bytes, 3, "B", false),
+            None => "-".to_owned(),
+        };
+
+        let values = map!(
+            "{cpu}" => cpu,
+            "{mem}" => mem,
+            "{name}" => name,
+            "{pid}" => pid.to_string()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        match *self.focused.lock().unwrap() {
+            Some(_) => vec![&self.text],
+            None => vec![],
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc() {
+        let stat = "4242 (Web Content (x)) S 1 4242 4242 0 -1 4194560 1673 0 0 0 \
+                    120 35 0 0 20 0 12 0 3511 1024 300 18446744073709551615";
+        assert_eq!(parse_stat(stat), Some(("Web Content (x)".to_owned(), 155)));
+        assert_eq!(parse_stat("garbage"), None);
+
+        let status = "Name:\tfirefox\nVmPeak:\t  900000 kB\nVmRSS:\t  204800 kB\n";
+        assert_eq!(parse_rss(status), Some(204_800 * 1024));
+    }
+}
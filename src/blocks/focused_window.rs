@@ -1,16 +1,15 @@
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Instant;
 
 use crossbeam_channel::Sender;
 use serde_derive::Deserialize;
 use swayipc::reply::{Event, WindowChange, WorkspaceChange};
-use swayipc::{Connection, EventType};
+use swayipc::EventType;
 use uuid::Uuid;
 
 use crate::blocks::{Block, ConfigBlock, Update};
 use crate::config::Config;
 use crate::errors::*;
+use crate::ipc;
 use crate::scheduler::Task;
 use crate::widget::I3BarWidget;
 use crate::widgets::text::TextWidget;
@@ -59,7 +58,6 @@ impl ConfigBlock for FocusedWindow {
 
     fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
         let id = Uuid::new_v4().to_simple().to_string();
-        let id_clone = id.clone();
 
         let title_original = Arc::new(Mutex::new(String::from("")));
         let title = title_original.clone();
@@ -67,118 +65,62 @@ impl ConfigBlock for FocusedWindow {
         let marks = marks_original.clone();
         let marks_type = block_config.show_marks;
 
-        let _test_conn =
-            Connection::new().block_error("focused_window", "failed to acquire connect to IPC")?;
-
-        thread::Builder::new()
-            .name("focused_window".into())
-            .spawn(move || {
-                for event in Connection::new()
-                    .unwrap()
-                    .subscribe(&[EventType::Window, EventType::Workspace])
-                    .unwrap()
-                {
-                    match event.unwrap() {
-                        Event::Window(e) => {
-                            match e.change {
-                                WindowChange::Focus => {
-                                    if let Some(name) = e.container.name {
-                                        let mut title = title_original.lock().unwrap();
-                                        *title = name;
-                                    }
-
-                                    let mut marks_str = String::from("");
-                                    for mark in e.container.marks {
-                                        match marks_type {
-                                            MarksType::All => {
-                                                marks_str.push_str(&format!("[{}]", mark));
-                                            }
-                                            MarksType::Visible => {
-                                                if !mark.starts_with('_') {
-                                                    marks_str.push_str(&format!("[{}]", mark));
-                                                }
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                    let mut marks = marks_original.lock().unwrap();
-                                    *marks = marks_str;
-
-                                    tx.send(Task {
-                                        id: id_clone.clone(),
-                                        update_time: Instant::now(),
-                                    })
-                                    .unwrap();
-                                }
-                                WindowChange::Title => {
-                                    if e.container.focused {
-                                        if let Some(name) = e.container.name {
-                                            let mut title = title_original.lock().unwrap();
-                                            *title = name;
-                                            tx.send(Task {
-                                                id: id_clone.clone(),
-                                                update_time: Instant::now(),
-                                            })
-                                            .unwrap();
-                                        }
-                                    }
-                                }
-                                WindowChange::Mark => {
-                                    let mut marks_str = String::from("");
-                                    for mark in e.container.marks {
-                                        match marks_type {
-                                            MarksType::All => {
-                                                marks_str.push_str(&format!("[{}]", mark));
-                                            }
-                                            MarksType::Visible => {
-                                                if !mark.starts_with('_') {
-                                                    marks_str.push_str(&format!("[{}]", mark));
-                                                }
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                    let mut marks = marks_original.lock().unwrap();
-                                    *marks = marks_str;
-
-                                    tx.send(Task {
-                                        id: id_clone.clone(),
-                                        update_time: Instant::now(),
-                                    })
-                                    .unwrap();
-                                }
-                                WindowChange::Close => {
-                                    if let Some(name) = e.container.name {
-                                        let mut title = title_original.lock().unwrap();
-                                        if name == *title {
-                                            *title = String::from("");
-                                            tx.send(Task {
-                                                id: id_clone.clone(),
-                                                update_time: Instant::now(),
-                                            })
-                                            .unwrap();
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            };
+        ipc::subscribe(
+            "focused_window",
+            id.clone(),
+            &[EventType::Window, EventType::Workspace],
+            tx,
+            move |event| match event {
+                Event::Window(e) => match e.change {
+                    WindowChange::Focus => {
+                        if let Some(name) = e.container.name {
+                            let mut title = title_original.lock().unwrap();
+                            *title = name;
                         }
-                        Event::Workspace(e) => {
-                            if let WorkspaceChange::Init = e.change {
+
+                        let mut marks = marks_original.lock().unwrap();
+                        *marks = format_marks(e.container.marks, marks_type);
+                        true
+                    }
+                    WindowChange::Title => {
+                        if e.container.focused {
+                            if let Some(name) = e.container.name {
                                 let mut title = title_original.lock().unwrap();
+                                *title = name;
+                                return true;
+                            }
+                        }
+                        false
+                    }
+                    WindowChange::Mark => {
+                        let mut marks = marks_original.lock().unwrap();
+                        *marks = format_marks(e.container.marks, marks_type);
+                        true
+                    }
+                    WindowChange::Close => {
+                        if let Some(name) = e.container.name {
+                            let mut title = title_original.lock().unwrap();
+                            if name == *title {
                                 *title = String::from("");
-                                tx.send(Task {
-                                    id: id_clone.clone(),
-                                    update_time: Instant::now(),
-                                })
-                                .unwrap();
+                                return true;
                             }
                         }
-                        _ => unreachable!(),
+                        false
+                    }
+                    _ => false,
+                },
+                Event::Workspace(e) => {
+                    if let WorkspaceChange::Init = e.change {
+                        let mut title = title_original.lock().unwrap();
+                        *title = String::from("");
+                        true
+                    } else {
+                        false
                     }
                 }
-            })
-            .unwrap();
+                _ => unreachable!(),
+            },
+        )?;
 
         Ok(FocusedWindow {
             id,
@@ -191,6 +133,24 @@ impl ConfigBlock for FocusedWindow {
     }
 }
 
+fn format_marks(marks: Vec<String>, marks_type: MarksType) -> String {
+    let mut marks_str = String::from("");
+    for mark in marks {
+        match marks_type {
+            MarksType::All => {
+                marks_str.push_str(&format!("[{}]", mark));
+            }
+            MarksType::Visible => {
+                if !mark.starts_with('_') {
+                    marks_str.push_str(&format!("[{}]", mark));
+                }
+            }
+            _ => (),
+        }
+    }
+    marks_str
+}
+
 impl Block for FocusedWindow {
     fn update(&mut self) -> Result<Option<Update>> {
         let mut marks_string = (*self
@@ -0,0 +1,221 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use inotify::{Inotify, WatchMask};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+#[derive(Debug, Default, PartialEq)]
+struct GitStatus {
+    branch: String,
+    ahead: u64,
+    behind: u64,
+    dirty: bool,
+}
+
+pub struct Git {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    path: PathBuf,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GitConfig {
+    /// Path of the repository
+    pub path: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "GitConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "GitConfig::default_format")]
+    pub format: String,
+
+    /// Whether to update immediately when `.git/HEAD` changes, e.g. on checkouts
+    #[serde(default = "GitConfig::default_watch_head")]
+    pub watch_head: bool,
+}
+
+impl GitConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{branch}{dirty}".to_owned()
+    }
+
+    fn default_watch_head() -> bool {
+        false
+    }
+}
+
+/// Parses the output of `git status --porcelain=v2 --branch`.
+fn parse_git_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            status.branch = head.to_owned();
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for count in ab.split_whitespace() {
+                if let Some(ahead) = count.strip_prefix('+') {
+                    status.ahead = ahead.parse().unwrap_or(0);
+                } else if let Some(behind) = count.strip_prefix('-') {
+                    status.behind = behind.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') {
+            // Every other line is a changed or untracked file
+            status.dirty = true;
+        }
+    }
+
+    status
+}
+
+impl ConfigBlock for Git {
+    type Config = GitConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let path = PathBuf::from(block_config.path);
+
+        if block_config.watch_head {
+            // HEAD is replaced rather than modified, so the whole directory is watched
+            let git_dir = path.join(".git");
+            let id = id.clone();
+            let mut notify = Inotify::init().block_error("git", "failed to start inotify")?;
+            notify
+                .add_watch(&git_dir, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)
+                .block_error("git", &format!("failed to watch {}", git_dir.display()))?;
+
+            thread::Builder::new()
+                .name("git".into())
+                .spawn(move || {
+                    let mut buffer = [0; 1024];
+                    loop {
+                        let mut events = notify
+                            .read_events_blocking(&mut buffer)
+                            .expect("Error while reading inotify events");
+
+                        if events.any(|event| match event.name {
+                            Some(name) => name == "HEAD",
+                            None => false,
+                        }) {
+                            tx.send(Task {
+                                id: id.clone(),
+                                update_time: Instant::now(),
+                            })
+                            .unwrap();
+                        }
+                    }
+                })
+                .unwrap();
+        }
+
+        Ok(Git {
+            id,
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("git"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("git", "Invalid format specified")?,
+            path,
+        })
+    }
+}
+
+impl Block for Git {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Otherwise `git status` may refresh the index and get in the way of other git processes
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.path)
+            .args([
+                "--no-optional-locks",
+                "status",
+                "--porcelain=v2",
+                "--branch",
+            ])
+            .output()
+            .block_error("git", "failed to run git")?;
+        if !output.status.success() {
+            return Err(BlockError(
+                "git".to_owned(),
+                format!("{} is not a git repository", self.path.display()),
+            ));
+        }
+        let status = parse_git_status(&String::from_utf8_lossy(&output.stdout));
+
+        let values = map!(
+            "{branch}" => status.branch,
+            "{ahead}" => status.ahead.to_string(),
+            "{behind}" => status.behind.to_string(),
+            "{dirty}" => if status.dirty { "*" } else { "" }.to_owned()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(if status.dirty {
+            State::Warning
+        } else {
+            State::Idle
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_status() {
+        let output = "\
+# branch.oid 9487c72d2b1e4e43a7e1e7b0a52f6c1c0f0e2a11
+# branch.head master
+# branch.upstream origin/master
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 3f1e 3f1e src/main.rs
+";
+        assert_eq!(
+            parse_git_status(output),
+            GitStatus {
+                branch: "master".to_owned(),
+                ahead: 2,
+                behind: 1,
+                dirty: true,
+            }
+        );
+
+        let status = parse_git_status("# branch.oid (initial)\n# branch.head main\n");
+        assert_eq!(status.branch, "main");
+        assert!(!status.dirty);
+    }
+}
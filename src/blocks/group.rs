@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use toml::value::Value;
+use uuid::Uuid;
+
+use crate::blocks::{create_block, Block, ConfigBlock, Update};
+use crate::config::{deserialize_blocks, Config};
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::is_clicked;
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+struct Child {
+    block: Box<dyn Block>,
+    /// When the block wants to be updated next, if at all
+    next_update: Option<Instant>,
+}
+
+/// A block that contains other blocks, which are shown together and can be
+/// collapsed behind a single icon.
+pub struct Group {
+    id: String,
+    toggle: ButtonWidget,
+    children: Vec<Child>,
+    collapsed: bool,
+    /// The children that requested an update since the last one
+    requested: Arc<Mutex<HashSet<String>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GroupConfig {
+    /// The blocks in the group, configured like top-level blocks
+    #[serde(deserialize_with = "deserialize_blocks")]
+    pub blocks: Vec<(String, Value)>,
+
+    /// Whether the group starts out collapsed
+    #[serde(default = "GroupConfig::default_collapsed")]
+    pub collapsed: bool,
+
+    /// Name of the icon that toggles the group
+    #[serde(default = "GroupConfig::default_icon")]
+    pub icon: String,
+}
+
+impl GroupConfig {
+    fn default_collapsed() -> bool {
+        false
+    }
+
+    fn default_icon() -> String {
+        "group".to_owned()
+    }
+}
+
+impl ConfigBlock for Group {
+    type Config = GroupConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let requested = Arc::new(Mutex::new(HashSet::new()));
+
+        // The scheduler only knows the group, so update requests of the children
+        // are passed on as requests for the group.
+        let (tx_children, rx_children) = crossbeam_channel::unbounded::<Task>();
+        {
+            let id = id.clone();
+            let requested = requested.clone();
+            thread::Builder::new()
+                .name("group".into())
+                .spawn(move || {
+                    for task in rx_children {
+                        requested.lock().unwrap().insert(task.id);
+                        tx.send(Task {
+                            id: id.clone(),
+                            update_time: task.update_time,
+                        })
+                        .unwrap();
+                    }
+                })
+                .unwrap();
+        }
+
+        let now = Instant::now();
+        let children = block_config
+            .blocks
+            .into_iter()
+            .map(|(name, block)| {
+                Ok(Child {
+                    block: create_block(&name, block, config.clone(), tx_children.clone())?,
+                    next_update: Some(now),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Group {
+            toggle: ButtonWidget::new(config, &id).with_icon(&block_config.icon),
+            id,
+            children,
+            collapsed: block_config.collapsed,
+            requested,
+        })
+    }
+}
+
+impl Block for Group {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let requested: HashSet<String> = self
+            .requested
+            .lock()
+            .block_error("group", "failed to acquire lock")?
+            .drain()
+            .collect();
+        let now = Instant::now();
+
+        for child in &mut self.children {
+            let due = match child.next_update {
+                Some(next_update) => next_update <= now,
+                None => false,
+            };
+            if due || requested.contains(child.block.id()) {
+                child.next_update = match child.block.update()? {
+                    Some(Update::Every(interval)) => Some(now + interval),
+                    Some(Update::Once) | None => None,
+                };
+            }
+        }
+
+        // The group is updated again as soon as any of its children is due
+        Ok(self
+            .children
+            .iter()
+            .filter_map(|child| child.next_update)
+            .min()
+            .map(|next_update| next_update.saturating_duration_since(now).into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        let mut widgets: Vec<&dyn I3BarWidget> = vec![&self.toggle];
+        if !self.collapsed {
+            for child in &self.children {
+                widgets.extend(child.block.view());
+            }
+        }
+        widgets
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) {
+            if event.button == MouseButton::Left {
+                self.collapsed = !self.collapsed;
+            }
+        } else if !self.collapsed {
+            for child in &mut self.children {
+                // Children are updated on clicks just like top-level blocks
+                let update = child.block.update_on_click() && is_clicked(&*child.block, event);
+                child.block.click(event)?;
+                if update {
+                    child.block.update()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::Deserialize;
+
+    #[test]
+    fn test_group() {
+        let block_config: Value = toml::from_str(
+            r#"
+            [[blocks]]
+            block = "time"
+            interval = 10
+
+            [[blocks]]
+            block = "time"
+            interval = 60
+            "#,
+        )
+        .unwrap();
+        let block_config = GroupConfig::deserialize(block_config).unwrap();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut group = Group::new(block_config, Config::default(), tx).unwrap();
+
+        // The group is due again with the child that updates first
+        assert_eq!(
+            group.update().unwrap(),
+            Some(Update::Every(std::time::Duration::from_secs(10)))
+        );
+        assert_eq!(group.view().len(), 3);
+
+        let click = I3BarEvent {
+            name: Some(group.id().to_owned()),
+            instance: None,
+            x: 0,
+            y: 0,
+            relative_x: None,
+            width: None,
+            button: MouseButton::Left,
+            double_click: false,
+        };
+        group.click(&click).unwrap();
+        assert_eq!(group.view().len(), 1);
+        group.click(&click).unwrap();
+        assert_eq!(group.view().len(), 3);
+    }
+}
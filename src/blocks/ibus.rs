@@ -4,13 +4,15 @@ use std::fs::{read_dir, File};
 use std::io::prelude::*;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
+use dbus::blocking::Connection as BlockingConnection;
 use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 use dbus::{
     arg,
     ffidisp::{Connection, ConnectionItem},
+    Message,
 };
 use regex::Regex;
 use serde_derive::Deserialize;
@@ -19,19 +21,40 @@ use uuid::Uuid;
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
 use crate::config::Config;
+use crate::de::deserialize_duration;
 use crate::errors::*;
-use crate::input::I3BarEvent;
+use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::util::{xdg_config_home, FormatTemplate};
 use crate::widget::I3BarWidget;
-use crate::widgets::text::TextWidget;
+use crate::widgets::button::ButtonWidget;
+
+/// The input method framework whose engine is shown.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IBusBackend {
+    #[default]
+    IBus,
+    Fcitx5,
+}
+
+/// The connection the engine is switched through.
+enum EngineConnection {
+    /// The private bus of the IBus daemon
+    IBus(Connection),
+    /// The session bus, where fcitx5 offers its controller
+    Fcitx5(BlockingConnection),
+}
 
 pub struct IBus {
     id: String,
-    text: TextWidget,
+    text: ButtonWidget,
     engine: Arc<Mutex<String>>,
     mappings: Option<BTreeMap<String, String>>,
     format: FormatTemplate,
+    connection: EngineConnection,
+    engines: Vec<String>,
+    update_interval: Duration,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -42,6 +65,21 @@ pub struct IBusConfig {
 
     #[serde(default = "IBusConfig::default_format")]
     pub format: String,
+
+    /// The input method framework, one of "ibus" or "fcitx5"
+    #[serde(default)]
+    pub backend: IBusBackend,
+
+    /// The engines cycled through on click
+    #[serde(default)]
+    pub engines: Vec<String>,
+
+    /// How often the engine of fcitx5 is looked up, as it does not signal changes
+    #[serde(
+        default = "IBusConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
 }
 
 impl IBusConfig {
@@ -52,6 +90,30 @@ impl IBusConfig {
     fn default_format() -> String {
         "{engine}".into()
     }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(1)
+    }
+}
+
+/// The engine after `current` in `engines`, or the first one if `current` is not among them.
+fn next_engine<'a>(engines: &'a [String], current: &str) -> Option<&'a String> {
+    let next = match engines.iter().position(|engine| engine == current) {
+        Some(position) => position + 1,
+        None => 0,
+    };
+    engines.get(next % engines.len().max(1))
+}
+
+fn fcitx5_call<R: arg::ReadAll, A: arg::AppendAll>(
+    connection: &BlockingConnection,
+    method: &str,
+    args: A,
+) -> Result<R> {
+    connection
+        .with_proxy("org.fcitx.Fcitx5", "/controller", Duration::from_secs(2))
+        .method_call("org.fcitx.Fcitx.Controller1", method, args)
+        .block_error("ibus", &format!("Failed to call {} of fcitx5", method))
 }
 
 impl ConfigBlock for IBus {
@@ -59,69 +121,90 @@ impl ConfigBlock for IBus {
 
     fn new(block_config: Self::Config, config: Config, send: Sender<Task>) -> Result<Self> {
         let id: String = Uuid::new_v4().to_simple().to_string();
-        let id_copy = id.clone();
 
-        let ibus_address = get_ibus_address()?;
-        let c = Connection::open_private(&ibus_address).block_error(
-            "ibus",
-            &format!("Failed to establish D-Bus connection to {}", ibus_address),
-        )?;
-        let p = c.with_path("org.freedesktop.IBus", "/org/freedesktop/IBus", 5000);
-        let info: arg::Variant<Box<dyn arg::RefArg>> = p
-            .get("org.freedesktop.IBus", "GlobalEngine")
-            .block_error("ibus", "Failed to query IBus")?;
-
-        // `info` should contain something containing an array with the contents as such:
-        // [name, longname, description, language, license, author, icon, layout, layout_variant, layout_option, rank, hotkeys, symbol, setup, version, textdomain, icon_prop_key]
-        // Refer to: https://github.com/ibus/ibus/blob/7cef5bf572596361bc502e8fa917569676a80372/src/ibusenginedesc.c
-        // e.g.                   name           longname        description     language
-        // ["IBusEngineDesc", {}, "xkb:us::eng", "English (US)", "English (US)", "en", "GPL", "Peng Huang <shawn.p.huang@gmail.com>", "ibus-keyboard", "us", 99, "", "", "", "", "", "", "", ""]
-        //                         ↑ We will use this element (name) as it is what GlobalEngineChanged signal returns.
-        let current_engine = info
-            .0
-            .as_iter()
-            .block_error("ibus", "Failed to parse D-Bus message (step 1)")?
-            .nth(2)
-            .block_error("ibus", "Failed to parse D-Bus message (step 2)")?
-            .as_str()
-            .unwrap_or("??");
-
-        let engine_original = Arc::new(Mutex::new(String::from(current_engine)));
-        let engine = engine_original.clone();
-        thread::Builder::new()
-            .name("ibus".into())
-            .spawn(move || {
-                let c = Connection::open_private(&ibus_address)
-                    .expect("Failed to establish D-Bus connection in thread");
-                c.add_match("interface='org.freedesktop.IBus',member='GlobalEngineChanged'")
-                    .expect("Failed to add D-Bus message rule - has IBus interface changed?");
-                loop {
-                    for ci in c.iter(100_000) {
-                        if let Some(engine_name) = parse_msg(&ci) {
-                            let mut engine = engine_original.lock().unwrap();
-                            *engine = engine_name.to_string();
-                            // Tell block to update now.
-                            send.send(Task {
-                                id: id.clone(),
-                                update_time: Instant::now(),
-                            })
-                            .unwrap();
-                        };
-                    }
-                }
-            })
-            .unwrap();
+        let (connection, engine) = match block_config.backend {
+            IBusBackend::IBus => {
+                let (connection, engine) = watch_ibus(id.clone(), send)?;
+                (EngineConnection::IBus(connection), engine)
+            }
+            IBusBackend::Fcitx5 => {
+                let connection = BlockingConnection::new_session()
+                    .block_error("ibus", "Failed to establish D-Bus connection")?;
+                // Updated on every update, as there is no signal for it
+                let engine = Arc::new(Mutex::new(String::new()));
+                (EngineConnection::Fcitx5(connection), engine)
+            }
+        };
 
         Ok(IBus {
-            id: id_copy,
-            text: TextWidget::new(config).with_text("IBus"),
+            text: ButtonWidget::new(config, &id).with_text("IBus"),
+            id,
             engine,
             mappings: block_config.mappings,
             format: FormatTemplate::from_string(&block_config.format)?,
+            connection,
+            engines: block_config.engines,
+            update_interval: block_config.interval,
         })
     }
 }
 
+/// Queries the global engine of IBus and keeps following it in the background.
+fn watch_ibus(id: String, send: Sender<Task>) -> Result<(Connection, Arc<Mutex<String>>)> {
+    let ibus_address = get_ibus_address()?;
+    let c = Connection::open_private(&ibus_address).block_error(
+        "ibus",
+        &format!("Failed to establish D-Bus connection to {}", ibus_address),
+    )?;
+    let p = c.with_path("org.freedesktop.IBus", "/org/freedesktop/IBus", 5000);
+    let info: arg::Variant<Box<dyn arg::RefArg>> = p
+        .get("org.freedesktop.IBus", "GlobalEngine")
+        .block_error("ibus", "Failed to query IBus")?;
+
+    // `info` should contain something containing an array with the contents as such:
+    // [name, longname, description, language, license, author, icon, layout, layout_variant, layout_option, rank, hotkeys, symbol, setup, version, textdomain, icon_prop_key]
+    // Refer to: https://github.com/ibus/ibus/blob/7cef5bf572596361bc502e8fa917569676a80372/src/ibusenginedesc.c
+    // e.g.                   name           longname        description     language
+    // ["IBusEngineDesc", {}, "xkb:us::eng", "English (US)", "English (US)", "en", "GPL", "Peng Huang <shawn.p.huang@gmail.com>", "ibus-keyboard", "us", 99, "", "", "", "", "", "", "", ""]
+    //                         ↑ We will use this element (name) as it is what GlobalEngineChanged signal returns.
+    let current_engine = info
+        .0
+        .as_iter()
+        .block_error("ibus", "Failed to parse D-Bus message (step 1)")?
+        .nth(2)
+        .block_error("ibus", "Failed to parse D-Bus message (step 2)")?
+        .as_str()
+        .unwrap_or("??");
+
+    let engine_original = Arc::new(Mutex::new(String::from(current_engine)));
+    let engine = engine_original.clone();
+    thread::Builder::new()
+        .name("ibus".into())
+        .spawn(move || {
+            let c = Connection::open_private(&ibus_address)
+                .expect("Failed to establish D-Bus connection in thread");
+            c.add_match("interface='org.freedesktop.IBus',member='GlobalEngineChanged'")
+                .expect("Failed to add D-Bus message rule - has IBus interface changed?");
+            loop {
+                for ci in c.iter(100_000) {
+                    if let Some(engine_name) = parse_msg(&ci) {
+                        let mut engine = engine_original.lock().unwrap();
+                        *engine = engine_name.to_string();
+                        // Tell block to update now.
+                        send.send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                    };
+                }
+            }
+        })
+        .unwrap();
+
+    Ok((c, engine))
+}
+
 impl Block for IBus {
     fn id(&self) -> &str {
         &self.id
@@ -129,6 +212,14 @@ impl Block for IBus {
 
     // Updates the internal state of the block.
     fn update(&mut self) -> Result<Option<Update>> {
+        if let EngineConnection::Fcitx5(ref connection) = self.connection {
+            let (engine,): (String,) = fcitx5_call(connection, "CurrentInputMethod", ())?;
+            *self
+                .engine
+                .lock()
+                .block_error("ibus", "failed to acquire lock")? = engine;
+        }
+
         let engine = (*self
             .engine
             .lock()
@@ -148,7 +239,10 @@ impl Block for IBus {
         );
 
         self.text.set_text(self.format.render_static_str(&values)?);
-        Ok(None)
+        match self.connection {
+            EngineConnection::IBus(_) => Ok(None),
+            EngineConnection::Fcitx5(_) => Ok(Some(self.update_interval.into())),
+        }
     }
 
     // Returns the view of the block, comprised of widgets.
@@ -156,10 +250,42 @@ impl Block for IBus {
         vec![&self.text]
     }
 
-    // This function is called on every block for every click.
-    // TODO: Filter events by using the event.name property,
-    // and use to switch between input engines?
-    fn click(&mut self, _: &I3BarEvent) -> Result<()> {
+    // Switches to the next of the configured engines.
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) || event.button != MouseButton::Left {
+            return Ok(());
+        }
+        let current = self
+            .engine
+            .lock()
+            .block_error("ibus", "failed to acquire lock")?
+            .clone();
+        match (&self.connection, next_engine(&self.engines, &current)) {
+            (EngineConnection::IBus(connection), Some(engine)) => {
+                let message = Message::new_method_call(
+                    "org.freedesktop.IBus",
+                    "/org/freedesktop/IBus",
+                    "org.freedesktop.IBus",
+                    "SetGlobalEngine",
+                )
+                .block_error("ibus", "Failed to create D-Bus message")?
+                .append1(engine);
+                // The block is updated by the GlobalEngineChanged signal
+                connection
+                    .send_with_reply_and_block(message, 2000)
+                    .block_error("ibus", &format!("Failed to switch to {}", engine))?;
+            }
+            (EngineConnection::IBus(_), None) => (),
+            (EngineConnection::Fcitx5(connection), Some(engine)) => {
+                fcitx5_call::<(), _>(connection, "SetCurrentIM", (engine.as_str(),))?;
+                self.update()?;
+            }
+            // Without configured engines, fcitx5 switches between its active and inactive one
+            (EngineConnection::Fcitx5(connection), None) => {
+                fcitx5_call::<(), _>(connection, "Toggle", ())?;
+                self.update()?;
+            }
+        }
         Ok(())
     }
 }
@@ -263,3 +389,17 @@ fn get_ibus_address() -> Result<String> {
 
     Ok(cap[1].to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_engine() {
+        let engines = vec!["xkb:us::eng".to_owned(), "mozc-jp".to_owned()];
+        assert_eq!(next_engine(&engines, "xkb:us::eng"), Some(&engines[1]));
+        assert_eq!(next_engine(&engines, "mozc-jp"), Some(&engines[0]));
+        assert_eq!(next_engine(&engines, "pinyin"), Some(&engines[0]));
+        assert_eq!(next_engine(&[], "pinyin"), None);
+    }
+}
@@ -0,0 +1,263 @@
+use std::env;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use dbus::arg::OwnedFd;
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+use dbus::blocking::Connection;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+const LOGIND: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+
+/// How idle is inhibited.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleInhibitorBackend {
+    /// An idle inhibitor of systemd-logind, which swayidle and other idle daemons respect
+    Logind,
+    /// The screensaver and DPMS of the X server, switched with `xset`
+    X,
+}
+
+/// The state of a backend.
+enum Inhibitor {
+    Logind {
+        connection: Connection,
+        /// The inhibitor taken on click, which lasts until this file descriptor is closed
+        inhibitor: Option<OwnedFd>,
+    },
+    X,
+}
+
+/// Whether `idle` is among the inhibited operations in `BlockInhibited`, which are separated
+/// by colons like `handle-lid-switch:idle:sleep`.
+fn is_idle_inhibited(block_inhibited: &str) -> bool {
+    block_inhibited.split(':').any(|what| what == "idle")
+}
+
+/// Whether both the screensaver and DPMS are off in the output of `xset q`, which contains
+/// e.g. `timeout:  600    cycle:  600` for the screensaver and `DPMS is Enabled`. Servers
+/// without the DPMS extension only have the screensaver.
+fn is_x_idle_inhibited(output: &str) -> Option<bool> {
+    let timeout: u32 = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("timeout:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    Some(timeout == 0 && !output.contains("DPMS is Enabled"))
+}
+
+fn xset(args: &[&str]) -> Result<String> {
+    let output = Command::new("xset")
+        .args(args)
+        .output()
+        .block_error("idle_inhibitor", "failed to run xset")?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "idle_inhibitor".to_owned(),
+            format!("`xset {}` failed", args.join(" ")),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub struct IdleInhibitor {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    inhibitor: Inhibitor,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct IdleInhibitorConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "IdleInhibitorConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "IdleInhibitorConfig::default_format")]
+    pub format: String,
+
+    /// How idle is inhibited, one of "logind" or "x". By default the X server is used if
+    /// `xset` can reach one outside of a Wayland session, and logind otherwise.
+    #[serde(default = "IdleInhibitorConfig::default_backend")]
+    pub backend: Option<IdleInhibitorBackend>,
+}
+
+impl IdleInhibitorConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "".to_owned()
+    }
+
+    fn default_backend() -> Option<IdleInhibitorBackend> {
+        None
+    }
+}
+
+impl ConfigBlock for IdleInhibitor {
+    type Config = IdleInhibitorConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let backend = block_config.backend.unwrap_or_else(|| {
+            if env::var_os("WAYLAND_DISPLAY").is_none() && xset(&["q"]).is_ok() {
+                IdleInhibitorBackend::X
+            } else {
+                IdleInhibitorBackend::Logind
+            }
+        });
+        let inhibitor = match backend {
+            IdleInhibitorBackend::Logind => Inhibitor::Logind {
+                connection: Connection::new_system()
+                    .block_error("idle_inhibitor", "Failed to establish D-Bus connection")?,
+                inhibitor: None,
+            },
+            IdleInhibitorBackend::X => Inhibitor::X,
+        };
+        Ok(IdleInhibitor {
+            output: ButtonWidget::new(config, &id).with_icon("idle_inhibitor_off"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("idle_inhibitor", "Invalid format specified")?,
+            inhibitor,
+        })
+    }
+}
+
+impl Block for IdleInhibitor {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let inhibited = match self.inhibitor {
+            Inhibitor::Logind {
+                ref connection,
+                ref inhibitor,
+            } => {
+                // Includes the inhibitors of other programs, e.g. of `systemd-inhibit --what=idle`
+                let block_inhibited: String = connection
+                    .with_proxy(LOGIND, MANAGER_PATH, Duration::from_secs(5))
+                    .get(MANAGER_INTERFACE, "BlockInhibited")
+                    .block_error("idle_inhibitor", "failed to get the inhibitors from logind")?;
+                inhibitor.is_some() || is_idle_inhibited(&block_inhibited)
+            }
+            Inhibitor::X => is_x_idle_inhibited(&xset(&["q"])?)
+                .block_error("idle_inhibitor", "failed to parse the output of xset")?,
+        };
+
+        let values = map!(
+            "{state}" => if inhibited { "on" } else { "off" }.to_owned()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        if inhibited {
+            self.output.set_icon("idle_inhibitor_on");
+            self.output.set_state(State::Info);
+        } else {
+            self.output.set_icon("idle_inhibitor_off");
+            self.output.set_state(State::Idle);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) || event.button != MouseButton::Left {
+            return Ok(());
+        }
+        match self.inhibitor {
+            Inhibitor::Logind {
+                ref connection,
+                ref mut inhibitor,
+            } => {
+                if inhibitor.take().is_none() {
+                    let (fd,): (OwnedFd,) = connection
+                        .with_proxy(LOGIND, MANAGER_PATH, Duration::from_secs(5))
+                        .method_call(
+                            MANAGER_INTERFACE,
+                            "Inhibit",
+                            ("idle", "i3status-rs", "Inhibited from the bar", "block"),
+                        )
+                        .block_error("idle_inhibitor", "failed to inhibit idle")?;
+                    *inhibitor = Some(fd);
+                }
+            }
+            Inhibitor::X => {
+                let inhibited = is_x_idle_inhibited(&xset(&["q"])?).unwrap_or(false);
+                if inhibited {
+                    xset(&["s", "on", "+dpms"])?;
+                } else {
+                    xset(&["s", "off", "-dpms"])?;
+                }
+            }
+        }
+        self.update()?;
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_idle_inhibited() {
+        assert!(is_idle_inhibited("handle-lid-switch:idle:sleep"));
+        assert!(is_idle_inhibited("idle"));
+        assert!(!is_idle_inhibited("sleep:handle-power-key"));
+        assert!(!is_idle_inhibited(""));
+    }
+
+    #[test]
+    fn test_is_x_idle_inhibited() {
+        let output = "\
+Keyboard Control:
+  auto repeat:  on    key click percent:  0    LED mask:  00000000
+Screen Saver:
+  prefer blanking:  yes    allow exposures:  yes
+  timeout:  600    cycle:  600
+DPMS (Energy Star):
+  Standby: 600    Suspend: 600    Off: 600
+  DPMS is Enabled
+  Monitor is On
+";
+        assert_eq!(is_x_idle_inhibited(output), Some(false));
+        let output = output
+            .replace("timeout:  600", "timeout:  0")
+            .replace("DPMS is Enabled", "DPMS is Disabled");
+        assert_eq!(is_x_idle_inhibited(&output), Some(true));
+        assert_eq!(is_x_idle_inhibited("Screen Saver:\n  timeout:  0    cycle:  600\n  Server does not have the DPMS Extension\n"), Some(true));
+        assert_eq!(is_x_idle_inhibited(""), None);
+    }
+}
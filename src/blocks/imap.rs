@@ -0,0 +1,464 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::{Backoff, Task};
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// How long to wait before connecting again after the connection was lost
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// How long to wait for the server to respond to a command
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to stay in IDLE, as servers may drop connections that are idle for 30 minutes
+const IDLE_TIMEOUT: Duration = Duration::from_secs(25 * 60);
+/// How often to check for new mail if the server does not support IDLE
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct Imap {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    threshold_warning: u64,
+    threshold_critical: u64,
+    /// The number of unread mails, if connected
+    unread: Arc<Mutex<Option<u64>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ImapConfig {
+    /// The IMAP server
+    pub host: String,
+
+    /// The port of the server
+    #[serde(default = "ImapConfig::default_port")]
+    pub port: u16,
+
+    /// Whether to connect with TLS, which is done by `openssl s_client`
+    #[serde(default = "ImapConfig::default_tls")]
+    pub tls: bool,
+
+    /// The user to log in as
+    pub user: String,
+
+    /// The password to log in with
+    #[serde(default = "ImapConfig::default_password")]
+    pub password: Option<String>,
+
+    /// Shell command that prints the password, instead of `password`
+    #[serde(default = "ImapConfig::default_password_command")]
+    pub password_command: Option<String>,
+
+    /// The mailbox to count the unread mails of
+    #[serde(default = "ImapConfig::default_mailbox")]
+    pub mailbox: String,
+
+    /// Number of unread mails from which the block is in the warning state
+    #[serde(default = "ImapConfig::default_threshold_warning")]
+    pub threshold_warning: u64,
+
+    /// Number of unread mails from which the block is in the critical state
+    #[serde(default = "ImapConfig::default_threshold_critical")]
+    pub threshold_critical: u64,
+
+    /// Format override
+    #[serde(default = "ImapConfig::default_format")]
+    pub format: String,
+}
+
+impl ImapConfig {
+    fn default_port() -> u16 {
+        993
+    }
+
+    fn default_tls() -> bool {
+        true
+    }
+
+    fn default_password() -> Option<String> {
+        None
+    }
+
+    fn default_password_command() -> Option<String> {
+        None
+    }
+
+    fn default_mailbox() -> String {
+        "INBOX".to_owned()
+    }
+
+    fn default_threshold_warning() -> u64 {
+        1
+    }
+
+    fn default_threshold_critical() -> u64 {
+        10
+    }
+
+    fn default_format() -> String {
+        "{unread}".to_owned()
+    }
+
+    fn password(&self) -> Result<String> {
+        match (&self.password, &self.password_command) {
+            (Some(password), _) => Ok(password.clone()),
+            (None, Some(command)) => {
+                let output = Command::new("sh")
+                    .args(["-c", command])
+                    .output()
+                    .block_error("imap", "failed to run password_command")?;
+                if !output.status.success() {
+                    return Err(BlockError(
+                        "imap".to_owned(),
+                        "password_command failed".to_owned(),
+                    ));
+                }
+                Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+            }
+            (None, None) => Err(BlockError(
+                "imap".to_owned(),
+                "either password or password_command is required".to_owned(),
+            )),
+        }
+    }
+}
+
+/// Quotes a string for IMAP commands.
+fn quote(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Counts the messages in the untagged responses to `SEARCH`, e.g. `* SEARCH 2 5 8`.
+fn count_search(responses: &[String]) -> u64 {
+    responses
+        .iter()
+        .filter_map(|line| line.strip_prefix("* SEARCH"))
+        .map(|ids| ids.split_whitespace().count() as u64)
+        .sum()
+}
+
+/// Whether an untagged response during IDLE means that the mails changed, e.g.
+/// `* 23 EXISTS` for a new mail or `* 4 FETCH (FLAGS (\Seen))` for one that was read.
+fn is_mailbox_change(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    words.next() == Some("*")
+        && matches!(words.next().map(str::parse::<u64>), Some(Ok(_)))
+        && matches!(
+            words.next(),
+            Some("EXISTS") | Some("EXPUNGE") | Some("FETCH")
+        )
+}
+
+/// A connection to an IMAP server, whose lines are read by a separate thread so that
+/// waiting for them can time out.
+struct Connection {
+    lines: Receiver<String>,
+    writer: Box<dyn Write + Send>,
+    /// The TLS client, if the connection goes through one
+    child: Option<Child>,
+    /// The socket otherwise, which is shut down on drop so that the reading thread,
+    /// which holds a clone of it, ends as well
+    socket: Option<TcpStream>,
+    tag: u32,
+}
+
+impl Connection {
+    fn connect(host: &str, port: u16, tls: bool) -> Result<Connection> {
+        let address = format!("{}:{}", host, port);
+        let (reader, writer, child, socket): (Box<dyn Read + Send>, Box<dyn Write + Send>, _, _) =
+            if tls {
+                let mut child = Command::new("openssl")
+                    .args([
+                        "s_client",
+                        "-quiet",
+                        "-verify_return_error",
+                        "-verify_hostname",
+                        host,
+                        "-connect",
+                        &address,
+                        "-servername",
+                        host,
+                    ])
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .block_error("imap", "failed to run openssl")?;
+                (
+                    Box::new(child.stdout.take().unwrap()),
+                    Box::new(child.stdin.take().unwrap()),
+                    Some(child),
+                    None,
+                )
+            } else {
+                let stream = TcpStream::connect(&address)
+                    .block_error("imap", &format!("failed to connect to {}", address))?;
+                let reader = stream
+                    .try_clone()
+                    .block_error("imap", "failed to clone socket")?;
+                let socket = stream
+                    .try_clone()
+                    .block_error("imap", "failed to clone socket")?;
+                (Box::new(reader), Box::new(stream), None, Some(socket))
+            };
+
+        let (tx_lines, lines) = crossbeam_channel::unbounded();
+        thread::Builder::new()
+            .name("imap".into())
+            .spawn(move || {
+                for line in BufReader::new(reader).lines() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(_) => break,
+                    };
+                    if tx_lines.send(line).is_err() {
+                        break;
+                    }
+                }
+            })
+            .unwrap();
+
+        let connection = Connection {
+            lines,
+            writer,
+            child,
+            socket,
+            tag: 0,
+        };
+        match connection.read_line(RESPONSE_TIMEOUT)? {
+            Some(greeting) if greeting.starts_with("* OK") => Ok(connection),
+            _ => Err(BlockError(
+                "imap".to_owned(),
+                format!("{} is not an IMAP server", address),
+            )),
+        }
+    }
+
+    /// Waits for the next line, or returns `None` on a timeout.
+    fn read_line(&self, timeout: Duration) -> Result<Option<String>> {
+        match self.lines.recv_timeout(timeout) {
+            Ok(line) => Ok(Some(line)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(BlockError(
+                "imap".to_owned(),
+                "connection closed by the server".to_owned(),
+            )),
+        }
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        self.writer
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .and_then(|_| self.writer.flush())
+            .block_error("imap", "failed to write to the server")
+    }
+
+    /// Sends a command with a new tag and returns it.
+    fn send(&mut self, command: &str) -> Result<String> {
+        self.tag += 1;
+        let tag = format!("a{}", self.tag);
+        self.write_line(&format!("{} {}", tag, command))?;
+        Ok(tag)
+    }
+
+    /// Reads the untagged responses up to the completion of the command `tag`.
+    fn read_response(&mut self, tag: &str) -> Result<Vec<String>> {
+        let mut responses = Vec::new();
+        loop {
+            let line = self
+                .read_line(RESPONSE_TIMEOUT)?
+                .block_error("imap", "timed out waiting for the server")?;
+            if let Some(status) = line.strip_prefix(&format!("{} ", tag)) {
+                if status.starts_with("OK") {
+                    return Ok(responses);
+                }
+                return Err(BlockError("imap".to_owned(), status.to_owned()));
+            }
+            responses.push(line);
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<Vec<String>> {
+        let tag = self.send(command)?;
+        self.read_response(&tag)
+    }
+
+    /// Waits until the mails in the selected mailbox change or IDLE times out. Returns
+    /// `false` if the server does not support IDLE.
+    fn idle(&mut self) -> Result<bool> {
+        let tag = self.send("IDLE")?;
+        match self.read_line(RESPONSE_TIMEOUT)? {
+            Some(ref line) if line.starts_with('+') => {}
+            Some(ref line) if line.starts_with(&tag) => return Ok(false),
+            _ => {
+                return Err(BlockError(
+                    "imap".to_owned(),
+                    "unexpected response to IDLE".to_owned(),
+                ))
+            }
+        }
+
+        let start = Instant::now();
+        while let Some(timeout) = IDLE_TIMEOUT.checked_sub(start.elapsed()) {
+            match self.read_line(timeout)? {
+                Some(ref line) if is_mailbox_change(line) => break,
+                Some(_) => {}
+                None => break,
+            }
+        }
+        self.write_line("DONE")?;
+        self.read_response(&tag)?;
+        Ok(true)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Some(ref mut child) = self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(ref socket) = self.socket {
+            let _ = socket.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Logs in and keeps `unread` up to date until the connection fails.
+fn watch(config: &ImapConfig, unread: &Mutex<Option<u64>>, notify: &dyn Fn()) -> Result<()> {
+    let mut connection = Connection::connect(&config.host, config.port, config.tls)?;
+    connection.command(&format!(
+        "LOGIN {} {}",
+        quote(&config.user),
+        quote(&config.password()?)
+    ))?;
+    // Unlike SELECT, EXAMINE does not mark mails as no longer recent
+    connection.command(&format!("EXAMINE {}", quote(&config.mailbox)))?;
+
+    loop {
+        let count = count_search(&connection.command("SEARCH UNSEEN")?);
+        let changed = unread.lock().unwrap().replace(count) != Some(count);
+        if changed {
+            notify();
+        }
+        if !connection.idle()? {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl ConfigBlock for Imap {
+    type Config = ImapConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let unread = Arc::new(Mutex::new(None));
+
+        {
+            let id = id.clone();
+            let unread = unread.clone();
+            let block_config = block_config.clone();
+            thread::Builder::new()
+                .name("imap".into())
+                .spawn(move || {
+                    let notify = || {
+                        tx.send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                    };
+                    // Wrong credentials fail every time, so the attempts are spread out
+                    let mut backoff = Backoff::new(RECONNECT_DELAY);
+                    loop {
+                        let _ = watch(&block_config, &unread, &notify);
+                        let delay = match unread.lock().unwrap().take() {
+                            Some(_) => backoff.success(),
+                            None => backoff.failure(),
+                        };
+                        notify();
+                        thread::sleep(delay);
+                    }
+                })
+                .unwrap();
+        }
+
+        Ok(Imap {
+            id,
+            text: TextWidget::new(config).with_icon("mail"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("imap", "Invalid format specified")?,
+            threshold_warning: block_config.threshold_warning,
+            threshold_critical: block_config.threshold_critical,
+            unread,
+        })
+    }
+}
+
+impl Block for Imap {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let unread = *self
+            .unread
+            .lock()
+            .block_error("imap", "failed to acquire lock")?;
+
+        match unread {
+            Some(unread) => {
+                self.text.set_state(if unread >= self.threshold_critical {
+                    State::Critical
+                } else if unread >= self.threshold_warning {
+                    State::Warning
+                } else {
+                    State::Idle
+                });
+                let values = map!("{unread}" => unread.to_string());
+                self.text.set_text(self.format.render_static_str(&values)?);
+            }
+            None => {
+                self.text.set_state(State::Idle);
+                self.text.set_text("×".to_owned());
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_responses() {
+        assert_eq!(quote(r#"pa"ss\word"#), r#""pa\"ss\\word""#);
+
+        let responses = vec!["* SEARCH 2 5 8".to_owned()];
+        assert_eq!(count_search(&responses), 3);
+        assert_eq!(count_search(&["* SEARCH".to_owned()]), 0);
+
+        assert!(is_mailbox_change("* 23 EXISTS"));
+        assert!(is_mailbox_change("* 4 FETCH (FLAGS (\\Seen))"));
+        assert!(!is_mailbox_change("* OK Still here"));
+        assert!(!is_mailbox_change("* 3 RECENT"));
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::backlight::BacklitDevice;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::{Config, LogicalDirection, Scrolling};
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+pub struct KbdBacklight {
+    id: String,
+    output: ButtonWidget,
+    device: BacklitDevice,
+    update_interval: Duration,
+    format: FormatTemplate,
+    scrolling: Scrolling,
+    /// The level to restore when the backlight is switched on again by a click
+    last_level: u64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KbdBacklightConfig {
+    /// The keyboard backlight in `/sys/class/leds/`, or the first one found if not set
+    #[serde(default = "KbdBacklightConfig::default_device")]
+    pub device: Option<String>,
+
+    /// Update interval in seconds, as changes by the keyboard itself are not notified
+    #[serde(
+        default = "KbdBacklightConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "KbdBacklightConfig::default_format")]
+    pub format: String,
+}
+
+impl KbdBacklightConfig {
+    fn default_device() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{brightness}%".to_owned()
+    }
+}
+
+/// The level one step up or down from `level`, within `0..=max`.
+fn step_level(level: u64, max: u64, direction: LogicalDirection) -> u64 {
+    match direction {
+        LogicalDirection::Up => (level + 1).min(max),
+        LogicalDirection::Down => level.saturating_sub(1),
+    }
+}
+
+impl ConfigBlock for KbdBacklight {
+    type Config = KbdBacklightConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let device = BacklitDevice::keyboard(block_config.device)?;
+        let last_level = device.max_brightness();
+
+        Ok(KbdBacklight {
+            scrolling: config.scrolling,
+            output: ButtonWidget::new(config, &id).with_icon("kbd_backlight"),
+            id,
+            device,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("kbd_backlight", "Invalid format specified")?,
+            last_level,
+        })
+    }
+}
+
+impl Block for KbdBacklight {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let level = self.device.raw_brightness()?;
+        let max = self.device.max_brightness();
+        if level > 0 {
+            self.last_level = level;
+        }
+
+        let brightness = (100.0 * level as f64 / max.max(1) as f64).round() as u64;
+        let values = map!(
+            "{brightness}" => brightness.to_string(),
+            "{level}" => level.to_string(),
+            "{max}" => max.to_string()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) {
+            return Ok(());
+        }
+
+        let level = self.device.raw_brightness()?;
+        let max = self.device.max_brightness();
+        let new_level = match event.button {
+            // Switches the backlight off, or back on at the level it had
+            MouseButton::Left if level > 0 => 0,
+            MouseButton::Left => self.last_level,
+            button => match self.scrolling.to_logical_direction(button) {
+                Some(direction) => step_level(level, max, direction),
+                None => return Ok(()),
+            },
+        };
+        if new_level != level {
+            self.device.set_raw_brightness(new_level)?;
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_level() {
+        assert_eq!(step_level(1, 3, LogicalDirection::Up), 2);
+        assert_eq!(step_level(3, 3, LogicalDirection::Up), 3);
+        assert_eq!(step_level(1, 3, LogicalDirection::Down), 0);
+        assert_eq!(step_level(0, 3, LogicalDirection::Down), 0);
+    }
+}
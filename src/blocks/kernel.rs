@@ -0,0 +1,172 @@
+use std::cmp::Ordering;
+use std::fs::{read_dir, read_to_string};
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Kernel {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    installed_command: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KernelConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "KernelConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "KernelConfig::default_format")]
+    pub format: String,
+
+    /// Shell command that prints the installed kernel version, in the format of `uname -r`
+    #[serde(default = "KernelConfig::default_installed_command")]
+    pub installed_command: Option<String>,
+}
+
+impl KernelConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn default_format() -> String {
+        "{running}".to_owned()
+    }
+
+    fn default_installed_command() -> Option<String> {
+        None
+    }
+}
+
+/// Compares kernel versions like `5.9.10-arch1-1`, with numbers compared by value.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |version: &str| -> Vec<String> {
+        let mut parts: Vec<String> = Vec::new();
+        for c in version.chars() {
+            match parts.last_mut() {
+                Some(part) if part.chars().all(|p| p.is_ascii_digit()) == c.is_ascii_digit() => {
+                    part.push(c)
+                }
+                _ => parts.push(c.to_string()),
+            }
+        }
+        parts
+    };
+    for (a, b) in split(a).iter().zip(split(b).iter()) {
+        let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+impl Kernel {
+    /// The newest kernel that has modules installed, which is the one the next boot picks
+    /// up on most distributions.
+    fn installed(&self) -> Result<String> {
+        if let Some(ref command) = self.installed_command {
+            let output = Command::new("sh")
+                .args(["-c", command])
+                .output()
+                .block_error("kernel", "failed to run installed_command")?;
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned());
+        }
+
+        let mut versions: Vec<String> = read_dir("/usr/lib/modules")
+            .block_error("kernel", "failed to read /usr/lib/modules")?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().join("modules.dep").exists())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions
+            .pop()
+            .block_error("kernel", "no installed kernel found")
+    }
+}
+
+impl ConfigBlock for Kernel {
+    type Config = KernelConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Kernel {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("kernel"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("kernel", "Invalid format specified")?,
+            installed_command: block_config.installed_command,
+        })
+    }
+}
+
+impl Block for Kernel {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // The same as `uname -r`
+        let running = read_to_string("/proc/sys/kernel/osrelease")
+            .block_error("kernel", "failed to read the kernel release")?
+            .trim()
+            .to_owned();
+        let installed = self.installed()?;
+
+        self.text.set_state(if installed == running {
+            State::Idle
+        } else {
+            State::Warning
+        });
+        let values = map!("{running}" => running, "{installed}" => installed);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(
+            compare_versions("5.10.1-arch1-1", "5.9.14-arch1-1"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_versions("5.9.14-arch1-1", "5.9.14-arch2-1"),
+            Ordering::Less
+        );
+        assert_eq!(compare_versions("5.9.14", "5.9.14"), Ordering::Equal);
+        assert_eq!(compare_versions("5.9", "5.9.1"), Ordering::Less);
+    }
+}
@@ -0,0 +1,203 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+#[derive(Debug, PartialEq)]
+struct RepeatSettings {
+    enabled: bool,
+    /// Milliseconds until a held key starts repeating
+    delay: u32,
+    /// Repeats per second
+    rate: u32,
+}
+
+impl RepeatSettings {
+    /// Parses the output of `xset q`, which contains e.g. `auto repeat:  on` and
+    /// `auto repeat delay:  660    repeat rate:  25` among the keyboard settings.
+    fn parse(output: &str) -> Option<RepeatSettings> {
+        let mut enabled = None;
+        let mut delay = None;
+        let mut rate = None;
+        for line in output.lines().map(str::trim) {
+            if let Some(rest) = line.strip_prefix("auto repeat delay:") {
+                let fields: Vec<&str> = rest.split_whitespace().collect();
+                if let [delay_value, "repeat", "rate:", rate_value] = fields[..] {
+                    delay = delay_value.parse().ok();
+                    rate = rate_value.parse().ok();
+                }
+            } else if let Some(rest) = line.strip_prefix("auto repeat:") {
+                enabled = rest.split_whitespace().next().map(|value| value == "on");
+            }
+        }
+        Some(RepeatSettings {
+            enabled: enabled?,
+            delay: delay?,
+            rate: rate?,
+        })
+    }
+}
+
+pub struct KeyRepeat {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    enabled: bool,
+    /// Whether the X server could be asked
+    available: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KeyRepeatConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "KeyRepeatConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "KeyRepeatConfig::default_format")]
+    pub format: String,
+}
+
+impl KeyRepeatConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{rate}".to_owned()
+    }
+}
+
+/// Asks the X server for the settings, or returns `None` if it cannot be reached.
+fn query() -> Option<RepeatSettings> {
+    let output = Command::new("xset").arg("q").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    RepeatSettings::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+impl ConfigBlock for KeyRepeat {
+    type Config = KeyRepeatConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(KeyRepeat {
+            output: ButtonWidget::new(config, &id).with_icon("keyboard"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("key_repeat", "Invalid format specified")?,
+            enabled: false,
+            available: false,
+        })
+    }
+}
+
+impl Block for KeyRepeat {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Collapsed e.g. on Wayland, where xset cannot connect to a display
+        let repeat = match query() {
+            Some(repeat) => repeat,
+            None => {
+                self.available = false;
+                return Ok(Some(self.update_interval.into()));
+            }
+        };
+        self.available = true;
+        self.enabled = repeat.enabled;
+
+        let values = map!(
+            "{rate}" => repeat.rate.to_string(),
+            "{delay}" => repeat.delay.to_string(),
+            "{state}" => if repeat.enabled { "on" } else { "off" }.to_owned()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        self.output.set_state(if repeat.enabled {
+            State::Idle
+        } else {
+            State::Warning
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.available {
+            vec![&self.output]
+        } else {
+            vec![]
+        }
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            let toggle = if self.enabled { "off" } else { "on" };
+            Command::new("xset")
+                .args(["r", toggle])
+                .status()
+                .block_error("key_repeat", "failed to run xset")?;
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let output = "\
+Keyboard Control:
+  auto repeat:  on    key click percent:  0    LED mask:  00000002
+  XKB indicators:
+    00: Caps Lock:   off    01: Num Lock:    on     02: Scroll Lock: off
+  auto repeat delay:  300    repeat rate:  40
+  auto repeating keys:  00ffffffdffffbbf
+                        fadfffefffedffff
+Pointer Control:
+  acceleration:  2/1    threshold:  4
+";
+        assert_eq!(
+            RepeatSettings::parse(output),
+            Some(RepeatSettings {
+                enabled: true,
+                delay: 300,
+                rate: 40,
+            })
+        );
+        assert_eq!(
+            RepeatSettings::parse(&output.replace("repeat:  on", "repeat:  off"))
+                .map(|r| r.enabled),
+            Some(false)
+        );
+        assert_eq!(
+            RepeatSettings::parse("xset:  unable to open display \"\"\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::fs::{read_dir, read_to_string};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct LockKeys {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    caps: String,
+    num: String,
+    scroll: String,
+    caps_off: String,
+    num_off: String,
+    scroll_off: String,
+    locks: Arc<Mutex<Option<Result<Locks>>>>,
+    /// Whether there is any text to show
+    visible: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LockKeysConfig {
+    /// Format override
+    #[serde(default = "LockKeysConfig::default_format")]
+    pub format: String,
+
+    /// Text shown while Caps Lock is active
+    #[serde(default = "LockKeysConfig::default_caps")]
+    pub caps: String,
+
+    /// Text shown while Num Lock is active
+    #[serde(default = "LockKeysConfig::default_num")]
+    pub num: String,
+
+    /// Text shown while Scroll Lock is active
+    #[serde(default = "LockKeysConfig::default_scroll")]
+    pub scroll: String,
+
+    /// Text shown while Caps Lock is inactive
+    #[serde(default = "LockKeysConfig::default_off")]
+    pub caps_off: String,
+
+    /// Text shown while Num Lock is inactive
+    #[serde(default = "LockKeysConfig::default_off")]
+    pub num_off: String,
+
+    /// Text shown while Scroll Lock is inactive
+    #[serde(default = "LockKeysConfig::default_off")]
+    pub scroll_off: String,
+}
+
+impl LockKeysConfig {
+    fn default_format() -> String {
+        "{caps}{num}{scroll}".to_owned()
+    }
+
+    fn default_caps() -> String {
+        "CAPS ".to_owned()
+    }
+
+    fn default_num() -> String {
+        "NUM ".to_owned()
+    }
+
+    fn default_scroll() -> String {
+        "SCROLL ".to_owned()
+    }
+
+    fn default_off() -> String {
+        "".to_owned()
+    }
+}
+
+/// How long to wait before running `xinput` again after it exited, e.g. because there is no
+/// X11 display to connect to
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Locks {
+    caps: bool,
+    num: bool,
+    scroll: bool,
+}
+
+impl Locks {
+    fn read() -> Result<Self> {
+        Ok(Locks {
+            caps: is_locked("capslock")?,
+            num: is_locked("numlock")?,
+            scroll: is_locked("scrolllock")?,
+        })
+    }
+}
+
+/// Whether any keyboard has the given lock LED, e.g. `capslock`, turned on. The kernel keeps
+/// these LEDs in sync with the lock state even when the keyboard has no physical LEDs.
+fn is_locked(led: &str) -> Result<bool> {
+    let suffix = format!("::{}", led);
+    let leds =
+        read_dir("/sys/class/leds").block_error("lock_keys", "failed to read /sys/class/leds")?;
+    for entry in leds {
+        let path = entry.block_error("lock_keys", "failed to read LED")?.path();
+        let is_lock_led = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.starts_with("input") && name.ends_with(&suffix),
+            None => false,
+        };
+        if !is_lock_led {
+            continue;
+        }
+        match read_to_string(path.join("brightness")) {
+            Ok(ref brightness) if brightness.trim() != "0" => return Ok(true),
+            _ => (),
+        }
+    }
+    Ok(false)
+}
+
+/// Spawns a new thread that reads the LEDs again whenever a key is released, as reported by
+/// `xinput test-xi2 --root`, and requests an update of the block `id` if they changed.
+fn watch(id: String, tx: Sender<Task>, locks: Arc<Mutex<Option<Result<Locks>>>>) {
+    thread::Builder::new()
+        .name("lock_keys".into())
+        .spawn(move || {
+            let mut last = None;
+            // Most keys do not change any lock, so the bar is not redrawn for them
+            let mut refresh = || {
+                let current = Locks::read();
+                let locked = current.as_ref().ok().copied();
+                if locked.is_none() || locked != last {
+                    last = locked;
+                    *locks.lock().unwrap() = Some(current);
+                    tx.send(Task {
+                        id: id.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+                }
+            };
+            loop {
+                refresh();
+                // xinput only flushes its output when it is a terminal
+                if let Ok(mut child) = Command::new("stdbuf")
+                    .args(["-oL", "xinput", "test-xi2", "--root"])
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                {
+                    let stdout = child.stdout.take().unwrap();
+                    for line in BufReader::new(stdout).lines() {
+                        match line {
+                            // The LED follows the lock by the time the key is released
+                            Ok(line) if line.ends_with("(RawKeyRelease)") => refresh(),
+                            Ok(_) => (),
+                            Err(_) => break,
+                        }
+                    }
+                    let _ = child.wait();
+                }
+                thread::sleep(RESTART_DELAY);
+            }
+        })
+        .unwrap();
+}
+
+impl ConfigBlock for LockKeys {
+    type Config = LockKeysConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let locks = Arc::new(Mutex::new(None));
+        watch(id.clone(), tx, locks.clone());
+
+        Ok(LockKeys {
+            id,
+            text: TextWidget::new(config).with_state(State::Info),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("lock_keys", "Invalid format specified")?,
+            caps: block_config.caps,
+            num: block_config.num,
+            scroll: block_config.scroll,
+            caps_off: block_config.caps_off,
+            num_off: block_config.num_off,
+            scroll_off: block_config.scroll_off,
+            locks,
+            visible: false,
+        })
+    }
+}
+
+impl Block for LockKeys {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let text = |locked: bool, on: &str, off: &str| {
+            if locked {
+                on.to_owned()
+            } else {
+                off.to_owned()
+            }
+        };
+        let locks = self
+            .locks
+            .lock()
+            .block_error("lock_keys", "failed to acquire lock")?;
+
+        match *locks {
+            Some(Ok(locks)) => {
+                let values = map!(
+                    "{caps}" => text(locks.caps, &self.caps, &self.caps_off),
+                    "{num}" => text(locks.num, &self.num, &self.num_off),
+                    "{scroll}" => text(locks.scroll, &self.scroll, &self.scroll_off)
+                );
+                let text = self.format.render_static_str(&values)?.trim().to_owned();
+                self.visible = !text.is_empty();
+                self.text.set_text(text);
+                self.text.set_state(State::Info);
+            }
+            Some(Err(ref e)) => {
+                self.visible = true;
+                self.text.set_text(format!("{}", e));
+                self.text.set_state(State::Critical);
+            }
+            None => (),
+        }
+
+        // Updated by the watching thread
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        // Hidden while no lock is active, unless there is an off text
+        if self.visible {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
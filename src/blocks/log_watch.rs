@@ -0,0 +1,246 @@
+use std::fs::{metadata, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use regex::Regex;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// Follows a file like `tail -F`, reopening it when it is rotated or truncated.
+struct LogFile {
+    path: PathBuf,
+    file: Option<File>,
+    inode: u64,
+    offset: u64,
+    /// The last line, as long as it is not terminated yet
+    partial: String,
+}
+
+impl LogFile {
+    /// Follows the file at `path`. Its current content is skipped unless `from_start` is set.
+    fn new(path: PathBuf, from_start: bool) -> LogFile {
+        let mut log = LogFile {
+            path,
+            file: None,
+            inode: 0,
+            offset: 0,
+            partial: String::new(),
+        };
+        if !from_start {
+            if let Ok(meta) = metadata(&log.path) {
+                log.inode = meta.ino();
+                log.offset = meta.len();
+                log.file = File::open(&log.path).ok();
+            }
+        }
+        log
+    }
+
+    /// Returns the lines that were completed since the last call.
+    fn read_lines(&mut self) -> Result<Vec<String>> {
+        let meta = match metadata(&self.path) {
+            Ok(meta) => meta,
+            // In the middle of a rotation
+            Err(_) => return Ok(Vec::new()),
+        };
+        if self.file.is_none() || meta.ino() != self.inode || meta.len() < self.offset {
+            self.file = Some(File::open(&self.path).block_error(
+                "log_watch",
+                &format!("failed to open {}", self.path.display()),
+            )?);
+            self.inode = meta.ino();
+            self.offset = 0;
+            self.partial.clear();
+        }
+
+        let file = self.file.as_mut().unwrap();
+        file.seek(SeekFrom::Start(self.offset))
+            .block_error("log_watch", "failed to seek in log file")?;
+        let mut new = Vec::new();
+        file.read_to_end(&mut new)
+            .block_error("log_watch", "failed to read log file")?;
+        self.offset += new.len() as u64;
+
+        self.partial.push_str(&String::from_utf8_lossy(&new));
+        let mut lines: Vec<String> = self.partial.split('\n').map(String::from).collect();
+        self.partial = lines.pop().unwrap_or_default();
+        Ok(lines)
+    }
+}
+
+pub struct LogWatch {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    regex: Regex,
+    log: LogFile,
+    total: bool,
+    count: u64,
+    warning: Option<u64>,
+    critical: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LogWatchConfig {
+    /// The log file to watch
+    pub path: String,
+
+    /// The regex lines are matched against
+    pub regex: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "LogWatchConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Whether to count all matches instead of the ones since the last update
+    #[serde(default = "LogWatchConfig::default_total")]
+    pub total: bool,
+
+    /// Minimum number of matches, where state is set to warning
+    #[serde(default = "LogWatchConfig::default_warning")]
+    pub warning: Option<u64>,
+
+    /// Minimum number of matches, where state is set to critical
+    #[serde(default = "LogWatchConfig::default_critical")]
+    pub critical: Option<u64>,
+
+    /// Format override
+    #[serde(default = "LogWatchConfig::default_format")]
+    pub format: String,
+}
+
+impl LogWatchConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_total() -> bool {
+        false
+    }
+
+    fn default_warning() -> Option<u64> {
+        None
+    }
+
+    fn default_critical() -> Option<u64> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+}
+
+impl ConfigBlock for LogWatch {
+    type Config = LogWatchConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(LogWatch {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("log_watch"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("log_watch", "Invalid format specified")?,
+            regex: Regex::new(&block_config.regex)
+                .block_error("log_watch", "invalid regex specified")?,
+            log: LogFile::new(PathBuf::from(block_config.path), block_config.total),
+            total: block_config.total,
+            count: 0,
+            warning: block_config.warning,
+            critical: block_config.critical,
+        })
+    }
+}
+
+impl Block for LogWatch {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let matches = self
+            .log
+            .read_lines()?
+            .iter()
+            .filter(|line| self.regex.is_match(line))
+            .count() as u64;
+        self.count = if self.total {
+            self.count + matches
+        } else {
+            matches
+        };
+
+        let values = map!("{count}" => self.count);
+        self.text.set_text(self.format.render_static_str(&values)?);
+        let reached = |threshold: Option<u64>| match threshold {
+            Some(threshold) => self.count >= threshold,
+            None => false,
+        };
+        self.text.set_state(if reached(self.critical) {
+            State::Critical
+        } else if reached(self.warning) {
+            State::Warning
+        } else {
+            State::Idle
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+    use std::fs::{rename, OpenOptions};
+    use std::io::Write;
+
+    #[test]
+    fn test_log_file_rotation() {
+        let dir = TempDir::new().unwrap();
+        let log_path = dir.child("app.log");
+        log_path.write_str("old entry\n").unwrap();
+
+        let mut log = LogFile::new(log_path.path().to_path_buf(), false);
+        assert!(log.read_lines().unwrap().is_empty());
+
+        let append = |text: &str| {
+            OpenOptions::new()
+                .append(true)
+                .open(log_path.path())
+                .unwrap()
+                .write_all(text.as_bytes())
+                .unwrap()
+        };
+        append("first\nsec");
+        assert_eq!(log.read_lines().unwrap(), vec!["first"]);
+        append("ond\n");
+        assert_eq!(log.read_lines().unwrap(), vec!["second"]);
+
+        rename(log_path.path(), dir.child("app.log.1").path()).unwrap();
+        log_path.write_str("rotated\n").unwrap();
+        assert_eq!(log.read_lines().unwrap(), vec!["rotated"]);
+    }
+}
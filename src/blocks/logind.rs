@@ -0,0 +1,209 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use dbus::blocking::stdintf::org_freedesktop_dbus::{Properties, PropertiesPropertiesChanged};
+use dbus::blocking::Connection;
+use dbus::message::MatchRule;
+use dbus::Message;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+const LOGIND: &str = "org.freedesktop.login1";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct SessionState {
+    locked: bool,
+    idle: bool,
+    active: bool,
+}
+
+impl SessionState {
+    /// Reads the state from the hints of the session, which stay at their defaults if
+    /// logind cannot be asked.
+    fn get(connection: &Connection, session: &dbus::Path<'static>) -> SessionState {
+        let proxy = connection.with_proxy(LOGIND, session, Duration::from_secs(5));
+        let hint = |name: &str| -> bool { proxy.get(SESSION_INTERFACE, name).unwrap_or(false) };
+        SessionState {
+            locked: hint("LockedHint"),
+            idle: hint("IdleHint"),
+            active: hint("Active"),
+        }
+    }
+
+    /// The most important thing about the session, as locked sessions are usually idle too.
+    fn name(self) -> &'static str {
+        if self.locked {
+            "locked"
+        } else if self.idle {
+            "idle"
+        } else if self.active {
+            "active"
+        } else {
+            "inactive"
+        }
+    }
+}
+
+fn request_update(send: &Sender<Task>, id: &str) {
+    send.send(Task {
+        id: id.to_owned(),
+        update_time: Instant::now(),
+    })
+    .unwrap();
+}
+
+pub struct Logind {
+    id: String,
+    text: TextWidget,
+    format: FormatTemplate,
+    state: Arc<Mutex<SessionState>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LogindConfig {
+    /// Format override
+    #[serde(default = "LogindConfig::default_format")]
+    pub format: String,
+}
+
+impl LogindConfig {
+    fn default_format() -> String {
+        "{state}".to_owned()
+    }
+}
+
+impl ConfigBlock for Logind {
+    type Config = LogindConfig;
+
+    fn new(block_config: Self::Config, config: Config, send: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let connection = Connection::new_system()
+            .block_error("logind", "Failed to establish D-Bus connection")?;
+        // "auto" is the session i3status-rs runs in, or else the graphical session of the user
+        let (session,): (dbus::Path<'static>,) = connection
+            .with_proxy(LOGIND, "/org/freedesktop/login1", Duration::from_secs(5))
+            .method_call("org.freedesktop.login1.Manager", "GetSession", ("auto",))
+            .block_error("logind", "failed to find the session")?;
+        let state = Arc::new(Mutex::new(SessionState::get(&connection, &session)));
+
+        let state_copy = state.clone();
+        let id_copy = id.clone();
+        thread::Builder::new()
+            .name("logind".into())
+            .spawn(move || {
+                let proxy = connection.with_proxy(LOGIND, &session, Duration::from_secs(5));
+                let (state, send_copy, id) = (state_copy.clone(), send.clone(), id_copy.clone());
+                let session_copy = session.clone();
+                let _handler = proxy.match_signal(
+                    move |s: PropertiesPropertiesChanged, c: &Connection, _: &Message| {
+                        if s.interface_name == SESSION_INTERFACE {
+                            *state.lock().unwrap() = SessionState::get(c, &session_copy);
+                            request_update(&send_copy, &id);
+                        }
+                        true
+                    },
+                );
+                // Sent when the session is asked to lock or unlock, which the locker may
+                // only report through LockedHint later, if at all
+                for (member, locked) in [("Lock", true), ("Unlock", false)] {
+                    let mut rule = MatchRule::new_signal(SESSION_INTERFACE, member);
+                    rule.path = Some(session.clone());
+                    let (state, send, id) = (state_copy.clone(), send.clone(), id_copy.clone());
+                    connection
+                        .add_match(rule, move |_: (), _: &Connection, _: &Message| {
+                            state.lock().unwrap().locked = locked;
+                            request_update(&send, &id);
+                            true
+                        })
+                        .unwrap();
+                }
+                loop {
+                    connection.process(Duration::from_secs(3600)).unwrap();
+                }
+            })
+            .unwrap();
+
+        Ok(Logind {
+            id,
+            text: TextWidget::new(config).with_icon("unlocked"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("logind", "Invalid format specified")?,
+            state,
+        })
+    }
+}
+
+impl Block for Logind {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let state = *self
+            .state
+            .lock()
+            .block_error("logind", "failed to acquire lock")?;
+
+        let values = map!("{state}" => state.name().to_owned());
+        self.text.set_text(self.format.render_static_str(&values)?);
+        if state.locked {
+            self.text.set_icon("locked");
+            self.text.set_state(State::Warning);
+        } else {
+            self.text.set_icon("unlocked");
+            self.text.set_state(State::Idle);
+        }
+
+        // Updated whenever the session changes
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_name() {
+        let active = SessionState {
+            locked: false,
+            idle: false,
+            active: true,
+        };
+        assert_eq!(active.name(), "active");
+        assert_eq!(
+            SessionState {
+                idle: true,
+                ..active
+            }
+            .name(),
+            "idle"
+        );
+        assert_eq!(
+            SessionState {
+                locked: true,
+                idle: true,
+                ..active
+            }
+            .name(),
+            "locked"
+        );
+        assert_eq!(SessionState::default().name(), "inactive");
+    }
+}
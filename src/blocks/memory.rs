@@ -157,14 +157,15 @@ impl Memstate {
 #[derive(Clone, Debug)]
 pub struct Memory {
     id: String,
-    memtype: Memtype,
+    /// The view that is shown, and the other one if `clickable`
+    memtype: AltFormat<Memtype>,
     output: (ButtonWidget, ButtonWidget),
-    clickable: bool,
     format: (FormatTemplate, FormatTemplate),
     update_interval: Duration,
     tx_update_request: Sender<Task>,
     warning: (f64, f64),
     critical: (f64, f64),
+    gradient: Option<(String, String)>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -211,6 +212,10 @@ pub struct MemoryConfig {
     /// Percentage of swap usage, where state is set to critical
     #[serde(default = "MemoryConfig::default_critical_swap")]
     pub critical_swap: f64,
+
+    /// Foreground colors at 0% and 100% usage, interpolated in between
+    #[serde(default = "MemoryConfig::default_gradient")]
+    pub gradient: Option<(String, String)>,
 }
 
 impl MemoryConfig {
@@ -253,6 +258,10 @@ impl MemoryConfig {
     fn default_critical_swap() -> f64 {
         95.0
     }
+
+    fn default_gradient() -> Option<(String, String)> {
+        None
+    }
 }
 
 impl Memory {
@@ -314,7 +323,15 @@ impl Memory {
             "{Cpi}" => format!("{:02}", cached.percent(mem_total) as i32),
             "{Cpb}" => format_percent_bar(cached.percent(mem_total)));
 
-        match self.memtype {
+        if let Some((ref start, ref end)) = self.gradient {
+            let (output, percent) = match self.memtype.get() {
+                Memtype::Memory => (&mut self.output.0, mem_used.percent(mem_total)),
+                Memtype::Swap => (&mut self.output.1, swap_used.percent(swap_total)),
+            };
+            output.set_color(color_gradient(start, end, f64::from(percent)).ok());
+        }
+
+        match self.memtype.get() {
             Memtype::Memory => self.output.0.set_state(match mem_used.percent(mem_total) {
                 x if f64::from(x) > self.critical.0 => State::Critical,
                 x if f64::from(x) > self.warning.0 => State::Warning,
@@ -340,30 +357,36 @@ impl Memory {
                 .block_error("memory", "failed to write to /tmp/i3log")?;
         });
 
-        Ok(match self.memtype {
+        Ok(match self.memtype.get() {
             Memtype::Memory => self.format.0.render_static_str(&values)?,
             Memtype::Swap => self.format.1.render_static_str(&values)?,
         })
     }
-
-    pub fn switch(&mut self) {
-        let old: Memtype = self.memtype.clone();
-        self.memtype = match old {
-            Memtype::Memory => Memtype::Swap,
-            _ => Memtype::Memory,
-        };
-    }
 }
 
 impl ConfigBlock for Memory {
     type Config = MemoryConfig;
 
     fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        if let Some((ref start, ref end)) = block_config.gradient {
+            color_gradient(start, end, 0.0).configuration_error("invalid memory gradient color")?;
+        }
+
         let icons: bool = block_config.icons;
         let widget = ButtonWidget::new(config, "memory").with_text("");
         Ok(Memory {
             id: Uuid::new_v4().to_simple().to_string(),
-            memtype: block_config.display_type,
+            memtype: AltFormat::new(
+                block_config.display_type.clone(),
+                if block_config.clickable {
+                    Some(match block_config.display_type {
+                        Memtype::Memory => Memtype::Swap,
+                        Memtype::Swap => Memtype::Memory,
+                    })
+                } else {
+                    None
+                },
+            ),
             output: if icons {
                 (
                     widget.clone().with_icon("memory_mem"),
@@ -372,7 +395,6 @@ impl ConfigBlock for Memory {
             } else {
                 (widget.clone(), widget)
             },
-            clickable: block_config.clickable,
             format: (
                 FormatTemplate::from_string(&block_config.format_mem)?,
                 FormatTemplate::from_string(&block_config.format_swap)?,
@@ -381,6 +403,7 @@ impl ConfigBlock for Memory {
             tx_update_request: tx,
             warning: (block_config.warning_mem, block_config.warning_swap),
             critical: (block_config.critical_mem, block_config.critical_swap),
+            gradient: block_config.gradient,
         })
     }
 }
@@ -489,7 +512,7 @@ impl Block for Memory {
         // Now, create the string to be shown
         let output_text = self.format_insert_values(mem_state)?;
 
-        match self.memtype {
+        match self.memtype.get() {
             Memtype::Memory => self.output.0.set_text(output_text),
             Memtype::Swap => self.output.1.set_text(output_text),
         }
@@ -518,8 +541,7 @@ impl Block for Memory {
         });
 
         if let Some(ref s) = event.name {
-            if self.clickable && event.button == MouseButton::Left && *s == "memory" {
-                self.switch();
+            if event.button == MouseButton::Left && *s == "memory" && self.memtype.toggle() {
                 self.update()?;
                 self.tx_update_request.send(Task {
                     id: self.id.clone(),
@@ -532,7 +554,7 @@ impl Block for Memory {
     }
 
     fn view(&self) -> Vec<&dyn I3BarWidget> {
-        vec![match self.memtype {
+        vec![match self.memtype.get() {
             Memtype::Memory => &self.output.0,
             Memtype::Swap => &self.output.1,
         }]
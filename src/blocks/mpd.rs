@@ -0,0 +1,366 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// How long to wait before connecting again after MPD went away
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// How long to wait for MPD to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long to wait for MPD to answer a command, except for `idle`
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Mpd {
+    id: String,
+    icon: ButtonWidget,
+    song: ButtonWidget,
+    prev: Option<ButtonWidget>,
+    play: Option<ButtonWidget>,
+    next: Option<ButtonWidget>,
+    format: FormatTemplate,
+    host: String,
+    password: Option<String>,
+    /// The connection for commands, opened again when MPD closed it
+    connection: Option<Connection>,
+    /// The playback state reported by MPD, i.e. `play`, `pause` or `stop`
+    state: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MpdConfig {
+    /// The address of MPD as `host:port`
+    #[serde(default = "MpdConfig::default_host")]
+    pub host: String,
+
+    /// The password, if MPD requires one
+    #[serde(default = "MpdConfig::default_password")]
+    pub password: Option<String>,
+
+    /// Format override
+    #[serde(default = "MpdConfig::default_format")]
+    pub format: String,
+
+    /// Array of control buttons to be displayed. Options are prev, play and next
+    #[serde(default = "MpdConfig::default_buttons")]
+    pub buttons: Vec<String>,
+}
+
+impl MpdConfig {
+    fn default_host() -> String {
+        "localhost:6600".to_owned()
+    }
+
+    fn default_password() -> Option<String> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{artist} - {title}".to_owned()
+    }
+
+    fn default_buttons() -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Reads the `key: value` pairs of a response up to the final `OK`. An `ACK` is an error.
+fn read_response<R: BufRead>(reader: &mut R) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader
+            .read_line(&mut line)
+            .block_error("mpd", "failed to read from MPD")?
+            == 0
+        {
+            return Err(BlockError(
+                "mpd".to_owned(),
+                "connection closed by MPD".to_owned(),
+            ));
+        }
+        let line = line.trim_end();
+        if line == "OK" {
+            return Ok(pairs);
+        }
+        if let Some(error) = line.strip_prefix("ACK ") {
+            return Err(BlockError("mpd".to_owned(), error.to_owned()));
+        }
+        if let Some((key, value)) = line.split_once(": ") {
+            pairs.push((key.to_owned(), value.to_owned()));
+        }
+    }
+}
+
+/// Looks up the value of `key` in a response.
+fn get<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.as_str())
+}
+
+/// A connection to MPD speaking its text protocol.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+}
+
+impl Connection {
+    /// Connects to MPD. Reads time out after `timeout`, if any.
+    fn connect(
+        host: &str,
+        password: &Option<String>,
+        timeout: Option<Duration>,
+    ) -> Result<Connection> {
+        let address = host
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addresses| addresses.next())
+            .block_error("mpd", &format!("failed to resolve {}", host))?;
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+            .block_error("mpd", &format!("failed to connect to {}", host))?;
+        stream
+            .set_read_timeout(timeout)
+            .and_then(|_| stream.set_write_timeout(Some(COMMAND_TIMEOUT)))
+            .block_error("mpd", "failed to set socket timeouts")?;
+        let mut reader = BufReader::new(
+            stream
+                .try_clone()
+                .block_error("mpd", "failed to clone socket")?,
+        );
+
+        // MPD greets with its version, e.g. `OK MPD 0.22.0`
+        let mut greeting = String::new();
+        reader
+            .read_line(&mut greeting)
+            .block_error("mpd", "failed to read from MPD")?;
+        if !greeting.starts_with("OK MPD") {
+            return Err(BlockError("mpd".to_owned(), format!("{} is not MPD", host)));
+        }
+
+        let mut connection = Connection { reader, stream };
+        if let Some(ref password) = *password {
+            connection.command(&format!("password \"{}\"", escape(password)))?;
+        }
+        Ok(connection)
+    }
+
+    fn command(&mut self, command: &str) -> Result<Vec<(String, String)>> {
+        writeln!(self.stream, "{}", command).block_error("mpd", "failed to write to MPD")?;
+        read_response(&mut self.reader)
+    }
+}
+
+/// Escapes an argument for use in double quotes.
+fn escape(argument: &str) -> String {
+    argument.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl ConfigBlock for Mpd {
+    type Config = MpdConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // A separate connection waits for changes, and is opened again whenever MPD restarts
+        {
+            let id = id.clone();
+            let host = block_config.host.clone();
+            let password = block_config.password.clone();
+            thread::Builder::new()
+                .name("mpd".into())
+                .spawn(move || loop {
+                    // `idle` waits for changes as long as it takes, so it has no timeout
+                    if let Ok(mut connection) = Connection::connect(&host, &password, None) {
+                        // Show the state right after connecting, e.g. when MPD came back
+                        let send = || {
+                            tx.send(Task {
+                                id: id.clone(),
+                                update_time: Instant::now(),
+                            })
+                            .unwrap();
+                        };
+                        send();
+                        while connection.command("idle player options").is_ok() {
+                            send();
+                        }
+                        send();
+                    }
+                    thread::sleep(RECONNECT_DELAY);
+                })
+                .unwrap();
+        }
+
+        let button = |name: &str, icon: &str| {
+            ButtonWidget::new(config.clone(), &format!("{}-{}", id, name))
+                .with_icon(icon)
+                .with_state(State::Info)
+        };
+        let mut play = None;
+        let mut prev = None;
+        let mut next = None;
+        for name in block_config.buttons {
+            match &*name {
+                "play" => play = Some(button("play", "music_play")),
+                "next" => next = Some(button("next", "music_next")),
+                "prev" => prev = Some(button("prev", "music_prev")),
+                x => {
+                    return Err(BlockError(
+                        "mpd".to_owned(),
+                        format!("unknown mpd button identifier: '{}'", x),
+                    ))
+                }
+            }
+        }
+
+        Ok(Mpd {
+            icon: button("icon", "music"),
+            song: button("song", ""),
+            prev,
+            play,
+            next,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("mpd", "Invalid format specified")?,
+            host: block_config.host,
+            password: block_config.password,
+            connection: None,
+            state: None,
+            id,
+        })
+    }
+}
+
+impl Mpd {
+    /// Sends a command over the command connection, connecting again once if MPD closed
+    /// it, which it does with connections that are unused for a while.
+    fn command(&mut self, command: &str) -> Result<Vec<(String, String)>> {
+        if let Some(ref mut connection) = self.connection {
+            if let Ok(response) = connection.command(command) {
+                return Ok(response);
+            }
+        }
+        self.connection = None;
+        let mut connection =
+            Connection::connect(&self.host, &self.password, Some(COMMAND_TIMEOUT))?;
+        let response = connection.command(command)?;
+        self.connection = Some(connection);
+        Ok(response)
+    }
+}
+
+impl Block for Mpd {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // MPD not running is not an error, the block just shows nothing until it is back
+        let status = match self.command("status") {
+            Ok(status) => status,
+            Err(_) => {
+                self.state = None;
+                return Ok(None);
+            }
+        };
+        let song = self.command("currentsong")?;
+
+        self.state = get(&status, "state").map(String::from);
+        if let Some(ref mut play) = self.play {
+            play.set_icon(match self.state.as_deref() {
+                Some("play") => "music_pause",
+                _ => "music_play",
+            });
+        }
+
+        // Files without tags are shown by their name
+        let file = get(&song, "file").unwrap_or("");
+        let file_name = file.rsplit('/').next().unwrap_or(file);
+        let values = map!(
+            "{artist}" => get(&song, "Artist").unwrap_or(""),
+            "{title}" => get(&song, "Title").unwrap_or(file_name),
+            "{album}" => get(&song, "Album").unwrap_or(""),
+            "{file}" => file_name
+        );
+        self.song.set_text(self.format.render_static_str(&values)?);
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        match self.state.as_deref() {
+            Some("play") | Some("pause") => {
+                let mut elements: Vec<&dyn I3BarWidget> = vec![&self.icon, &self.song];
+                for button in [&self.prev, &self.play, &self.next]
+                    .iter()
+                    .copied()
+                    .flatten()
+                {
+                    elements.push(button);
+                }
+                elements
+            }
+            _ => vec![&self.icon],
+        }
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        let name = match event
+            .name
+            .as_ref()
+            .and_then(|name| name.strip_prefix(&self.id))
+        {
+            Some(name) => name,
+            None => return Ok(()),
+        };
+        if event.button != MouseButton::Left {
+            return Ok(());
+        }
+        let command = match name {
+            "-play" if self.state.as_deref() == Some("play") => "pause 1",
+            "-play" => "play",
+            "-next" => "next",
+            "-prev" => "previous",
+            _ => return Ok(()),
+        };
+        // The idle connection triggers the update. A failure, e.g. because MPD just quit,
+        // must not stop the bar, and the block is hidden by its next update anyway.
+        let _ = self.command(command);
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_response() {
+        let mut response =
+            "file: music/a.flac\nArtist: Someone\nTitle: Some: Song\nOK\n".as_bytes();
+        let pairs = read_response(&mut response).unwrap();
+        assert_eq!(get(&pairs, "Artist"), Some("Someone"));
+        assert_eq!(get(&pairs, "Title"), Some("Some: Song"));
+        assert_eq!(get(&pairs, "Album"), None);
+
+        let mut response = "ACK [3@0] {password} incorrect password\n".as_bytes();
+        assert!(read_response(&mut response).is_err());
+        let mut response = "state: play\n".as_bytes();
+        assert!(read_response(&mut response).is_err());
+
+        assert_eq!(escape(r#"pa"ss\"#), r#"pa\"ss\\"#);
+    }
+}
@@ -1,4 +1,8 @@
 use std::boxed::Box;
+use std::ffi::OsString;
+use std::fs::read_to_string;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -8,39 +12,78 @@ use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 use dbus::{
     arg,
     ffidisp::{BusType, Connection, ConnectionItem},
-    Message,
+    Message, Path,
 };
 use serde_derive::Deserialize;
 use uuid::Uuid;
 
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
-use crate::config::Config;
+use crate::config::{Config, LogicalDirection, Scrolling};
 use crate::de::deserialize_duration;
 use crate::errors::*;
-use crate::input::I3BarEvent;
+use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
-use crate::subprocess::spawn_child_async;
+use crate::subprocess::{spawn_child_async, spawn_child_async_with_env};
+use crate::util::FormatTemplate;
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
-use crate::widgets::rotatingtext::RotatingTextWidget;
+use crate::widgets::rotatingtext::{RotatingTextWidget, RotationDirection};
+use crate::widgets::text::TextWidget;
 
 pub struct Music {
     id: String,
     current_song: RotatingTextWidget,
+    song_time: ButtonWidget,
+    progress: ButtonWidget,
+    volume_widget: ButtonWidget,
+    quality: ButtonWidget,
+    quality_format: Option<FormatTemplate>,
     prev: Option<ButtonWidget>,
     play: Option<ButtonWidget>,
     next: Option<ButtonWidget>,
-    on_collapsed_click_widget: ButtonWidget,
+    icon: ButtonWidget,
+    buttons_position: ButtonsPosition,
+    /// Shown between the song and the buttons, if configured
+    buttons_separator: Option<TextWidget>,
     on_collapsed_click: Option<String>,
+    on_click: Option<String>,
+    /// The `xesam:url` of the current track
+    url: Option<String>,
+    /// The `mpris:trackid` of the current track, needed for seeking
+    track_id: Option<String>,
     dbus_conn: Connection,
     player_avail: bool,
+    /// Whether the player reported that it is paused or stopped
+    paused: bool,
+    paused_interval: Duration,
     marquee: bool,
     player: Option<String>,
     auto_discover: bool,
     smart_trim: bool,
     max_width: usize,
     separator: String,
+    /// The title and artist, of which a missing one is shown as the placeholder rather than
+    /// leaving a stray separator
+    song_format: FormatTemplate,
+    show_time: bool,
+    show_progress: bool,
+    show_remaining: bool,
+    show_volume: bool,
+    volume: Option<f64>,
+    scrolling: Scrolling,
+    position: Option<i64>,
+    length: Option<i64>,
+    lyrics: bool,
+    lrc: Option<Lyrics>,
+}
+
+#[derive(Deserialize, Copy, Clone, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ButtonsPosition {
+    Left,
+    #[default]
+    Right,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -65,6 +108,7 @@ pub struct MusicConfig {
 
     /// Marquee interval in seconds. This is the delay between each rotation.
     #[serde(
+        alias = "rotation_interval",
         default = "MusicConfig::default_marquee_interval",
         deserialize_with = "deserialize_duration"
     )]
@@ -72,11 +116,23 @@ pub struct MusicConfig {
 
     /// Marquee speed in seconds. This is the scrolling time used per character.
     #[serde(
+        alias = "rotation_step",
         default = "MusicConfig::default_marquee_speed",
         deserialize_with = "deserialize_duration"
     )]
     pub marquee_speed: Duration,
 
+    /// Which way the marquee scrolls, "left" or "right"
+    #[serde(alias = "rotation_direction", default)]
+    pub marquee_direction: RotationDirection,
+
+    /// Update interval in seconds while the player is paused or stopped, which stops the marquee
+    #[serde(
+        default = "MusicConfig::default_paused_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub paused_interval: Duration,
+
     /// Bool to specify whether smart trimming should be used when marquee rotation is disabled<br/> and the title + artist is longer than max-width. It will trim from both the artist and the title in proportion to their lengths, to try and show the most information possible.
     #[serde(default = "MusicConfig::default_smart_trim")]
     pub smart_trim: bool,
@@ -89,8 +145,47 @@ pub struct MusicConfig {
     #[serde(default = "MusicConfig::default_buttons")]
     pub buttons: Vec<String>,
 
+    /// Whether the buttons are placed before or after the song, "left" or "right"
+    #[serde(default)]
+    pub buttons_position: ButtonsPosition,
+
+    /// Text to show between the song and the buttons
+    #[serde(default = "MusicConfig::default_buttons_separator")]
+    pub buttons_separator: Option<String>,
+
     #[serde(default = "MusicConfig::default_on_collapsed_click")]
     pub on_collapsed_click: Option<String>,
+
+    /// Shell command to run when the song is clicked. The URL of the track is passed in
+    /// `$MUSIC_URL`.
+    #[serde(default = "MusicConfig::default_on_click")]
+    pub on_click: Option<String>,
+
+    /// Bool to specify whether the playback position should be shown after the song.
+    /// Clicking the time, or the song without `on_click`, toggles between elapsed and
+    /// remaining time.
+    #[serde(default = "MusicConfig::default_show_time")]
+    pub show_time: bool,
+
+    /// Bool to specify whether a progress bar of the track should be shown after the song.
+    /// Clicking on the bar seeks to that position.
+    #[serde(default = "MusicConfig::default_show_progress")]
+    pub show_progress: bool,
+
+    /// Bool to specify whether the current line of synced lyrics should be shown instead of
+    /// the song, if there is an `.lrc` file next to the playing file.
+    #[serde(default = "MusicConfig::default_lyrics")]
+    pub lyrics: bool,
+
+    /// Bool to specify whether the player's own volume should be shown after the song.
+    /// Scrolling on it adjusts the volume.
+    #[serde(default = "MusicConfig::default_show_volume")]
+    pub show_volume: bool,
+
+    /// Format of the quality of the track, shown after the song if set. The bitrate and
+    /// codec are only known if the player exposes them.
+    #[serde(default = "MusicConfig::default_quality_format")]
+    pub quality_format: Option<String>,
 }
 
 impl MusicConfig {
@@ -114,6 +209,10 @@ impl MusicConfig {
         Duration::from_millis(500)
     }
 
+    fn default_paused_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
     fn default_smart_trim() -> bool {
         false
     }
@@ -126,9 +225,37 @@ impl MusicConfig {
         vec![]
     }
 
+    fn default_buttons_separator() -> Option<String> {
+        None
+    }
+
     fn default_on_collapsed_click() -> Option<String> {
         None
     }
+
+    fn default_on_click() -> Option<String> {
+        None
+    }
+
+    fn default_show_time() -> bool {
+        false
+    }
+
+    fn default_show_progress() -> bool {
+        false
+    }
+
+    fn default_lyrics() -> bool {
+        false
+    }
+
+    fn default_show_volume() -> bool {
+        false
+    }
+
+    fn default_quality_format() -> Option<String> {
+        None
+    }
 }
 
 impl ConfigBlock for Music {
@@ -142,6 +269,9 @@ impl ConfigBlock for Music {
             let c = Connection::get_private(BusType::Session).unwrap();
             c.add_match("interface='org.freedesktop.DBus.Properties',member='PropertiesChanged',path='/org/mpris/MediaPlayer2'")
                 .unwrap();
+            // Players appearing or disappearing from the bus
+            c.add_match("interface='org.freedesktop.DBus',member='NameOwnerChanged',arg0namespace='org.mpris.MediaPlayer2'")
+                .unwrap();
             loop {
                 for ci in c.iter(100_000) {
                     if let ConnectionItem::Signal(_) = ci {
@@ -192,25 +322,56 @@ impl ConfigBlock for Music {
 
         Ok(Music {
             id: id_copy,
+            song_format: FormatTemplate::from_string("{title}{separator}{artist}")?
+                .with_placeholder(&config.placeholder),
+            scrolling: config.scrolling,
             current_song: RotatingTextWidget::new(
-                Duration::new(block_config.marquee_interval.as_secs(), 0),
-                Duration::new(0, block_config.marquee_speed.subsec_nanos()),
                 block_config.max_width,
                 block_config.dynamic_width,
                 config.clone(),
             )
-            .with_icon("music")
+            .with_rotation_interval(block_config.marquee_interval)
+            .with_rotation_speed(block_config.marquee_speed)
+            .with_rotation_direction(block_config.marquee_direction)
+            // The icon is a separate widget, so it can be clicked on its own
+            .with_icon("")
+            .with_name("song")
             .with_state(State::Info),
+            song_time: ButtonWidget::new(config.clone(), "song_time").with_state(State::Info),
+            progress: ButtonWidget::new(config.clone(), "progress").with_state(State::Info),
+            volume_widget: ButtonWidget::new(config.clone(), "volume")
+                .with_icon("volume_full")
+                .with_state(State::Info),
+            quality: ButtonWidget::new(config.clone(), "quality").with_state(State::Info),
+            quality_format: match block_config.quality_format {
+                Some(ref format) => Some(
+                    FormatTemplate::from_string(format)
+                        .block_error("music", "Invalid quality_format specified")?
+                        .with_placeholder(&config.placeholder),
+                ),
+                None => None,
+            },
             prev,
             play,
             next,
-            on_collapsed_click_widget: ButtonWidget::new(config, "on_collapsed_click")
+            buttons_position: block_config.buttons_position,
+            buttons_separator: block_config.buttons_separator.map(|separator| {
+                TextWidget::new(config.clone())
+                    .with_text(&separator)
+                    .with_state(State::Info)
+            }),
+            icon: ButtonWidget::new(config, "icon")
                 .with_icon("music")
                 .with_state(State::Info),
             on_collapsed_click: block_config.on_collapsed_click,
+            on_click: block_config.on_click,
+            url: None,
+            track_id: None,
             dbus_conn: Connection::get_private(BusType::Session)
                 .block_error("music", "failed to establish D-Bus connection")?,
             player_avail: false,
+            paused: false,
+            paused_interval: block_config.paused_interval,
             auto_discover: block_config.player.is_none(),
             player: if block_config.player.is_none() {
                 block_config.player
@@ -224,17 +385,81 @@ impl ConfigBlock for Music {
             smart_trim: block_config.smart_trim,
             max_width: block_config.max_width,
             separator: block_config.separator,
+            show_time: block_config.show_time,
+            show_progress: block_config.show_progress,
+            show_remaining: false,
+            show_volume: block_config.show_volume,
+            volume: None,
+            position: None,
+            length: None,
+            lyrics: block_config.lyrics,
+            lrc: None,
         })
     }
 }
 
+impl Music {
+    fn set_volume(&mut self, volume: Option<f64>) {
+        self.volume = volume;
+        if let Some(volume) = volume {
+            self.volume_widget
+                .set_text(format!("{:.0}%", volume * 100.0));
+        }
+    }
+
+    /// Seeks to the fraction of the track at which the progress bar was clicked.
+    fn seek(&mut self, event: &I3BarEvent) -> Result<()> {
+        let (length, track_id) = match (self.length, &self.track_id) {
+            (Some(length), Some(track_id)) => (length, track_id),
+            _ => return Ok(()),
+        };
+        // Only recent versions of i3bar and swaybar report where on the block was clicked
+        let fraction = match (event.relative_x, event.width) {
+            (Some(x), Some(width)) if width > 0 => (x as f64 / width as f64).clamp(0.0, 1.0),
+            _ => return Ok(()),
+        };
+        let position = (fraction * length as f64) as i64;
+
+        let track_id = Path::new(track_id.as_str()).block_error("music", "invalid track id")?;
+        let m = Message::new_method_call(
+            self.player.as_ref().unwrap(),
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player",
+            "SetPosition",
+        )
+        .block_error("music", "failed to create D-Bus method call")?
+        .append2(track_id, position);
+        self.dbus_conn
+            .send(m)
+            .block_error("music", "failed to call method via D-Bus")?;
+
+        self.position = Some(position);
+        self.progress.set_text(format_progress(position, length));
+        if self.show_time {
+            self.song_time.set_text(self.format_time());
+        }
+        Ok(())
+    }
+
+    fn format_time(&self) -> String {
+        let position = self.position.unwrap_or(0);
+        match self.length {
+            Some(length) if self.show_remaining => {
+                format!("-{}", format_microseconds(length - position))
+            }
+            _ => format_microseconds(position),
+        }
+    }
+}
+
 impl Block for Music {
     fn id(&self) -> &str {
         &self.id
     }
 
     fn update(&mut self) -> Result<Option<Update>> {
-        let (rotated, next) = if self.marquee {
+        // Nothing changes while the player is paused, so the marquee stops as well
+        let (rotated, mut next) = if self.marquee && !(self.paused && self.player_avail) {
             self.current_song.next()?
         } else {
             (false, None)
@@ -249,10 +474,49 @@ impl Block for Music {
                 1000,
             );
             let data = c.get("org.mpris.MediaPlayer2.Player", "Metadata");
+            let position = if self.show_time || self.show_progress || self.lyrics {
+                match c.get("org.mpris.MediaPlayer2.Player", "Position") {
+                    Ok(data) => {
+                        let data: Box<dyn RefArg> = data;
+                        data.as_i64()
+                    }
+                    Err(_) => None,
+                }
+            } else {
+                None
+            };
+            let mut lyric = None;
 
             if let Ok(metadata) = data {
-                let (mut title, mut artist) =
-                    extract_from_metadata(&metadata).unwrap_or((String::new(), String::new()));
+                let SongMetadata {
+                    mut title,
+                    mut artist,
+                    length,
+                    url,
+                    track_id,
+                    bitrate,
+                    codec,
+                } = extract_from_metadata(&metadata).unwrap_or_default();
+                self.length = length;
+                self.url = url.clone();
+                self.track_id = track_id;
+                if let Some(ref format) = self.quality_format {
+                    let values = map!(
+                        "{bitrate}" => bitrate.map(|b| format!("{}kbit/s", b)),
+                        "{codec}" => codec
+                    );
+                    self.quality.set_text(format.render_optional(&values)?);
+                }
+
+                // The lyrics are only parsed once per track
+                if self.lyrics && self.lrc.as_ref().map(|lrc| &lrc.url) != url.as_ref() {
+                    self.lrc = url.map(Lyrics::load);
+                }
+                if let (Some(lrc), Some(position)) = (&self.lrc, position) {
+                    lyric = lrc
+                        .line_at(position)
+                        .map(|(line, next_line)| (line.to_owned(), next_line));
+                }
 
                 if title.is_empty() && artist.is_empty() {
                     self.player_avail = false;
@@ -260,37 +524,26 @@ impl Block for Music {
                 } else {
                     self.player_avail = true;
 
-                    let textlen = title.chars().count()
-                        + self.separator.chars().count()
-                        + artist.chars().count();
-                    if textlen < self.max_width || !self.smart_trim {
-                        self.current_song
-                            .set_text(format!("{}{}{}", title, self.separator, artist));
-                    } else if title.is_empty() {
-                        // Only display artist, truncated appropriately
-                        self.current_song.set_text({
-                            match artist.char_indices().nth(self.max_width) {
-                                None => artist.to_string(),
-                                Some((i, _)) => {
-                                    artist.truncate(i);
-                                    artist.to_string()
-                                }
-                            }
-                        });
-                    } else if artist.is_empty() {
-                        // Only display title, truncated appropriately
-                        self.current_song.set_text({
-                            match title.char_indices().nth(self.max_width) {
-                                None => title.to_string(),
-                                Some((i, _)) => {
-                                    title.truncate(i);
-                                    title.to_string()
-                                }
-                            }
-                        });
+                    let values = map!(
+                        "{title}" => Some(title.clone()).filter(|title| !title.is_empty()),
+                        "{separator}" => Some(self.separator.clone()),
+                        "{artist}" => Some(artist.clone()).filter(|artist| !artist.is_empty())
+                    );
+                    let text = self.song_format.render_optional(&values)?;
+                    let textlen = text.chars().count();
+                    if let Some((ref line, _)) = lyric {
+                        self.current_song.set_text(line.clone());
+                    } else if textlen < self.max_width || !self.smart_trim {
+                        self.current_song.set_text(text);
                     } else {
+                        // The placeholder is trimmed like the value it stands in for
+                        for value in [&mut title, &mut artist] {
+                            if value.is_empty() {
+                                *value = self.song_format.placeholder().to_owned();
+                            }
+                        }
+
                         // Below code is by https://github.com/jgbyrne
-                        let text = format!("{}{}{}", title, self.separator, artist);
                         if textlen > self.max_width {
                             // overshoot: # of chars we need to trim
                             // substance: # of chars available for trimming
@@ -360,31 +613,78 @@ impl Block for Music {
                 }
             }
 
+            let playing = match c.get("org.mpris.MediaPlayer2.Player", "PlaybackStatus") {
+                Err(_) => None,
+                Ok(data) => {
+                    let data: Box<dyn RefArg> = data;
+                    Some(data.as_str().map(|s| s == "Playing").unwrap_or(true))
+                }
+            };
+            self.paused = playing == Some(false);
+
             if let Some(ref mut play) = self.play {
-                let data = c.get("org.mpris.MediaPlayer2.Player", "PlaybackStatus");
-                match data {
-                    Err(_) => play.set_icon("music_play"),
+                match playing {
+                    Some(true) => play.set_icon("music_pause"),
+                    _ => play.set_icon("music_play"),
+                }
+            }
+
+            if let (Some((_, Some(next_line))), Some(position), Some(true)) =
+                (&lyric, position, playing)
+            {
+                // Update when the next line is due, but keep polling for seeks
+                let delay = Duration::from_micros((next_line - position).max(0) as u64)
+                    .min(Duration::new(2, 0));
+                next = Some(next.map_or(delay, |d| d.min(delay)));
+            }
+
+            if self.show_volume {
+                // Not every player exposes its volume
+                let volume = match c.get("org.mpris.MediaPlayer2.Player", "Volume") {
                     Ok(data) => {
                         let data: Box<dyn RefArg> = data;
-                        let state = data;
-                        if state.as_str().map(|s| s != "Playing").unwrap_or(false) {
-                            play.set_icon("music_play")
-                        } else {
-                            play.set_icon("music_pause")
-                        }
+                        data.as_f64()
                     }
-                }
+                    Err(_) => None,
+                };
+                self.set_volume(volume);
+            }
+
+            self.position = position;
+            if self.show_time {
+                self.song_time.set_text(self.format_time());
+            }
+            if let (true, Some(position), Some(length)) =
+                (self.show_progress, position, self.length)
+            {
+                self.progress.set_text(format_progress(position, length));
             }
+            if (self.show_time || self.show_progress)
+                && playing == Some(true)
+                && self.position.is_some()
+            {
+                // Tick every second while the position is being displayed
+                let tick = Duration::from_secs(1);
+                return Ok(Some(next.map_or(tick, |d| d.min(tick)).into()));
+            }
+        }
+        if self.paused && self.player_avail {
+            // Resuming playback is signalled via D-Bus
+            return Ok(Some(self.paused_interval.into()));
         }
-        Ok(match (next, self.marquee) {
-            (Some(_), _) => next.map(|d| d.into()),
-            (None, _) => Some(Duration::new(2, 0).into()),
+        Ok(match next {
+            Some(next) => Some(next.into()),
+            // Any changes of an available player are signalled via D-Bus
+            None if self.player.is_none() || !self.player_avail => Some(Duration::new(2, 0).into()),
+            None => None,
         })
     }
 
     fn click(&mut self, event: &I3BarEvent) -> Result<()> {
         if let Some(ref name) = event.name {
             let action = match name as &str {
+                // The first click already paused or resumed playback
+                "play" if event.double_click => "Stop",
                 "play" => "PlayPause",
                 "next" => "Next",
                 "prev" => "Previous",
@@ -403,10 +703,55 @@ impl Block for Music {
                     .block_error("music", "failed to call method via D-Bus")
                     .map(|_| ())
             } else {
-                if name == "on_collapsed_click" && self.on_collapsed_click.is_some() {
-                    let command = self.on_collapsed_click.as_ref().unwrap();
-                    spawn_child_async("sh", &["-c", command])
+                if name == "volume" && self.player_avail {
+                    let step = match self.scrolling.to_logical_direction(event.button) {
+                        Some(LogicalDirection::Up) => 0.05,
+                        Some(LogicalDirection::Down) => -0.05,
+                        None => 0.0,
+                    };
+                    if let (Some(volume), true) = (self.volume, step != 0.0) {
+                        let volume = (volume + step).clamp(0.0, 1.0);
+                        self.dbus_conn
+                            .with_path(
+                                self.player.clone().unwrap(),
+                                "/org/mpris/MediaPlayer2",
+                                1000,
+                            )
+                            .set("org.mpris.MediaPlayer2.Player", "Volume", volume)
+                            .block_error("music", "failed to set volume via D-Bus")?;
+                        self.set_volume(Some(volume));
+                    }
+                }
+                // Without an `on_click`, the song toggles the time just like the time itself
+                if let ("song", Some(ref command)) = (name.as_str(), &self.on_click) {
+                    let url = self.url.clone().unwrap_or_default();
+                    spawn_child_async_with_env("sh", &["-c", command], &[("MUSIC_URL", &url)])
                         .block_error("music", "could not spawn child")?;
+                } else if (name == "song" || name == "song_time")
+                    && self.show_time
+                    && self.length.is_some()
+                {
+                    self.show_remaining = !self.show_remaining;
+                    self.song_time.set_text(self.format_time());
+                }
+                if name == "progress" && event.button == MouseButton::Left {
+                    self.seek(event)?;
+                }
+                if name == "icon" {
+                    if self.player_avail {
+                        // Not all players support raising their window, so ignore failures
+                        if let Ok(m) = Message::new_method_call(
+                            self.player.as_ref().unwrap(),
+                            "/org/mpris/MediaPlayer2",
+                            "org.mpris.MediaPlayer2",
+                            "Raise",
+                        ) {
+                            self.dbus_conn.send(m).ok();
+                        }
+                    } else if let Some(ref command) = self.on_collapsed_click {
+                        spawn_child_async("sh", &["-c", command])
+                            .block_error("music", "could not spawn child")?;
+                    }
                 }
                 Ok(())
             }
@@ -417,22 +762,50 @@ impl Block for Music {
 
     fn view(&self) -> Vec<&dyn I3BarWidget> {
         if self.player_avail {
-            let mut elements: Vec<&dyn I3BarWidget> = Vec::new();
-            elements.push(&self.current_song);
+            let mut song: Vec<&dyn I3BarWidget> = vec![&self.current_song];
+            if self.show_time && self.position.is_some() {
+                song.push(&self.song_time);
+            }
+            if self.show_progress && self.position.is_some() && self.length.is_some() {
+                song.push(&self.progress);
+            }
+            if self.show_volume && self.volume.is_some() {
+                song.push(&self.volume_widget);
+            }
+            if self.quality_format.is_some() {
+                song.push(&self.quality);
+            }
+
+            let mut buttons: Vec<&dyn I3BarWidget> = Vec::new();
             if let Some(ref prev) = self.prev {
-                elements.push(prev);
+                buttons.push(prev);
             }
             if let Some(ref play) = self.play {
-                elements.push(play);
+                buttons.push(play);
             }
             if let Some(ref next) = self.next {
-                elements.push(next);
+                buttons.push(next);
+            }
+
+            let (first, second) = match self.buttons_position {
+                ButtonsPosition::Left => (buttons, song),
+                ButtonsPosition::Right => (song, buttons),
+            };
+            let mut elements: Vec<&dyn I3BarWidget> = vec![&self.icon];
+            elements.extend(first);
+            // Without buttons there is nothing to separate
+            if let (Some(ref separator), false) = (
+                &self.buttons_separator,
+                self.prev.is_none() && self.play.is_none() && self.next.is_none(),
+            ) {
+                elements.push(separator);
             }
+            elements.extend(second);
             elements
         } else if self.current_song.is_empty() {
-            vec![&self.on_collapsed_click_widget]
+            vec![&self.icon]
         } else {
-            vec![&self.current_song]
+            vec![&self.icon, &self.current_song]
         }
     }
 }
@@ -451,10 +824,139 @@ fn extract_artist_from_value(value: &dyn arg::RefArg) -> Result<&str> {
     }
 }
 
+/// Formats a duration in microseconds, as used by MPRIS, as `m:ss`.
+fn format_microseconds(us: i64) -> String {
+    let secs = us.max(0) / 1_000_000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Renders the position within the track as a bar of ten characters.
+fn format_progress(position: i64, length: i64) -> String {
+    const WIDTH: i64 = 10;
+    let filled = match length {
+        0 => 0,
+        _ => (position * WIDTH / length).clamp(0, WIDTH),
+    };
+    (0..WIDTH)
+        .map(|i| if i < filled { '━' } else { '─' })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct SongMetadata {
+    title: String,
+    artist: String,
+    /// Track length in microseconds
+    length: Option<i64>,
+    url: Option<String>,
+    track_id: Option<String>,
+    /// Bitrate in kbit/s
+    bitrate: Option<u64>,
+    codec: Option<String>,
+}
+
+/// Synced lyrics of a track, read from an LRC file.
+struct Lyrics {
+    /// The `xesam:url` of the track the lyrics belong to
+    url: String,
+    /// Lines sorted by their timestamp in microseconds
+    lines: Vec<(i64, String)>,
+}
+
+impl Lyrics {
+    /// Reads the `.lrc` file next to a local track. Tracks without one get no lines.
+    fn load(url: String) -> Lyrics {
+        let lines = lrc_path(&url)
+            .and_then(|path| read_to_string(path).ok())
+            .map(|lrc| parse_lrc(&lrc))
+            .unwrap_or_default();
+        Lyrics { url, lines }
+    }
+
+    /// Returns the line for the given position and the timestamp of the following line, if
+    /// any. Before the first line and during instrumental breaks there is nothing to show.
+    fn line_at(&self, position: i64) -> Option<(&str, Option<i64>)> {
+        let index = self.lines.iter().rposition(|(time, _)| *time <= position)?;
+        let next_line = self.lines.get(index + 1).map(|(time, _)| *time);
+        match self.lines[index].1.as_str() {
+            "" => None,
+            line => Some((line, next_line)),
+        }
+    }
+}
+
+/// Returns the path of the `.lrc` file belonging to a `file://` URL.
+fn lrc_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("file://")?.as_bytes();
+
+    let mut decoded = Vec::with_capacity(path.len());
+    let mut i = 0;
+    while i < path.len() {
+        let escaped = path
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (path[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    let mut path = PathBuf::from(OsString::from_vec(decoded));
+    path.set_extension("lrc");
+    Some(path)
+}
+
+/// Parses the `[mm:ss.xx]` timestamped lines of an LRC file. A line may carry several
+/// timestamps, and tags such as `[ar:Artist]` are skipped.
+fn parse_lrc(lrc: &str) -> Vec<(i64, String)> {
+    let mut lines = Vec::new();
+
+    for line in lrc.lines() {
+        let mut rest = line.trim();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let end = match tag.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            if let Some(time) = parse_lrc_timestamp(&tag[..end]) {
+                times.push(time);
+            }
+            rest = &tag[end + 1..];
+        }
+        for time in times {
+            lines.push((time, rest.trim().to_owned()));
+        }
+    }
+
+    lines.sort_by_key(|(time, _)| *time);
+    lines
+}
+
+/// Parses a `mm:ss.xx` timestamp into microseconds.
+fn parse_lrc_timestamp(timestamp: &str) -> Option<i64> {
+    let mut parts = timestamp.splitn(2, ':');
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(minutes * 60_000_000 + (seconds * 1_000_000.0).round() as i64)
+}
+
 #[allow(clippy::borrowed_box)] // TODO: remove clippy workaround
-fn extract_from_metadata(metadata: &Box<dyn arg::RefArg>) -> Result<(String, String)> {
+fn extract_from_metadata(metadata: &Box<dyn arg::RefArg>) -> Result<SongMetadata> {
     let mut title = String::new();
     let mut artist = String::new();
+    let mut length = None;
+    let mut url = None;
+    let mut track_id = None;
+    let mut bitrate = None;
+    let mut codec = None;
 
     let mut iter = metadata
         .as_iter()
@@ -476,10 +978,43 @@ fn extract_from_metadata(metadata: &Box<dyn arg::RefArg>) -> Result<(String, Str
                         .block_error("music", "failed to extract metadata")?,
                 )
             }
-            _ => {}
+            "mpris:length" => length = value.as_i64().or_else(|| value.as_u64().map(|l| l as i64)),
+            "xesam:url" => url = value.as_str().map(String::from),
+            "mpris:trackid" => track_id = value.as_str().map(String::from),
+            // Not part of the MPRIS spec, so players use keys like `xesam:audioBitrate` or
+            // their own namespace
+            other => match other
+                .rsplit(':')
+                .next()
+                .unwrap_or(other)
+                .to_lowercase()
+                .as_str()
+            {
+                "audiobitrate" | "bitrate" => bitrate = value.as_f64().map(bitrate_kbps),
+                "audiocodec" | "codec" => codec = value.as_str().map(String::from),
+                _ => {}
+            },
         };
     }
-    Ok((title, artist))
+    Ok(SongMetadata {
+        title,
+        artist,
+        length,
+        url,
+        track_id,
+        bitrate,
+        codec,
+    })
+}
+
+/// Players report the bitrate either in bit/s or in kbit/s, which can be told apart by its
+/// magnitude.
+fn bitrate_kbps(bitrate: f64) -> u64 {
+    if bitrate >= 10_000.0 {
+        (bitrate / 1000.0).round() as u64
+    } else {
+        bitrate.round() as u64
+    }
 }
 
 fn get_first_available_player(connection: &Connection) -> Option<String> {
@@ -499,3 +1034,76 @@ fn get_first_available_player(connection: &Connection) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_format_progress() {
+        assert_eq!(format_progress(0, 100), "──────────");
+        assert_eq!(format_progress(45, 100), "━━━━──────");
+        assert_eq!(format_progress(100, 100), "━━━━━━━━━━");
+        assert_eq!(format_progress(10, 0), "──────────");
+    }
+
+    #[test]
+    fn test_extract_quality() {
+        let mut metadata: HashMap<String, arg::Variant<Box<dyn RefArg>>> = HashMap::new();
+        metadata.insert(
+            "xesam:title".to_owned(),
+            arg::Variant(Box::new("Song".to_owned())),
+        );
+        metadata.insert(
+            "xesam:audioBitrate".to_owned(),
+            arg::Variant(Box::new(320_000i32)),
+        );
+        metadata.insert(
+            "mpv:codec".to_owned(),
+            arg::Variant(Box::new("flac".to_owned())),
+        );
+        let metadata: Box<dyn RefArg> = Box::new(metadata);
+        let song = extract_from_metadata(&metadata).unwrap();
+        assert_eq!(song.title, "Song");
+        assert_eq!(song.bitrate, Some(320));
+        assert_eq!(song.codec, Some("flac".to_owned()));
+
+        assert_eq!(bitrate_kbps(1411.0), 1411);
+        assert_eq!(bitrate_kbps(256_000.0), 256);
+    }
+
+    #[test]
+    fn test_lrc_path() {
+        assert_eq!(
+            lrc_path("file:///home/user/Music/Some%20Song.flac"),
+            Some(PathBuf::from("/home/user/Music/Some Song.lrc"))
+        );
+        assert_eq!(lrc_path("https://example.com/stream"), None);
+    }
+
+    #[test]
+    fn test_parse_lrc() {
+        let lrc = Lyrics {
+            url: String::new(),
+            lines: parse_lrc(
+                "\
+[ar:Artist]
+[ti:Title]
+[00:12.00]First line
+[00:17.20][01:02.50]Chorus
+[00:30.00]
+",
+            ),
+        };
+        assert_eq!(lrc.lines.len(), 4);
+        assert_eq!(lrc.line_at(1_000_000), None);
+        assert_eq!(
+            lrc.line_at(12_000_000),
+            Some(("First line", Some(17_200_000)))
+        );
+        assert_eq!(lrc.line_at(20_000_000), Some(("Chorus", Some(30_000_000))));
+        assert_eq!(lrc.line_at(40_000_000), None);
+        assert_eq!(lrc.line_at(70_000_000), Some(("Chorus", None)));
+    }
+}
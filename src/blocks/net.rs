@@ -21,7 +21,8 @@ use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::subprocess::spawn_child_async;
 use crate::util::{
-    escape_pango_text, format_percent_bar, format_speed, format_vec_to_bar_graph, FormatTemplate,
+    escape_pango_text, format_percent_bar, format_speed, format_vec_to_bar_graph, AltFormat,
+    FormatTemplate,
 };
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
@@ -346,7 +347,7 @@ impl NetworkDevice {
 }
 
 pub struct Net {
-    format: FormatTemplate,
+    format: AltFormat<FormatTemplate>,
     output: ButtonWidget,
     config: Config,
     network: ButtonWidget,
@@ -369,7 +370,14 @@ pub struct Net {
     rx_buff: Vec<u64>,
     tx_bytes: u64,
     rx_bytes: u64,
+    total_tx: Option<String>,
+    total_rx: Option<String>,
+    /// Byte counts subtracted from the totals, non-zero if they start with the block
+    tx_offset: u64,
+    rx_offset: u64,
     use_bits: bool,
+    /// Whether speeds are suffixed with `/s`, as when `speed_unit` is set
+    per_second: bool,
     speed_min_unit: Unit,
     speed_digits: usize,
     active: bool,
@@ -394,12 +402,35 @@ impl Default for Unit {
     }
 }
 
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    Bits,
+    Bytes,
+}
+
 impl fmt::Display for Unit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
+/// Formats a speed like `format_speed`, optionally with a per-second unit like `Mbit/s`.
+fn format_rate(
+    bytes_speed: u64,
+    digits: usize,
+    min_unit: Unit,
+    use_bits: bool,
+    per_second: bool,
+) -> String {
+    let speed = format_speed(bytes_speed, digits, &min_unit.to_string(), use_bits);
+    match (per_second, use_bits) {
+        (false, _) => speed,
+        (true, true) => format!("{}bit/s", speed.trim_end_matches('b')),
+        (true, false) => format!("{}/s", speed),
+    }
+}
+
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct NetConfig {
@@ -413,6 +444,10 @@ pub struct NetConfig {
     #[serde(default = "NetConfig::default_format")]
     pub format: String,
 
+    /// Alternative format, toggled by left-clicking the block.
+    #[serde(default = "NetConfig::default_format_alt")]
+    pub format_alt: Option<String>,
+
     /// Which interface in /sys/class/net/ to read from.
     #[serde(default = "NetConfig::default_device")]
     pub device: String,
@@ -464,6 +499,11 @@ pub struct NetConfig {
     #[serde(default = "NetConfig::default_use_bits")]
     pub use_bits: bool,
 
+    /// Whether to show speeds in "bits" or "bytes" per second, with the unit suffixed by
+    /// `/s`. Takes precedence over `use_bits`.
+    #[serde(default = "NetConfig::default_speed_unit")]
+    pub speed_unit: Option<SpeedUnit>,
+
     /// Number of digits to show for throughput indiciators.
     #[serde(default = "NetConfig::default_speed_digits")]
     pub speed_digits: usize,
@@ -486,6 +526,10 @@ pub struct NetConfig {
 
     #[serde(default = "NetConfig::default_on_click")]
     pub on_click: Option<String>,
+
+    /// Whether the transferred totals count from the start of the block instead of from boot.
+    #[serde(default = "NetConfig::default_reset_on_start")]
+    pub reset_on_start: bool,
 }
 
 impl NetConfig {
@@ -497,6 +541,10 @@ impl NetConfig {
         "{speed_up} {speed_down}".to_owned()
     }
 
+    fn default_format_alt() -> Option<String> {
+        None
+    }
+
     fn default_device() -> String {
         match NetworkDevice::default_device() {
             Some(ref s) if !s.is_empty() => s.to_string(),
@@ -564,6 +612,10 @@ impl NetConfig {
         false
     }
 
+    fn default_speed_unit() -> Option<SpeedUnit> {
+        None
+    }
+
     fn default_speed_min_unit() -> Unit {
         Unit::K
     }
@@ -575,6 +627,10 @@ impl NetConfig {
     fn default_on_click() -> Option<String> {
         None
     }
+
+    fn default_reset_on_start() -> bool {
+        false
+    }
 }
 
 impl ConfigBlock for Net {
@@ -592,31 +648,47 @@ impl ConfigBlock for Net {
         let vpn = device.is_vpn();
         let id = Uuid::new_v4().to_simple().to_string();
 
-        let (_, net_config) = config
+        // Not found if the block is part of a group
+        let net_config = config
             .blocks
             .iter()
             .find(|(block_name, _)| block_name == "net")
-            .internal_error("net", "unexpected")?;
-
-        let format = if net_config.get("format").is_some() {
-            // if "format" option is present it will be preferred
-            block_config.format
-        } else if let Some(format) = old_format(&net_config) {
-            // Only choose those deprecated options which are true
-            format
-        } else {
-            // Default format
-            block_config.format
+            .map(|(_, net_config)| net_config);
+
+        let format = match net_config {
+            // Only choose those deprecated options which are true, unless the
+            // "format" option is present
+            Some(net_config) if net_config.get("format").is_none() => {
+                old_format(net_config).unwrap_or(block_config.format)
+            }
+            _ => block_config.format,
+        };
+        let format_alt = match block_config.format_alt {
+            Some(ref format_alt) => Some(
+                FormatTemplate::from_string(format_alt)
+                    .block_error("net", "Invalid format_alt specified")?,
+            ),
+            None => None,
         };
+        // Values are only collected if either format needs them
+        let formats = format!("{}{}", format, block_config.format_alt.unwrap_or_default());
 
         Ok(Net {
             id: id.clone(),
             update_interval: block_config.interval,
-            format: FormatTemplate::from_string(&format)
-                .block_error("net", "Invalid format specified")?,
-            output: ButtonWidget::new(config.clone(), "").with_text(""),
+            format: AltFormat::new(
+                FormatTemplate::from_string(&format)
+                    .block_error("net", "Invalid format specified")?,
+                format_alt,
+            ),
+            output: ButtonWidget::new(config.clone(), &id).with_text(""),
             config: config.clone(),
-            use_bits: block_config.use_bits,
+            use_bits: match block_config.speed_unit {
+                Some(SpeedUnit::Bits) => true,
+                Some(SpeedUnit::Bytes) => false,
+                None => block_config.use_bits,
+            },
+            per_second: block_config.speed_unit.is_some(),
             speed_min_unit: block_config.speed_min_unit,
             speed_digits: block_config.speed_digits,
             network: ButtonWidget::new(config, &id).with_icon(if wireless {
@@ -628,34 +700,34 @@ impl ConfigBlock for Net {
             }),
             // Might want to signal an error if the user wants the SSID of a
             // wired connection instead.
-            ssid: if wireless && format.contains("{ssid}") {
+            ssid: if wireless && formats.contains("{ssid}") {
                 Some(" ".to_string())
             } else {
                 None
             },
             max_ssid_width: block_config.max_ssid_width,
-            signal_strength: if wireless && format.contains("{signal_strength}") {
+            signal_strength: if wireless && formats.contains("{signal_strength}") {
                 Some(0.to_string())
             } else {
                 None
             },
-            signal_strength_bar: if wireless && format.contains("{signal_strength_bar}") {
+            signal_strength_bar: if wireless && formats.contains("{signal_strength_bar}") {
                 Some("".to_string())
             } else {
                 None
             },
             // TODO: a better way to deal with this?
-            bitrate: if format.contains("{bitrate}") {
+            bitrate: if formats.contains("{bitrate}") {
                 Some("".to_string())
             } else {
                 None
             },
-            ip_addr: if format.contains("{ip}") {
+            ip_addr: if formats.contains("{ip}") {
                 Some("".to_string())
             } else {
                 None
             },
-            ipv6_addr: if format.contains("{ipv6}") {
+            ipv6_addr: if formats.contains("{ipv6}") {
                 Some("".to_string())
             } else {
                 None
@@ -670,6 +742,26 @@ impl ConfigBlock for Net {
             tx_buff: vec![0; 10],
             rx_bytes: init_rx_bytes,
             tx_bytes: init_tx_bytes,
+            total_tx: if formats.contains("{total_tx}") {
+                Some("".to_string())
+            } else {
+                None
+            },
+            total_rx: if formats.contains("{total_rx}") {
+                Some("".to_string())
+            } else {
+                None
+            },
+            tx_offset: if block_config.reset_on_start {
+                init_tx_bytes
+            } else {
+                0
+            },
+            rx_offset: if block_config.reset_on_start {
+                init_rx_bytes
+            } else {
+                0
+            },
             active: true,
             hide_inactive: block_config.hide_inactive,
             hide_missing: block_config.hide_missing,
@@ -812,11 +904,12 @@ impl Net {
             self.tx_bytes = current_tx;
 
             if let Some(ref mut tx) = self.output_tx {
-                *tx = format_speed(
+                *tx = format_rate(
                     tx_bytes,
                     self.speed_digits,
-                    &self.speed_min_unit.to_string(),
+                    self.speed_min_unit,
                     self.use_bits,
+                    self.per_second,
                 );
             };
 
@@ -832,11 +925,12 @@ impl Net {
             self.rx_bytes = current_rx;
 
             if let Some(ref mut rx) = self.output_rx {
-                *rx = format_speed(
+                *rx = format_rate(
                     rx_bytes,
                     self.speed_digits,
-                    &self.speed_min_unit.to_string(),
+                    self.speed_min_unit,
                     self.use_bits,
+                    self.per_second,
                 );
             };
 
@@ -848,6 +942,18 @@ impl Net {
         }
         Ok(())
     }
+
+    fn update_totals(&mut self) -> Result<()> {
+        if let Some(ref mut total_tx) = self.total_tx {
+            let tx_bytes = self.device.tx_bytes()?.saturating_sub(self.tx_offset);
+            *total_tx = format_speed(tx_bytes, self.speed_digits, "B", false);
+        }
+        if let Some(ref mut total_rx) = self.total_rx {
+            let rx_bytes = self.device.rx_bytes()?.saturating_sub(self.rx_offset);
+            *total_rx = format_speed(rx_bytes, self.speed_digits, "B", false);
+        }
+        Ok(())
+    }
 }
 
 impl Block for Net {
@@ -886,6 +992,7 @@ impl Block for Net {
         }
 
         self.update_tx_rx()?;
+        self.update_totals()?;
 
         let empty_string = "".to_string();
         let s_up = format!(
@@ -917,11 +1024,13 @@ impl Block for Net {
             "{speed_up}" =>  &s_up,
             "{speed_down}" => &s_dn,
             "{graph_up}" =>  self.graph_tx.as_ref().unwrap_or(&empty_string),
-            "{graph_down}" =>  self.graph_rx.as_ref().unwrap_or(&empty_string)
+            "{graph_down}" =>  self.graph_rx.as_ref().unwrap_or(&empty_string),
+            "{total_tx}" => self.total_tx.as_ref().unwrap_or(&empty_string),
+            "{total_rx}" => self.total_rx.as_ref().unwrap_or(&empty_string)
         );
 
         self.output
-            .set_text(self.format.render_static_str(&values)?);
+            .set_text(self.format.get().render_static_str(&values)?);
 
         Ok(Some(self.update_interval.into()))
     }
@@ -940,9 +1049,13 @@ impl Block for Net {
         if let Some(ref name) = e.name {
             if name.as_str() == self.id {
                 if let MouseButton::Left = e.button {
-                    if let Some(ref cmd) = self.on_click {
-                        spawn_child_async("sh", &["-c", cmd])
-                            .block_error("net", "could not spawn child")?;
+                    // The throughput is calculated from the update interval, so
+                    // the other format is only shown on the next regular update.
+                    if !self.format.toggle() {
+                        if let Some(ref cmd) = self.on_click {
+                            spawn_child_async("sh", &["-c", cmd])
+                                .block_error("net", "could not spawn child")?;
+                        }
                     }
                 }
             }
@@ -1105,3 +1218,16 @@ fn maybe_ssid_convert(raw: Option<&[u8]>) -> Result<Option<String>> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(1_500_000, 3, Unit::K, false, false), "1.50MB");
+        assert_eq!(format_rate(1_500_000, 3, Unit::K, false, true), "1.50MB/s");
+        assert_eq!(format_rate(1_500_000, 3, Unit::K, true, true), "12.0Mbit/s");
+        assert_eq!(format_rate(10, 3, Unit::B, true, true), "80.0bit/s");
+    }
+}
@@ -27,7 +27,7 @@ use crate::util::FormatTemplate;
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
 
-enum NetworkState {
+pub(crate) enum NetworkState {
     Unknown,
     Asleep,
     Disconnected,
@@ -54,7 +54,7 @@ impl From<u32> for NetworkState {
     }
 }
 
-enum ActiveConnectionState {
+pub(crate) enum ActiveConnectionState {
     Unknown,
     Activating,
     Activated,
@@ -76,7 +76,7 @@ impl From<u32> for ActiveConnectionState {
 }
 
 impl ActiveConnectionState {
-    fn to_state(&self, good: State) -> State {
+    pub(crate) fn to_state(&self, good: State) -> State {
         match self {
             ActiveConnectionState::Activated => good,
             ActiveConnectionState::Activating => State::Warning,
@@ -164,7 +164,7 @@ impl fmt::Display for Ipv4Address {
     }
 }
 
-struct ConnectionManager {}
+pub(crate) struct ConnectionManager {}
 
 impl ConnectionManager {
     pub fn new() -> Self {
@@ -247,12 +247,12 @@ impl ConnectionManager {
 }
 
 #[derive(Clone)]
-struct NmConnection<'a> {
+pub(crate) struct NmConnection<'a> {
     path: Path<'a>,
 }
 
 impl<'a> NmConnection<'a> {
-    fn state(&self, c: &Connection) -> Result<ActiveConnectionState> {
+    pub(crate) fn state(&self, c: &Connection) -> Result<ActiveConnectionState> {
         let m = ConnectionManager::get(
             c,
             self.path.clone(),
@@ -267,7 +267,7 @@ impl<'a> NmConnection<'a> {
         Ok(ActiveConnectionState::from(state.0))
     }
 
-    fn id(&self, c: &Connection) -> Result<String> {
+    pub(crate) fn id(&self, c: &Connection) -> Result<String> {
         let m = ConnectionManager::get(
             c,
             self.path.clone(),
@@ -282,6 +282,36 @@ impl<'a> NmConnection<'a> {
         Ok(id.0)
     }
 
+    pub(crate) fn vpn(&self, c: &Connection) -> Result<bool> {
+        let m = ConnectionManager::get(
+            c,
+            self.path.clone(),
+            "org.freedesktop.NetworkManager.Connection.Active",
+            "Vpn",
+        )
+        .block_error("networkmanager", "Failed to retrieve connection VPN flag")?;
+
+        let vpn: Variant<bool> = m
+            .get1()
+            .block_error("networkmanager", "Failed to read Vpn")?;
+        Ok(vpn.0)
+    }
+
+    pub(crate) fn connection_type(&self, c: &Connection) -> Result<String> {
+        let m = ConnectionManager::get(
+            c,
+            self.path.clone(),
+            "org.freedesktop.NetworkManager.Connection.Active",
+            "Type",
+        )
+        .block_error("networkmanager", "Failed to retrieve connection type")?;
+
+        let connection_type: Variant<String> = m
+            .get1()
+            .block_error("networkmanager", "Failed to read Type")?;
+        Ok(connection_type.0)
+    }
+
     fn devices(&self, c: &Connection) -> Result<Vec<NmDevice>> {
         let m = ConnectionManager::get(
             c,
@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// The status of a sync folder, ordered by how much it needs attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SyncStatus {
+    Idle,
+    Syncing,
+    Error,
+}
+
+impl SyncStatus {
+    /// Parses a status of the socket API, like `OK` or `SYNC+SWM` for shared folders.
+    fn from_socket_api(status: &str) -> Option<Self> {
+        match status.trim_end_matches("+SWM") {
+            "OK" | "IGNORE" => Some(SyncStatus::Idle),
+            "SYNC" | "NEW" => Some(SyncStatus::Syncing),
+            "ERROR" => Some(SyncStatus::Error),
+            _ => None,
+        }
+    }
+}
+
+/// The sync folders of the client and their last known status.
+type Folders = BTreeMap<String, SyncStatus>;
+
+pub struct Nextcloud {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    folders: Arc<Mutex<Folders>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NextcloudConfig {
+    /// The socket of the desktop client, by default the one of the Nextcloud client
+    #[serde(default = "NextcloudConfig::default_socket_path")]
+    pub socket_path: Option<String>,
+
+    /// Format override
+    #[serde(default = "NextcloudConfig::default_format")]
+    pub format: String,
+}
+
+impl NextcloudConfig {
+    fn default_socket_path() -> Option<String> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{status}".to_owned()
+    }
+}
+
+/// Handles a message of the client, returning a folder whose status has to be asked for.
+fn handle_message(message: &str, folders: &mut Folders) -> Option<String> {
+    let (command, argument) = message.split_once(':')?;
+    match command {
+        "REGISTER_PATH" => {
+            let folder = argument.trim_end_matches('/').to_owned();
+            folders.entry(folder.clone()).or_insert(SyncStatus::Idle);
+            Some(folder)
+        }
+        "UNREGISTER_PATH" => {
+            folders.remove(argument.trim_end_matches('/'));
+            None
+        }
+        // Sent whenever something changed within the folder
+        "UPDATE_VIEW" => {
+            let folder = argument.trim_end_matches('/');
+            if folders.contains_key(folder) {
+                Some(folder.to_owned())
+            } else {
+                None
+            }
+        }
+        "STATUS" => {
+            let (status, path) = argument.split_once(':')?;
+            let status = SyncStatus::from_socket_api(status)?;
+            // Statuses of single files are only sent when asked for them
+            if let Some(folder) = folders.get_mut(path.trim_end_matches('/')) {
+                *folder = status;
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Listens to the client until it quits, keeping track of its folders.
+fn listen(socket_path: &str, folders: &Mutex<Folders>, notify: &dyn Fn()) -> Result<()> {
+    let mut socket = UnixStream::connect(socket_path)
+        .block_error("nextcloud", "failed to connect to the client")?;
+    let reader = BufReader::new(
+        socket
+            .try_clone()
+            .block_error("nextcloud", "failed to clone socket")?,
+    );
+    for line in reader.lines() {
+        let line = line.block_error("nextcloud", "failed to read from the client")?;
+        let (query, changed) = {
+            let mut folders = folders
+                .lock()
+                .block_error("nextcloud", "failed to acquire lock")?;
+            let before = folders.clone();
+            let query = handle_message(&line, &mut folders);
+            (query, *folders != before)
+        };
+        if let Some(folder) = query {
+            writeln!(socket, "RETRIEVE_FOLDER_STATUS:{}", folder)
+                .block_error("nextcloud", "failed to write to the client")?;
+        }
+        if changed {
+            notify();
+        }
+    }
+    Ok(())
+}
+
+impl ConfigBlock for Nextcloud {
+    type Config = NextcloudConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let socket_path = match block_config.socket_path {
+            Some(socket_path) => socket_path,
+            None => format!(
+                "{}/Nextcloud/socket",
+                env::var("XDG_RUNTIME_DIR")
+                    .block_error("nextcloud", "XDG_RUNTIME_DIR is not set")?
+            ),
+        };
+
+        let folders = Arc::new(Mutex::new(Folders::new()));
+        let folders_copy = folders.clone();
+        let id_copy = id.clone();
+        thread::Builder::new()
+            .name("nextcloud".into())
+            .spawn(move || {
+                let notify = || {
+                    tx.send(Task {
+                        id: id_copy.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap()
+                };
+                loop {
+                    // Errors are expected while the client is not running, so just try again later
+                    let _ = listen(&socket_path, &folders_copy, &notify);
+                    let was_connected = {
+                        let mut folders = folders_copy
+                            .lock()
+                            .expect("main thread paniced while holding nextcloud folders mutex");
+                        !std::mem::take(&mut *folders).is_empty()
+                    };
+                    if was_connected {
+                        notify();
+                    }
+                    thread::sleep(Duration::from_secs(5));
+                }
+            })
+            .unwrap();
+
+        Ok(Nextcloud {
+            text: TextWidget::new(config).with_icon("nextcloud"),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("nextcloud", "Invalid format specified")?,
+            folders,
+        })
+    }
+}
+
+impl Block for Nextcloud {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let folders = self
+            .folders
+            .lock()
+            .block_error("nextcloud", "failed to acquire lock")?;
+        let status = folders.values().max().copied().unwrap_or(SyncStatus::Idle);
+
+        let (name, icon, state) = match status {
+            SyncStatus::Idle => ("idle", "nextcloud", State::Idle),
+            SyncStatus::Syncing => ("syncing", "nextcloud_sync", State::Info),
+            SyncStatus::Error => ("error", "nextcloud_error", State::Critical),
+        };
+        let values = map!(
+            "{status}" => name.to_owned(),
+            "{folders}" => folders.len().to_string()
+        );
+        self.text.set_icon(icon);
+        self.text.set_state(state);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        // Updated whenever the client reports a change
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        // The client is not running without any folders
+        if self.folders.lock().unwrap().is_empty() {
+            vec![]
+        } else {
+            vec![&self.text]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_message() {
+        let mut folders = Folders::new();
+        assert_eq!(
+            handle_message("REGISTER_PATH:/home/user/Nextcloud/", &mut folders),
+            Some("/home/user/Nextcloud".to_owned())
+        );
+        assert_eq!(folders["/home/user/Nextcloud"], SyncStatus::Idle);
+
+        handle_message("STATUS:SYNC+SWM:/home/user/Nextcloud", &mut folders);
+        assert_eq!(folders["/home/user/Nextcloud"], SyncStatus::Syncing);
+        // Files within the folder do not change its status
+        handle_message("STATUS:ERROR:/home/user/Nextcloud/notes.txt", &mut folders);
+        assert_eq!(folders["/home/user/Nextcloud"], SyncStatus::Syncing);
+
+        assert_eq!(
+            handle_message("UPDATE_VIEW:/home/user/Nextcloud/", &mut folders),
+            Some("/home/user/Nextcloud".to_owned())
+        );
+        assert_eq!(handle_message("UPDATE_VIEW:/tmp", &mut folders), None);
+        assert_eq!(handle_message("GET_STRINGS:END", &mut folders), None);
+
+        handle_message("UNREGISTER_PATH:/home/user/Nextcloud/", &mut folders);
+        assert!(folders.is_empty());
+    }
+}
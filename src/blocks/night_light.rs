@@ -0,0 +1,224 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use regex::Regex;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::process::find_processes;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// The program adjusting the color temperature of the screen.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NightLightBackend {
+    #[default]
+    Redshift,
+    Gammastep,
+    Wlsunset,
+}
+
+impl NightLightBackend {
+    fn program(self) -> &'static str {
+        match self {
+            NightLightBackend::Redshift => "redshift",
+            NightLightBackend::Gammastep => "gammastep",
+            NightLightBackend::Wlsunset => "wlsunset",
+        }
+    }
+
+    /// Asks for the current period and color temperature. wlsunset cannot tell them.
+    fn status(self) -> NightLightStatus {
+        match self {
+            NightLightBackend::Redshift | NightLightBackend::Gammastep => {
+                match Command::new(self.program()).arg("-p").output() {
+                    Ok(output) => parse_print_mode(&String::from_utf8_lossy(&output.stdout)),
+                    Err(_) => NightLightStatus::default(),
+                }
+            }
+            NightLightBackend::Wlsunset => NightLightStatus::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct NightLightStatus {
+    period: Option<String>,
+    temperature: Option<u32>,
+}
+
+/// Parses the output of the print mode of redshift and gammastep, which contains lines
+/// like `Period: Night` and `Color temperature: 4500K`.
+fn parse_print_mode(output: &str) -> NightLightStatus {
+    let mut status = NightLightStatus::default();
+    for line in output.lines() {
+        if let Some(period) = line.strip_prefix("Period: ") {
+            status.period = Some(period.trim().to_owned());
+        } else if let Some(temperature) = line.strip_prefix("Color temperature: ") {
+            status.temperature = temperature.trim().trim_end_matches('K').parse().ok();
+        }
+    }
+    status
+}
+
+pub struct NightLight {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    backend: NightLightBackend,
+    pattern: Regex,
+    /// The process of the backend when it was last seen
+    pid: Option<u32>,
+    /// Whether the adjustment is on. It cannot be asked for, so it is assumed to be on
+    /// whenever the backend starts.
+    enabled: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NightLightConfig {
+    /// The program adjusting the color temperature, one of "redshift", "gammastep" or "wlsunset"
+    #[serde(default)]
+    pub backend: NightLightBackend,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "NightLightConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "NightLightConfig::default_format")]
+    pub format: String,
+}
+
+impl NightLightConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{temperature}".to_owned()
+    }
+}
+
+impl NightLight {
+    fn find_backend(&self) -> Option<u32> {
+        find_processes(Path::new("/proc"), &self.pattern, false)
+            .first()
+            .copied()
+    }
+}
+
+impl ConfigBlock for NightLight {
+    type Config = NightLightConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let backend = block_config.backend;
+        Ok(NightLight {
+            output: ButtonWidget::new(config, &id).with_icon("night_light"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("night_light", "Invalid format specified")?,
+            backend,
+            pattern: Regex::new(&format!("^{}$", backend.program()))
+                .block_error("night_light", "invalid process pattern")?,
+            pid: None,
+            enabled: false,
+        })
+    }
+}
+
+impl Block for NightLight {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let pid = self.find_backend();
+        if pid != self.pid {
+            self.pid = pid;
+            self.enabled = pid.is_some();
+        }
+
+        let status = if self.enabled {
+            self.backend.status()
+        } else {
+            NightLightStatus::default()
+        };
+        let values = map!(
+            "{temperature}" => status
+                .temperature
+                .map(|temperature| format!("{}K", temperature))
+                .unwrap_or_default(),
+            "{period}" => status.period.unwrap_or_default()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        if self.enabled {
+            self.output.set_icon("night_light");
+            self.output.set_state(State::Info);
+        } else {
+            self.output.set_icon("night_light_off");
+            self.output.set_state(State::Idle);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) || event.button != MouseButton::Left {
+            return Ok(());
+        }
+        // redshift and gammastep toggle the adjustment on SIGUSR1, while wlsunset cycles
+        // between forcing the high and low temperature and following the time of day
+        if let Some(pid) = self.find_backend() {
+            kill(Pid::from_raw(pid as i32), Signal::SIGUSR1)
+                .block_error("night_light", "failed to signal the backend")?;
+            self.pid = Some(pid);
+            if self.backend != NightLightBackend::Wlsunset {
+                self.enabled = !self.enabled;
+            }
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_print_mode() {
+        let output = "Location: 52.52 N, 13.40 E\nPeriod: Transition (71.48% day)\nColor temperature: 5604K\nBrightness: 1.00\n";
+        assert_eq!(
+            parse_print_mode(output),
+            NightLightStatus {
+                period: Some("Transition (71.48% day)".to_owned()),
+                temperature: Some(5604),
+            }
+        );
+        assert_eq!(parse_print_mode(""), NightLightStatus::default());
+    }
+}
@@ -0,0 +1,180 @@
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use dbus::ffidisp::{BusType, Connection, ConnectionItem};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::networkmanager::{ActiveConnectionState, ConnectionManager};
+use crate::blocks::Update;
+use crate::blocks::{Block, ConfigBlock};
+use crate::config::Config;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct NmVpn {
+    id: String,
+    text: ButtonWidget,
+    dbus_conn: Connection,
+    manager: ConnectionManager,
+    format: FormatTemplate,
+    connection: Option<String>,
+    active: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NmVpnConfig {
+    /// Format override
+    #[serde(default = "NmVpnConfig::default_format")]
+    pub format: String,
+
+    /// Name of the NetworkManager connection to toggle on click
+    #[serde(default = "NmVpnConfig::default_connection")]
+    pub connection: Option<String>,
+}
+
+impl NmVpnConfig {
+    fn default_format() -> String {
+        "{name}".to_owned()
+    }
+
+    fn default_connection() -> Option<String> {
+        None
+    }
+}
+
+impl ConfigBlock for NmVpn {
+    type Config = NmVpnConfig;
+
+    fn new(block_config: Self::Config, config: Config, send: Sender<Task>) -> Result<Self> {
+        let id: String = Uuid::new_v4().to_simple().to_string();
+        let id_copy = id.clone();
+        let dbus_conn = Connection::get_private(BusType::System)
+            .block_error("nm_vpn", "failed to establish D-Bus connection")?;
+
+        thread::Builder::new()
+            .name("nm_vpn".into())
+            .spawn(move || {
+                let c = Connection::get_private(BusType::System).unwrap();
+                let rule = "type='signal',\
+                        path='/org/freedesktop/NetworkManager',\
+                        interface='org.freedesktop.NetworkManager',\
+                        member='PropertiesChanged'";
+
+                c.add_match(rule).unwrap();
+
+                loop {
+                    for event in c.iter(300_000) {
+                        match event {
+                            ConnectionItem::Nothing => (),
+                            _ => send
+                                .send(Task {
+                                    id: id_copy.clone(),
+                                    update_time: Instant::now(),
+                                })
+                                .unwrap(),
+                        }
+                    }
+                }
+            })
+            .unwrap();
+
+        Ok(NmVpn {
+            text: ButtonWidget::new(config, &id).with_icon("vpn_off"),
+            id,
+            dbus_conn,
+            manager: ConnectionManager::new(),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("nm_vpn", "Invalid format specified")?,
+            connection: block_config.connection,
+            active: Vec::new(),
+        })
+    }
+}
+
+impl Block for NmVpn {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mut names = Vec::new();
+        let mut degraded = None;
+
+        for conn in self
+            .manager
+            .active_connections(&self.dbus_conn)
+            .unwrap_or_else(|_| Vec::new())
+        {
+            // Wireguard connections are not flagged as VPNs by NetworkManager
+            let is_vpn = conn.vpn(&self.dbus_conn).unwrap_or(false)
+                || conn
+                    .connection_type(&self.dbus_conn)
+                    .map(|t| t == "wireguard")
+                    .unwrap_or(false);
+            if !is_vpn {
+                continue;
+            }
+
+            if let Ok(name) = conn.id(&self.dbus_conn) {
+                names.push(name);
+            }
+            match conn.state(&self.dbus_conn) {
+                Ok(ActiveConnectionState::Activated) => (),
+                Ok(conn_state) => degraded = Some(conn_state.to_state(State::Good)),
+                Err(_) => degraded = Some(State::Critical),
+            }
+        }
+
+        let state = if names.is_empty() {
+            State::Idle
+        } else {
+            degraded.unwrap_or(State::Good)
+        };
+
+        let name = if names.is_empty() {
+            self.connection.clone().unwrap_or_default()
+        } else {
+            names.join(", ")
+        };
+        let values = map!("{name}" => name);
+
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_icon(if names.is_empty() {
+            "vpn_off"
+        } else {
+            "vpn_on"
+        });
+        self.text.set_state(state);
+        self.active = names;
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(&self.id) {
+            if let (MouseButton::Left, Some(ref connection)) = (e.button, &self.connection) {
+                let action = if self.active.contains(connection) {
+                    "down"
+                } else {
+                    "up"
+                };
+                spawn_child_async("nmcli", &["connection", action, connection])
+                    .block_error("nm_vpn", "could not spawn nmcli")?;
+            }
+        }
+
+        Ok(())
+    }
+}
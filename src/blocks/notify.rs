@@ -0,0 +1,185 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// The notification daemon whose do not disturb mode is shown.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyBackend {
+    #[default]
+    Dunst,
+    Mako,
+    Swaync,
+}
+
+impl NotifyBackend {
+    /// The command that tells whether do not disturb is on.
+    fn state_command(self) -> &'static [&'static str] {
+        match self {
+            NotifyBackend::Dunst => &["dunstctl", "is-paused"],
+            NotifyBackend::Mako => &["makoctl", "mode"],
+            NotifyBackend::Swaync => &["swaync-client", "--get-dnd", "--skip-wait"],
+        }
+    }
+
+    /// The command that toggles do not disturb.
+    fn toggle_command(self) -> &'static [&'static str] {
+        match self {
+            NotifyBackend::Dunst => &["dunstctl", "set-paused", "toggle"],
+            NotifyBackend::Mako => &["makoctl", "mode", "-t", "do-not-disturb"],
+            NotifyBackend::Swaync => &["swaync-client", "--toggle-dnd", "--skip-wait"],
+        }
+    }
+
+    /// Parses the output of the state command. mako has no do not disturb mode of its own,
+    /// so it is on while the `do-not-disturb` mode is active.
+    fn parse_state(self, output: &str) -> Option<bool> {
+        match self {
+            NotifyBackend::Dunst | NotifyBackend::Swaync => match output.trim() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+            NotifyBackend::Mako => Some(output.lines().any(|mode| mode == "do-not-disturb")),
+        }
+    }
+}
+
+/// Runs `command` of `backend` and returns its output.
+fn run(backend: NotifyBackend, command: &[&str]) -> Result<String> {
+    let output = Command::new(command[0])
+        .args(&command[1..])
+        .output()
+        .block_error("notify", &format!("failed to run {}", command[0]))?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "notify".to_owned(),
+            format!("`{}` failed, is {:?} running?", command.join(" "), backend),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub struct Notify {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    backend: NotifyBackend,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// The notification daemon, one of "dunst", "mako" or "swaync"
+    #[serde(default)]
+    pub backend: NotifyBackend,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "NotifyConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "NotifyConfig::default_format")]
+    pub format: String,
+}
+
+impl NotifyConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "".to_owned()
+    }
+}
+
+impl ConfigBlock for Notify {
+    type Config = NotifyConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(Notify {
+            output: ButtonWidget::new(config, &id).with_icon("notification"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("notify", "Invalid format specified")?,
+            backend: block_config.backend,
+        })
+    }
+}
+
+impl Block for Notify {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let output = run(self.backend, self.backend.state_command())?;
+        let dnd = self
+            .backend
+            .parse_state(&output)
+            .block_error("notify", "unexpected do not disturb state")?;
+
+        let values = map!(
+            "{state}" => if dnd { "on" } else { "off" }.to_owned()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        if dnd {
+            self.output.set_icon("notification_off");
+            self.output.set_state(State::Warning);
+        } else {
+            self.output.set_icon("notification");
+            self.output.set_state(State::Idle);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            run(self.backend, self.backend.toggle_command())?;
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(NotifyBackend::Dunst.parse_state("true\n"), Some(true));
+        assert_eq!(NotifyBackend::Swaync.parse_state("false\n"), Some(false));
+        assert_eq!(NotifyBackend::Dunst.parse_state(""), None);
+        assert_eq!(
+            NotifyBackend::Mako.parse_state("default\ndo-not-disturb\n"),
+            Some(true)
+        );
+        assert_eq!(NotifyBackend::Mako.parse_state("default\n"), Some(false));
+    }
+}
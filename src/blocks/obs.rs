@@ -0,0 +1,307 @@
+//! A block for displaying the recording and streaming status of OBS Studio.
+//!
+//! The block talks to the obs-websocket plugin (protocol version 5), which is bundled with OBS
+//! since version 28.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::websocket::{base64, sha256, WebSocket};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// Subscribe to the `Outputs` event category only.
+const EVENT_SUBSCRIPTION_OUTPUTS: u64 = 1 << 6;
+
+#[derive(Debug, Default)]
+pub struct ObsStatus {
+    pub connected: bool,
+    /// When the current recording has started
+    pub recording: Option<Instant>,
+    /// When the current stream has started
+    pub streaming: Option<Instant>,
+}
+
+pub struct Obs {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    status: Arc<Mutex<ObsStatus>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ObsConfig {
+    /// The host obs-websocket is listening on
+    #[serde(default = "ObsConfig::default_host")]
+    pub host: String,
+
+    /// The port obs-websocket is listening on
+    #[serde(default = "ObsConfig::default_port")]
+    pub port: u16,
+
+    /// The obs-websocket server password, if authentication is enabled
+    #[serde(default = "ObsConfig::default_password")]
+    pub password: Option<String>,
+
+    /// Format override
+    #[serde(default = "ObsConfig::default_format")]
+    pub format: String,
+}
+
+impl ObsConfig {
+    fn default_host() -> String {
+        "localhost".to_owned()
+    }
+
+    fn default_port() -> u16 {
+        4455
+    }
+
+    fn default_password() -> Option<String> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{status} {time}".to_owned()
+    }
+}
+
+/// Computes the authentication string for the `Identify` message from the server's `Hello`.
+fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = base64(&sha256(format!("{}{}", password, salt).as_bytes()));
+    base64(&sha256(format!("{}{}", secret, challenge).as_bytes()))
+}
+
+fn receive(ws: &mut WebSocket) -> Result<Value> {
+    serde_json::from_slice(&ws.receive()?).block_error("obs", "failed to parse OBS message")
+}
+
+/// Returns when an output has started from the `outputActive` and `outputDuration` (in
+/// milliseconds) fields of a status response or event.
+fn output_start(data: &Value) -> Option<Instant> {
+    if !data["outputActive"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    let elapsed = Duration::from_millis(data["outputDuration"].as_u64().unwrap_or(0));
+    Instant::now().checked_sub(elapsed)
+}
+
+/// Connects to OBS and keeps `status` up to date until the connection fails.
+fn listen(
+    host: &str,
+    port: u16,
+    password: &Option<String>,
+    status: &Mutex<ObsStatus>,
+    notify: &dyn Fn(),
+) -> Result<()> {
+    let mut ws = WebSocket::connect("obs", host, port, "obswebsocket.json")?;
+
+    // Hello
+    let hello = receive(&mut ws)?;
+    let mut identify = json!({
+        "rpcVersion": 1,
+        "eventSubscriptions": EVENT_SUBSCRIPTION_OUTPUTS,
+    });
+    let auth = &hello["d"]["authentication"];
+    if !auth.is_null() {
+        let password = password
+            .as_ref()
+            .block_error("obs", "OBS requires a password")?;
+        identify["authentication"] = Value::from(authentication(
+            password,
+            auth["salt"].as_str().unwrap_or_default(),
+            auth["challenge"].as_str().unwrap_or_default(),
+        ));
+    }
+    ws.send(&json!({"op": 1, "d": identify}).to_string())?;
+
+    for request in &["GetRecordStatus", "GetStreamStatus"] {
+        ws.send(
+            &json!({
+                "op": 6,
+                "d": {"requestType": request, "requestId": request},
+            })
+            .to_string(),
+        )?;
+    }
+
+    loop {
+        let message = receive(&mut ws)?;
+        let data = &message["d"];
+        let mut status = status
+            .lock()
+            .expect("main thread paniced while holding obs status mutex");
+        match message["op"].as_u64() {
+            // Identified
+            Some(2) => status.connected = true,
+            // Event
+            Some(5) => match data["eventType"].as_str() {
+                Some("RecordStateChanged") => status.recording = output_start(&data["eventData"]),
+                Some("StreamStateChanged") => status.streaming = output_start(&data["eventData"]),
+                _ => continue,
+            },
+            // RequestResponse
+            Some(7) => match data["requestType"].as_str() {
+                Some("GetRecordStatus") => status.recording = output_start(&data["responseData"]),
+                Some("GetStreamStatus") => status.streaming = output_start(&data["responseData"]),
+                _ => continue,
+            },
+            _ => continue,
+        }
+        drop(status);
+        notify();
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Keeps the returned status up to date in a background thread, reconnecting whenever OBS
+/// is restarted, and requests an update of the block `id` on every change.
+pub fn watch(
+    host: String,
+    port: u16,
+    password: Option<String>,
+    id: String,
+    tx: Sender<Task>,
+) -> Arc<Mutex<ObsStatus>> {
+    let status = Arc::new(Mutex::new(ObsStatus::default()));
+    let status_copy = status.clone();
+    thread::Builder::new()
+        .name("obs".into())
+        .spawn(move || {
+            let notify = || {
+                tx.send(Task {
+                    id: id.clone(),
+                    update_time: Instant::now(),
+                })
+                .unwrap()
+            };
+            loop {
+                // Errors are expected while OBS is not running, so just try again later
+                let _ = listen(&host, port, &password, &status_copy, &notify);
+                let was_connected = {
+                    let mut status = status_copy
+                        .lock()
+                        .expect("main thread paniced while holding obs status mutex");
+                    std::mem::take(&mut *status).connected
+                };
+                if was_connected {
+                    notify();
+                }
+                thread::sleep(Duration::from_secs(5));
+            }
+        })
+        .unwrap();
+    status
+}
+
+impl ConfigBlock for Obs {
+    type Config = ObsConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let format = FormatTemplate::from_string(&block_config.format)
+            .block_error("obs", "Invalid format specified")?;
+        let status = watch(
+            block_config.host,
+            block_config.port,
+            block_config.password,
+            id.clone(),
+            tx,
+        );
+
+        Ok(Obs {
+            id,
+            text: TextWidget::new(config).with_icon("obs"),
+            format,
+            status,
+        })
+    }
+}
+
+impl Block for Obs {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let status = self
+            .status
+            .lock()
+            .block_error("obs", "failed to acquire lock")?;
+
+        let active: Vec<&str> = [(status.recording, "REC"), (status.streaming, "LIVE")]
+            .iter()
+            .filter(|(start, _)| start.is_some())
+            .map(|&(_, name)| name)
+            .collect();
+
+        if !status.connected || active.is_empty() {
+            self.text.set_text(String::new());
+            self.text.set_state(State::Idle);
+            return Ok(None);
+        }
+
+        // Show the time of whichever output has been running the longest
+        let start = status.recording.into_iter().chain(status.streaming).min();
+        let elapsed = start.map(|s| s.elapsed()).unwrap_or_default();
+        let values = map!(
+            "{status}" => active.join(" "),
+            "{time}" => format_duration(elapsed)
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(if status.streaming.is_some() {
+            State::Critical
+        } else {
+            State::Warning
+        });
+
+        Ok(Some(Duration::from_secs(1).into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authentication() {
+        assert_eq!(
+            authentication(
+                "supersecretpassword",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(59)), "0:59");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1:02:03");
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::env;
 use std::ffi::OsString;
 use std::fs;
@@ -17,7 +18,7 @@ use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
-use crate::scheduler::Task;
+use crate::scheduler::{Backoff, Task};
 use crate::util::{has_command, FormatTemplate};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
@@ -25,7 +26,7 @@ use crate::widgets::button::ButtonWidget;
 pub struct Pacman {
     output: ButtonWidget,
     id: String,
-    update_interval: Duration,
+    backoff: Backoff,
     format: FormatTemplate,
     format_singular: FormatTemplate,
     format_up_to_date: FormatTemplate,
@@ -140,7 +141,7 @@ impl ConfigBlock for Pacman {
     ) -> Result<Self> {
         Ok(Pacman {
             id: Uuid::new_v4().to_simple().to_string(),
-            update_interval: block_config.interval,
+            backoff: Backoff::new(block_config.interval),
             format: FormatTemplate::from_string(&block_config.format)
                 .block_error("pacman", "Invalid format specified for pacman::format")?,
             format_singular: FormatTemplate::from_string(&block_config.format_singular)
@@ -289,19 +290,12 @@ fn has_critical_update(updates: &str, regex: &Regex) -> bool {
     updates.lines().filter(|line| regex.is_match(line)).count() > 0
 }
 
-impl Block for Pacman {
-    fn id(&self) -> &str {
-        &self.id
-    }
-
-    fn view(&self) -> Vec<&dyn I3BarWidget> {
-        vec![&self.output]
-    }
-
-    fn update(&mut self) -> Result<Option<Update>> {
-        let (formatting_map, critical, cum_count) = match &self.watched {
+impl Pacman {
+    /// Returns the format values, whether there is a critical update, and the total number of
+    /// updates.
+    fn check_updates(&self) -> Result<(HashMap<&str, usize>, bool, usize)> {
+        Ok(match &self.watched {
             Watched::Pacman => {
-                check_fakeroot_command_exists()?;
                 let pacman_available_updates = get_pacman_available_updates()?;
                 let pacman_count = get_update_count(&pacman_available_updates);
                 let formatting_map = map!("{count}" => pacman_count, "{pacman}" => pacman_count);
@@ -320,7 +314,6 @@ impl Block for Pacman {
                 (formatting_map, critical, aur_count)
             }
             Watched::Both(aur_command) => {
-                check_fakeroot_command_exists()?;
                 let pacman_available_updates = get_pacman_available_updates()?;
                 let aur_available_updates = get_aur_available_updates(&aur_command)?;
                 let pacman_count = get_update_count(&pacman_available_updates);
@@ -332,6 +325,32 @@ impl Block for Pacman {
                 });
                 (formatting_map, critical, pacman_count + aur_count)
             }
+        })
+    }
+}
+
+impl Block for Pacman {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn update(&mut self) -> Result<Option<Update>> {
+        // A missing fakeroot is a setup problem rather than a temporary failure
+        if let Watched::Pacman | Watched::Both(_) = self.watched {
+            check_fakeroot_command_exists()?;
+        }
+        let (formatting_map, critical, cum_count) = match self.check_updates() {
+            Ok(result) => result,
+            Err(_) => {
+                // Most likely there is no network connection, so don't retry too often
+                self.output.set_text("×".to_string());
+                self.output.set_state(State::Critical);
+                return Ok(Some(self.backoff.failure().into()));
+            }
         };
         self.output.set_text(match cum_count {
             0 => self.format_up_to_date.render_static_str(&formatting_map)?,
@@ -348,7 +367,7 @@ impl Block for Pacman {
                 }
             }
         });
-        Ok(Some(self.update_interval.into()))
+        Ok(Some(self.backoff.success().into()))
     }
 
     fn click(&mut self, event: &I3BarEvent) -> Result<()> {
@@ -0,0 +1,295 @@
+use std::fs::read_to_string;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::os::unix::io::FromRawFd;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use nix::libc;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// `Ok(None)` means the host did not answer within the timeout.
+type PingResult = Result<Option<Duration>>;
+
+pub struct Ping {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    warning: f64,
+    critical: f64,
+    result: Arc<Mutex<Option<PingResult>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PingConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "PingConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// How long to wait for an answer
+    #[serde(
+        default = "PingConfig::default_timeout",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub timeout: Duration,
+
+    /// The host to ping, defaults to the default gateway
+    #[serde(default = "PingConfig::default_host")]
+    pub host: Option<String>,
+
+    /// Minimum latency in milliseconds, where state is set to warning
+    #[serde(default = "PingConfig::default_warning")]
+    pub warning: f64,
+
+    /// Minimum latency in milliseconds, where state is set to critical
+    #[serde(default = "PingConfig::default_critical")]
+    pub critical: f64,
+
+    /// Format override
+    #[serde(default = "PingConfig::default_format")]
+    pub format: String,
+}
+
+impl PingConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_timeout() -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn default_host() -> Option<String> {
+        None
+    }
+
+    fn default_warning() -> f64 {
+        50.0
+    }
+
+    fn default_critical() -> f64 {
+        150.0
+    }
+
+    fn default_format() -> String {
+        "{ping}ms".to_owned()
+    }
+}
+
+/// Reads the IPv4 default gateway from the kernel routing table.
+fn default_gateway() -> Option<IpAddr> {
+    let routes = read_to_string("/proc/net/route").ok()?;
+    routes.lines().skip(1).find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        match columns.as_slice() {
+            [_, "00000000", gateway, ..] => {
+                // The kernel prints the address as a native-endian integer
+                let gateway = u32::from_str_radix(gateway, 16).ok()?;
+                Some(IpAddr::V4(Ipv4Addr::from(gateway.to_ne_bytes())))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Sends an ICMP echo request through an unprivileged ICMP datagram socket. This requires the
+/// user's group to be included in the `net.ipv4.ping_group_range` sysctl.
+fn icmp_ping(addr: Ipv4Addr, seq: u16, timeout: Duration) -> io::Result<Option<Duration>> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_ICMP) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // The socket API of ICMP datagram sockets is the same as for UDP. The kernel takes care
+    // of the identifier and the checksum.
+    let socket = unsafe { UdpSocket::from_raw_fd(fd) };
+
+    let seq = seq.to_be_bytes();
+    let request = [8, 0, 0, 0, 0, 0, seq[0], seq[1]];
+    let start = Instant::now();
+    socket.send_to(&request, SocketAddr::new(IpAddr::V4(addr), 0))?;
+
+    let mut reply = [0; 64];
+    loop {
+        let remaining = match timeout.checked_sub(start.elapsed()) {
+            Some(remaining) if remaining > Duration::from_millis(0) => remaining,
+            _ => return Ok(None),
+        };
+        socket.set_read_timeout(Some(remaining))?;
+        match socket.recv(&mut reply) {
+            // Echo reply with our sequence number
+            Ok(len) if len >= 8 && reply[0] == 0 && reply[6..8] == seq => {
+                return Ok(Some(start.elapsed()))
+            }
+            Ok(_) => (),
+            Err(ref e)
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut =>
+            {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Extracts the round-trip time of the output of `ping -c1`.
+fn parse_ping_output(output: &str) -> Option<Duration> {
+    let time = output.split("time=").nth(1)?;
+    let ms: f64 = time.split_whitespace().next()?.parse().ok()?;
+    Some(Duration::from_micros((ms * 1000.0).round() as u64))
+}
+
+fn command_ping(host: &str, timeout: Duration) -> PingResult {
+    let output = Command::new("ping")
+        .args(["-c1", "-W", &timeout.as_secs().max(1).to_string(), host])
+        .output()
+        .block_error("ping", "failed to run ping")?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(parse_ping_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn ping(host: &Option<String>, seq: u16, timeout: Duration) -> PingResult {
+    let (addr, host) = match host {
+        Some(host) => {
+            let addr = (host.as_str(), 0)
+                .to_socket_addrs()
+                .block_error("ping", &format!("failed to resolve {}", host))?
+                .map(|addr| addr.ip())
+                .find(IpAddr::is_ipv4);
+            (addr, host.clone())
+        }
+        None => {
+            let addr = default_gateway().block_error("ping", "no default gateway")?;
+            (Some(addr), addr.to_string())
+        }
+    };
+
+    if let Some(IpAddr::V4(addr)) = addr {
+        if let Ok(rtt) = icmp_ping(addr, seq, timeout) {
+            return Ok(rtt);
+        }
+    }
+
+    // ICMP sockets are not permitted or the host has no IPv4 address
+    command_ping(&host, timeout)
+}
+
+impl ConfigBlock for Ping {
+    type Config = PingConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let result = Arc::new(Mutex::new(None));
+
+        // Waiting for an answer must not stall the bar
+        let result_copy = result.clone();
+        let id_copy = id.clone();
+        let host = block_config.host;
+        let timeout = block_config.timeout;
+        let interval = block_config.interval;
+        thread::Builder::new()
+            .name("ping".into())
+            .spawn(move || {
+                for seq in (0..=u16::MAX).cycle() {
+                    let rtt = ping(&host, seq, timeout);
+                    *result_copy
+                        .lock()
+                        .expect("main thread paniced while holding ping result mutex") = Some(rtt);
+                    tx.send(Task {
+                        id: id_copy.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+                    thread::sleep(interval);
+                }
+            })
+            .unwrap();
+
+        Ok(Ping {
+            id,
+            text: TextWidget::new(config).with_icon("ping").with_text("..."),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("ping", "Invalid format specified")?,
+            warning: block_config.warning,
+            critical: block_config.critical,
+            result,
+        })
+    }
+}
+
+impl Block for Ping {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let result = self
+            .result
+            .lock()
+            .block_error("ping", "failed to acquire lock")?;
+
+        match *result {
+            Some(Ok(Some(rtt))) => {
+                let ms = rtt.as_secs_f64() * 1000.0;
+                let values = map!("{ping}" => format!("{:.1}", ms));
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(match ms {
+                    x if x >= self.critical => State::Critical,
+                    x if x >= self.warning => State::Warning,
+                    _ => State::Good,
+                });
+            }
+            Some(Ok(None)) => {
+                self.text.set_text("timeout".to_owned());
+                self.text.set_state(State::Critical);
+            }
+            Some(Err(ref e)) => {
+                self.text.set_text(format!("{}", e));
+                self.text.set_state(State::Critical);
+            }
+            None => (),
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ping_output() {
+        let output = "\
+PING 192.168.1.1 (192.168.1.1) 56(84) bytes of data.
+64 bytes from 192.168.1.1: icmp_seq=1 ttl=64 time=1.52 ms
+
+--- 192.168.1.1 ping statistics ---
+1 packets transmitted, 1 received, 0% packet loss, time 0ms
+";
+        assert_eq!(parse_ping_output(output), Some(Duration::from_micros(1520)));
+        assert_eq!(parse_ping_output("1 packets transmitted, 0 received"), None);
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::{cycle_next, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+const PROFILE_PATH: &str = "/sys/firmware/acpi/platform_profile";
+const CHOICES_PATH: &str = "/sys/firmware/acpi/platform_profile_choices";
+
+pub struct PlatformProfile {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    profiles: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PlatformProfileConfig {
+    /// The profiles to cycle through on click, all profiles of the hardware by default
+    #[serde(default = "PlatformProfileConfig::default_profiles")]
+    pub profiles: Option<Vec<String>>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "PlatformProfileConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "PlatformProfileConfig::default_format")]
+    pub format: String,
+}
+
+impl PlatformProfileConfig {
+    fn default_profiles() -> Option<Vec<String>> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{profile}".to_owned()
+    }
+}
+
+/// The icon for a profile. Drivers name their profiles differently, so the names are
+/// grouped by what they are for.
+fn profile_icon(profile: &str) -> &'static str {
+    match profile {
+        "low-power" | "quiet" | "cool" => "profile_power_saver",
+        "performance" | "balanced-performance" => "profile_performance",
+        _ => "profile_balanced",
+    }
+}
+
+fn read_file(path: &str) -> Result<String> {
+    read_to_string(path)
+        .block_error("platform_profile", &format!("failed to read {}", path))
+        .map(|content| content.trim().to_owned())
+}
+
+impl ConfigBlock for PlatformProfile {
+    type Config = PlatformProfileConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        if !Path::new(PROFILE_PATH).exists() {
+            return Err(BlockError(
+                "platform_profile".to_owned(),
+                "the hardware has no platform profiles".to_owned(),
+            ));
+        }
+        // The choices are separated by spaces, from the lowest to the highest power
+        let profiles = match block_config.profiles {
+            Some(profiles) => profiles,
+            None => read_file(CHOICES_PATH)?
+                .split_whitespace()
+                .map(str::to_owned)
+                .collect(),
+        };
+
+        Ok(PlatformProfile {
+            output: ButtonWidget::new(config, &id).with_icon("profile_balanced"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("platform_profile", "Invalid format specified")?,
+            profiles,
+        })
+    }
+}
+
+impl Block for PlatformProfile {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let profile = read_file(PROFILE_PATH)?;
+        self.output.set_icon(profile_icon(&profile));
+        let values = map!("{profile}" => profile);
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        self.output.set_state(State::Idle);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            let current = read_file(PROFILE_PATH)?;
+            if let Some(next) = cycle_next(&self.profiles, Some(current.as_str())) {
+                // Writing needs root, e.g. a udev rule that makes the file writable.
+                // The failure is shown until the next update instead of stopping the bar.
+                if let Err(error) = write(PROFILE_PATH, next) {
+                    self.output
+                        .set_text(format!("failed to set {}: {}", next, error));
+                    self.output.set_state(State::Critical);
+                    return Ok(());
+                }
+            }
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_icon() {
+        assert_eq!(profile_icon("quiet"), "profile_power_saver");
+        assert_eq!(profile_icon("balanced"), "profile_balanced");
+    }
+}
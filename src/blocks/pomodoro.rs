@@ -15,46 +15,44 @@ use crate::subprocess::spawn_child_async;
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
 
-enum State {
-    Started(Instant),
-    Stopped,
-    Paused(Duration),
-    OnBreak(Instant),
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
 }
 
-impl State {
-    fn elapsed(&self) -> Duration {
+impl Phase {
+    /// The phase after this one, given the number of pomodoros finished so far. Every
+    /// `cycles`th pomodoro is followed by a long break.
+    fn next(self, count: usize, cycles: usize) -> Phase {
         match self {
-            State::Started(start) => Instant::now().duration_since(start.to_owned()),
-            State::Stopped => unreachable!(),
-            State::Paused(duration) => duration.to_owned(),
-            State::OnBreak(start) => Instant::now().duration_since(start.to_owned()),
+            Phase::Work if cycles > 0 && count.is_multiple_of(cycles) => Phase::LongBreak,
+            Phase::Work => Phase::ShortBreak,
+            Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Phase::Work => "\u{f04b}",
+            Phase::ShortBreak | Phase::LongBreak => "\u{2615}",
         }
     }
 }
 
-impl fmt::Display for State {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+enum State {
+    Stopped,
+    Running(Phase, Instant),
+    Paused(Phase, Duration),
+}
+
+impl State {
+    fn elapsed(&self) -> Duration {
         match self {
-            State::Stopped => write!(f, "\u{25a0} 0:00"),
-            State::Started(_) => write!(
-                f,
-                "\u{f04b} {}:{:02}",
-                self.elapsed().as_secs() / 60,
-                self.elapsed().as_secs() % 60
-            ),
-            State::OnBreak(_) => write!(
-                f,
-                "\u{2615} {}:{:02}",
-                self.elapsed().as_secs() / 60,
-                self.elapsed().as_secs() % 60
-            ),
-            State::Paused(duration) => write!(
-                f,
-                "\u{f04c} {}:{:02}",
-                duration.as_secs() / 60,
-                duration.as_secs() % 60
-            ),
+            State::Running(_, start) => Instant::now().duration_since(start.to_owned()),
+            State::Stopped => Duration::from_secs(0),
+            State::Paused(_, duration) => duration.to_owned(),
         }
     }
 }
@@ -65,36 +63,94 @@ pub struct Pomodoro {
     state: State,
     length: Duration,
     break_length: Duration,
+    long_break_length: Duration,
+    cycles: usize,
     update_interval: Duration,
     message: String,
     break_message: String,
     count: usize,
     use_nag: bool,
     nag_path: std::path::PathBuf,
+    use_notify: bool,
 }
 
 impl Pomodoro {
+    fn phase_length(&self, phase: Phase) -> Duration {
+        match phase {
+            Phase::Work => self.length,
+            Phase::ShortBreak => self.break_length,
+            Phase::LongBreak => self.long_break_length,
+        }
+    }
+
     fn set_text(&mut self) {
-        self.time
-            .set_text(format!("{} | {}", self.count, self.state));
+        let (symbol, remaining) = match self.state {
+            State::Stopped => ("\u{25a0}", self.length),
+            State::Running(phase, _) => (
+                phase.symbol(),
+                self.phase_length(phase)
+                    .checked_sub(self.state.elapsed())
+                    .unwrap_or_default(),
+            ),
+            State::Paused(phase, elapsed) => (
+                "\u{f04c}",
+                self.phase_length(phase)
+                    .checked_sub(elapsed)
+                    .unwrap_or_default(),
+            ),
+        };
+        self.time.set_text(format!(
+            "{} | {} {}",
+            self.count,
+            symbol,
+            Remaining(remaining)
+        ));
+    }
+
+    fn announce(&self, message: &str, level: &str) {
+        if self.use_nag {
+            spawn_child_async(
+                self.nag_path.to_str().unwrap(),
+                &["-t", level, "-m", message],
+            )
+            .expect("Failed to start i3-nagbar");
+        }
+        if self.use_notify {
+            // The notification is a nicety, so a missing notify-send is not an error
+            spawn_child_async("notify-send", &["Pomodoro", message]).ok();
+        }
     }
+}
 
-    fn nag(&self, message: &str, level: &str) {
-        spawn_child_async(
-            self.nag_path.to_str().unwrap(),
-            &["-t", level, "-m", message],
-        )
-        .expect("Failed to start i3-nagbar");
+/// Shows a duration as minutes and seconds, rounded up so that a phase ends at `0:00`.
+struct Remaining(Duration);
+
+impl fmt::Display for Remaining {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut seconds = self.0.as_secs();
+        if self.0.subsec_nanos() > 0 {
+            seconds += 1;
+        }
+        write!(f, "{}:{:02}", seconds / 60, seconds % 60)
     }
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct PomodoroConfig {
-    #[serde(default = "PomodoroConfig::default_length")]
+    #[serde(default = "PomodoroConfig::default_length", alias = "work")]
     pub length: u64,
-    #[serde(default = "PomodoroConfig::default_break_length")]
+    #[serde(
+        default = "PomodoroConfig::default_break_length",
+        alias = "short_break"
+    )]
     pub break_length: u64,
+    /// Length of the break after every `cycles` pomodoros in minutes
+    #[serde(default = "PomodoroConfig::default_long_break")]
+    pub long_break: u64,
+    /// The number of pomodoros until a long break, or 0 for only short breaks
+    #[serde(default = "PomodoroConfig::default_cycles")]
+    pub cycles: usize,
     #[serde(default = "PomodoroConfig::default_message")]
     pub message: String,
     #[serde(default = "PomodoroConfig::default_break_message")]
@@ -103,6 +159,9 @@ pub struct PomodoroConfig {
     pub use_nag: bool,
     #[serde(default = "PomodoroConfig::default_nag_path")]
     pub nag_path: std::path::PathBuf,
+    /// Whether to send a desktop notification at the end of every phase
+    #[serde(default = "PomodoroConfig::default_use_notify")]
+    pub use_notify: bool,
 }
 
 impl PomodoroConfig {
@@ -114,6 +173,14 @@ impl PomodoroConfig {
         5
     }
 
+    fn default_long_break() -> u64 {
+        15
+    }
+
+    fn default_cycles() -> usize {
+        4
+    }
+
     fn default_message() -> String {
         "Pomodoro over! Take a break!".to_owned()
     }
@@ -129,6 +196,10 @@ impl PomodoroConfig {
     fn default_nag_path() -> std::path::PathBuf {
         std::path::PathBuf::from("i3-nagbar")
     }
+
+    fn default_use_notify() -> bool {
+        false
+    }
 }
 
 impl ConfigBlock for Pomodoro {
@@ -143,12 +214,15 @@ impl ConfigBlock for Pomodoro {
             state: State::Stopped,
             length: Duration::from_secs(block_config.length * 60), // convert to minutes
             break_length: Duration::from_secs(block_config.break_length * 60), // convert to minutes
+            long_break_length: Duration::from_secs(block_config.long_break * 60), // convert to minutes
+            cycles: block_config.cycles,
             update_interval: Duration::from_millis(1000),
             message: block_config.message,
             break_message: block_config.break_message,
             use_nag: block_config.use_nag,
             count: 0,
             nag_path: block_config.nag_path,
+            use_notify: block_config.use_notify,
         })
     }
 }
@@ -159,28 +233,19 @@ impl Block for Pomodoro {
     }
 
     fn update(&mut self) -> Result<Option<Update>> {
-        self.set_text();
-        match &self.state {
-            State::Started(_) => {
-                if self.state.elapsed() >= self.length {
-                    if self.use_nag {
-                        self.nag(&self.message, "error");
-                    }
-
-                    self.state = State::OnBreak(Instant::now());
-                }
-            }
-            State::OnBreak(_) => {
-                if self.state.elapsed() >= self.break_length {
-                    if self.use_nag {
-                        self.nag(&self.break_message, "warning");
-                    }
-                    self.state = State::Stopped;
+        if let State::Running(phase, _) = self.state {
+            if self.state.elapsed() >= self.phase_length(phase) {
+                if phase == Phase::Work {
                     self.count += 1;
+                    self.announce(&self.message, "error");
+                } else {
+                    self.announce(&self.break_message, "warning");
                 }
+                // The next phase starts right away
+                self.state = State::Running(phase.next(self.count, self.cycles), Instant::now());
             }
-            _ => {}
         }
+        self.set_text();
 
         Ok(Some(self.update_interval.into()))
     }
@@ -193,21 +258,19 @@ impl Block for Pomodoro {
                         self.state = State::Stopped;
                         self.count = 0;
                     }
-                    _ => match &self.state {
+                    _ => match self.state {
                         State::Stopped => {
-                            self.state = State::Started(Instant::now());
+                            self.state = State::Running(Phase::Work, Instant::now());
                         }
-                        State::Started(_) => {
-                            self.state = State::Paused(self.state.elapsed());
+                        State::Running(phase, _) => {
+                            self.state = State::Paused(phase, self.state.elapsed());
                         }
-                        State::Paused(duration) => {
-                            self.state = State::Started(
-                                Instant::now().checked_sub(duration.to_owned()).unwrap(),
+                        State::Paused(phase, duration) => {
+                            self.state = State::Running(
+                                phase,
+                                Instant::now().checked_sub(duration).unwrap(),
                             );
                         }
-                        State::OnBreak(_) => {
-                            self.state = State::Started(Instant::now());
-                        }
                     },
                 }
             }
@@ -221,3 +284,25 @@ impl Block for Pomodoro {
         vec![&self.time]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_phase() {
+        assert_eq!(Phase::Work.next(1, 4), Phase::ShortBreak);
+        assert_eq!(Phase::Work.next(4, 4), Phase::LongBreak);
+        assert_eq!(Phase::Work.next(8, 4), Phase::LongBreak);
+        assert_eq!(Phase::Work.next(4, 0), Phase::ShortBreak);
+        assert_eq!(Phase::LongBreak.next(4, 4), Phase::Work);
+        assert_eq!(Phase::ShortBreak.next(1, 4), Phase::Work);
+    }
+
+    #[test]
+    fn test_remaining() {
+        assert_eq!(Remaining(Duration::from_secs(25 * 60)).to_string(), "25:00");
+        assert_eq!(Remaining(Duration::from_millis(59_500)).to_string(), "1:00");
+        assert_eq!(Remaining(Duration::from_secs(0)).to_string(), "0:00");
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use dbus::arg::{RefArg, Variant};
+use dbus::blocking::stdintf::org_freedesktop_dbus::{Properties, PropertiesPropertiesChanged};
+use dbus::blocking::Connection;
+use dbus::Message;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::{cycle_next, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+const SERVICE: &str = "org.freedesktop.UPower.PowerProfiles";
+const PATH: &str = "/org/freedesktop/UPower/PowerProfiles";
+const INTERFACE: &str = "org.freedesktop.UPower.PowerProfiles";
+
+pub struct PowerProfile {
+    id: String,
+    output: ButtonWidget,
+    format: FormatTemplate,
+    connection: Connection,
+    /// The active profile, or `None` while power-profiles-daemon is not running
+    profile: Arc<Mutex<Option<String>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PowerProfileConfig {
+    /// Format override
+    #[serde(default = "PowerProfileConfig::default_format")]
+    pub format: String,
+}
+
+impl PowerProfileConfig {
+    fn default_format() -> String {
+        "{profile}".to_owned()
+    }
+}
+
+fn get_active_profile(connection: &Connection) -> Option<String> {
+    connection
+        .with_proxy(SERVICE, PATH, Duration::from_secs(5))
+        .get(INTERFACE, "ActiveProfile")
+        .ok()
+}
+
+impl PowerProfile {
+    /// The names of the profiles the hardware supports, in the order power-profiles-daemon
+    /// lists them, which is from the lowest to the highest power.
+    fn profiles(&self) -> Result<Vec<String>> {
+        let profiles: Vec<HashMap<String, Variant<Box<dyn RefArg>>>> = self
+            .connection
+            .with_proxy(SERVICE, PATH, Duration::from_secs(5))
+            .get(INTERFACE, "Profiles")
+            .block_error("power_profile", "failed to get the profiles")?;
+        Ok(profiles
+            .iter()
+            .filter_map(|profile| profile.get("Profile")?.as_str().map(str::to_owned))
+            .collect())
+    }
+}
+
+impl ConfigBlock for PowerProfile {
+    type Config = PowerProfileConfig;
+
+    fn new(block_config: Self::Config, config: Config, send: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let connection = Connection::new_system()
+            .block_error("power_profile", "Failed to establish D-Bus connection")?;
+        let profile = Arc::new(Mutex::new(get_active_profile(&connection)));
+
+        let signal_connection = Connection::new_system()
+            .block_error("power_profile", "Failed to establish D-Bus connection")?;
+        let profile_copy = profile.clone();
+        let id_copy = id.clone();
+        thread::Builder::new()
+            .name("power_profile".into())
+            .spawn(move || {
+                let proxy = signal_connection.with_proxy(SERVICE, PATH, Duration::from_secs(5));
+                let _handler = proxy.match_signal(
+                    move |s: PropertiesPropertiesChanged, _: &Connection, _: &Message| {
+                        if s.interface_name != INTERFACE {
+                            return true;
+                        }
+                        if let Some(active) = s.changed_properties.get("ActiveProfile") {
+                            *profile_copy.lock().unwrap() = active.as_str().map(str::to_owned);
+                            send.send(Task {
+                                id: id_copy.clone(),
+                                update_time: Instant::now(),
+                            })
+                            .unwrap();
+                        }
+                        true
+                    },
+                );
+                loop {
+                    signal_connection
+                        .process(Duration::from_secs(3600))
+                        .unwrap();
+                }
+            })
+            .unwrap();
+
+        Ok(PowerProfile {
+            output: ButtonWidget::new(config, &id).with_icon("profile_balanced"),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("power_profile", "Invalid format specified")?,
+            connection,
+            profile,
+        })
+    }
+}
+
+impl Block for PowerProfile {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let profile = self
+            .profile
+            .lock()
+            .block_error("power_profile", "failed to acquire lock")?
+            .clone()
+            .unwrap_or_default();
+
+        let values = map!("{profile}" => profile.clone());
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        self.output.set_icon(match profile.as_str() {
+            "power-saver" => "profile_power_saver",
+            "performance" => "profile_performance",
+            _ => "profile_balanced",
+        });
+
+        // Updated whenever the active profile changes
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) || event.button != MouseButton::Left {
+            return Ok(());
+        }
+        let current = self
+            .profile
+            .lock()
+            .block_error("power_profile", "failed to acquire lock")?
+            .clone();
+        let profiles = self.profiles()?;
+        if let Some(next) = cycle_next(&profiles, current.as_deref()) {
+            // The change is shown once power-profiles-daemon reports it
+            self.connection
+                .with_proxy(SERVICE, PATH, Duration::from_secs(5))
+                .set(INTERFACE, "ActiveProfile", next.clone())
+                .block_error("power_profile", "failed to set the profile")?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
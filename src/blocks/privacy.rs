@@ -0,0 +1,208 @@
+use std::collections::BTreeSet;
+use std::fs::{read_dir, read_link};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use regex::Regex;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Privacy {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    /// The watched devices that are open, by file name
+    in_use: Arc<Mutex<BTreeSet<String>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrivacyConfig {
+    /// Device files to watch, where `*` matches any part of a file name
+    #[serde(default = "PrivacyConfig::default_devices")]
+    pub devices: Vec<String>,
+
+    /// How often to look for open devices, in seconds
+    #[serde(
+        default = "PrivacyConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "PrivacyConfig::default_format")]
+    pub format: String,
+}
+
+impl PrivacyConfig {
+    fn default_devices() -> Vec<String> {
+        // Cameras, and the capture devices of sound cards
+        vec!["/dev/video*".to_owned(), "/dev/snd/pcmC*D*c".to_owned()]
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn default_format() -> String {
+        "{devices}".to_owned()
+    }
+}
+
+/// Turns a path with `*` wildcards into a regex matching the whole path.
+fn pattern_to_regex(pattern: &str) -> Result<Regex> {
+    let pattern = regex::escape(pattern).replace(r"\*", "[^/]*");
+    Regex::new(&format!("^{}$", pattern))
+        .block_error("privacy", &format!("invalid device pattern '{}'", pattern))
+}
+
+/// Finds the devices matching `patterns` that any process has open, by looking at the
+/// file descriptors in `/proc`. Processes of other users can only be seen by root.
+fn open_devices(patterns: &[Regex]) -> BTreeSet<String> {
+    let mut devices = BTreeSet::new();
+    let processes = match read_dir("/proc") {
+        Ok(processes) => processes,
+        Err(_) => return devices,
+    };
+    for process in processes.filter_map(|entry| entry.ok()) {
+        let is_pid = match process.file_name().to_str() {
+            Some(name) => name.bytes().all(|b| b.is_ascii_digit()),
+            None => false,
+        };
+        if !is_pid {
+            continue;
+        }
+        let fds = match read_dir(process.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        for target in fds.filter_map(|fd| read_link(fd.ok()?.path()).ok()) {
+            if let Some(device) = matching_device(&target, patterns) {
+                devices.insert(device);
+            }
+        }
+    }
+    devices
+}
+
+/// The file name of `target`, if it matches one of the patterns.
+fn matching_device(target: &Path, patterns: &[Regex]) -> Option<String> {
+    let path = target.to_str()?;
+    if patterns.iter().any(|pattern| pattern.is_match(path)) {
+        Some(target.file_name()?.to_string_lossy().into_owned())
+    } else {
+        None
+    }
+}
+
+impl ConfigBlock for Privacy {
+    type Config = PrivacyConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let patterns = block_config
+            .devices
+            .iter()
+            .map(|device| pattern_to_regex(device))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Scanning every process may take a moment, so it is done in the background
+        let in_use = Arc::new(Mutex::new(BTreeSet::new()));
+        {
+            let id = id.clone();
+            let in_use = in_use.clone();
+            let interval = block_config.interval;
+            thread::Builder::new()
+                .name("privacy".into())
+                .spawn(move || loop {
+                    let devices = open_devices(&patterns);
+                    let changed = {
+                        let mut in_use = in_use.lock().unwrap();
+                        let changed = *in_use != devices;
+                        *in_use = devices;
+                        changed
+                    };
+                    if changed {
+                        tx.send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                    }
+                    thread::sleep(interval);
+                })
+                .unwrap();
+        }
+
+        Ok(Privacy {
+            id,
+            text: TextWidget::new(config)
+                .with_icon("privacy")
+                .with_state(State::Warning),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("privacy", "Invalid format specified")?,
+            in_use,
+        })
+    }
+}
+
+impl Block for Privacy {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let devices = self
+            .in_use
+            .lock()
+            .block_error("privacy", "failed to acquire lock")?
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let values = map!("{devices}" => devices);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.in_use.lock().unwrap().is_empty() {
+            vec![]
+        } else {
+            vec![&self.text]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_device() {
+        let patterns: Vec<Regex> = PrivacyConfig::default_devices()
+            .iter()
+            .map(|device| pattern_to_regex(device).unwrap())
+            .collect();
+        let matches = |path: &str| matching_device(Path::new(path), &patterns);
+
+        assert_eq!(matches("/dev/video0"), Some("video0".to_owned()));
+        assert_eq!(matches("/dev/snd/pcmC1D0c"), Some("pcmC1D0c".to_owned()));
+        assert_eq!(matches("/dev/snd/pcmC1D0p"), None);
+        assert_eq!(matches("/dev/snd/controlC1"), None);
+        assert_eq!(matches("socket:[12345]"), None);
+    }
+}
@@ -0,0 +1,219 @@
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use regex::Regex;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::focused_process::parse_rss;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::{format_speed, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct Process {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    name: String,
+    pattern: Regex,
+    full: bool,
+    restart_command: Option<String>,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProcessConfig {
+    /// A regex the whole name of the process has to match, so a plain name matches exactly
+    pub process: String,
+
+    /// Match against the full command line instead of the name, like `pgrep -f`
+    #[serde(default = "ProcessConfig::default_full")]
+    pub full: bool,
+
+    /// Shell command to run on left click while the process is not running
+    #[serde(default = "ProcessConfig::default_restart_command")]
+    pub restart_command: Option<String>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "ProcessConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ProcessConfig::default_format")]
+    pub format: String,
+}
+
+impl ProcessConfig {
+    fn default_full() -> bool {
+        false
+    }
+
+    fn default_restart_command() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{name}".to_owned()
+    }
+}
+
+/// What `pattern` is matched against for the process `dir` in `/proc`: its name, or with
+/// `full` its command line with the arguments separated by spaces.
+fn process_name(dir: &Path, full: bool) -> Option<String> {
+    if full {
+        let cmdline = read_to_string(dir.join("cmdline")).ok()?;
+        Some(cmdline.trim_end_matches('\0').replace('\0', " "))
+    } else {
+        let comm = read_to_string(dir.join("comm")).ok()?;
+        Some(comm.trim_end_matches('\n').to_owned())
+    }
+}
+
+/// Finds the processes in `proc_dir` matching `pattern`, sorted by their ID.
+pub fn find_processes(proc_dir: &Path, pattern: &Regex, full: bool) -> Vec<u32> {
+    let processes = match read_dir(proc_dir) {
+        Ok(processes) => processes,
+        Err(_) => return Vec::new(),
+    };
+    let mut pids: Vec<u32> = processes
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse().ok()?;
+            let name = process_name(&entry.path(), full)?;
+            if pattern.is_match(&name) {
+                Some(pid)
+            } else {
+                None
+            }
+        })
+        .collect();
+    pids.sort_unstable();
+    pids
+}
+
+impl ConfigBlock for Process {
+    type Config = ProcessConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let pattern = Regex::new(&format!("^(?:{})$", block_config.process)).block_error(
+            "process",
+            &format!("invalid process pattern '{}'", block_config.process),
+        )?;
+        Ok(Process {
+            output: ButtonWidget::new(config, &id).with_icon("process"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("process", "Invalid format specified")?,
+            name: block_config.process,
+            pattern,
+            full: block_config.full,
+            restart_command: block_config.restart_command,
+            tx_update_request: tx,
+        })
+    }
+}
+
+impl Block for Process {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let pids = find_processes(Path::new("/proc"), &self.pattern, self.full);
+        // Processes may exit while being looked at, which leaves their memory unknown
+        let mem: Option<u64> = pids
+            .iter()
+            .map(|pid| read_to_string(format!("/proc/{}/status", pid)).ok())
+            .map(|status| parse_rss(&status?))
+            .sum();
+
+        let values = map!(
+            "{name}" => self.name.clone(),
+            "{count}" => pids.len().to_string(),
+            "{pid}" => pids.first().map(|pid| pid.to_string()).unwrap_or_default(),
+            "{mem}" => match mem {
+                Some(mem) if !pids.is_empty() => format_speed(mem, 3, "B", false),
+                _ => String::new(),
+            }
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+        self.output.set_state(if pids.is_empty() {
+            State::Critical
+        } else {
+            State::Good
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if !event.matches_name(self.id()) || event.button != MouseButton::Left {
+            return Ok(());
+        }
+        if let Some(ref command) = self.restart_command {
+            if find_processes(Path::new("/proc"), &self.pattern, self.full).is_empty() {
+                spawn_child_async("sh", &["-c", command])
+                    .block_error("process", "could not spawn child")?;
+                // Give the process a moment to start before looking again
+                self.tx_update_request.send(Task {
+                    id: self.id.clone(),
+                    update_time: Instant::now() + Duration::from_secs(1),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_find_processes() {
+        let dir = TempDir::new().unwrap();
+        dir.child("1/comm").write_str("systemd\n").unwrap();
+        dir.child("1/cmdline").write_str("/sbin/init\0").unwrap();
+        dir.child("42/comm").write_str("syncthing\n").unwrap();
+        dir.child("42/cmdline")
+            .write_str("/usr/bin/syncthing\0-no-browser\0")
+            .unwrap();
+        dir.child("7/comm").write_str("syncthing\n").unwrap();
+        dir.child("7/cmdline").write_str("syncthing\0").unwrap();
+        dir.child("self/comm").write_str("syncthing\n").unwrap();
+
+        let pattern = Regex::new("^(?:syncthing)$").unwrap();
+        assert_eq!(find_processes(dir.path(), &pattern, false), vec![7, 42]);
+        let pattern = Regex::new("^(?:sync)$").unwrap();
+        assert!(find_processes(dir.path(), &pattern, false).is_empty());
+        let pattern = Regex::new("^(?:.*-no-browser)$").unwrap();
+        assert_eq!(find_processes(dir.path(), &pattern, true), vec![42]);
+    }
+}
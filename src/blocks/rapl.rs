@@ -0,0 +1,141 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct Rapl {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    zone: PathBuf,
+    max_energy: u64,
+    /// The energy counter in microjoules, at the time it was read
+    last_energy: Option<(u64, Instant)>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RaplConfig {
+    /// The RAPL zone in /sys/class/powercap, e.g. `intel-rapl:0` for the CPU package
+    #[serde(default = "RaplConfig::default_zone")]
+    pub zone: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "RaplConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "RaplConfig::default_format")]
+    pub format: String,
+}
+
+impl RaplConfig {
+    fn default_zone() -> String {
+        "intel-rapl:0".to_owned()
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn default_format() -> String {
+        "{power}".to_owned()
+    }
+}
+
+fn read_counter(path: &Path) -> Result<u64> {
+    read_to_string(path)
+        .block_error("rapl", &format!("failed to read {}", path.display()))?
+        .trim()
+        .parse()
+        .block_error("rapl", &format!("failed to parse {}", path.display()))
+}
+
+/// The energy used between two readings of a counter that wraps around after `max_energy`.
+fn energy_delta(last: u64, current: u64, max_energy: u64) -> u64 {
+    if current >= last {
+        current - last
+    } else {
+        max_energy.saturating_sub(last) + current
+    }
+}
+
+impl ConfigBlock for Rapl {
+    type Config = RaplConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let zone = Path::new("/sys/class/powercap").join(&block_config.zone);
+        let max_energy = read_counter(&zone.join("max_energy_range_uj"))?;
+
+        Ok(Rapl {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("rapl"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("rapl", "Invalid format specified")?,
+            zone,
+            max_energy,
+            last_energy: None,
+        })
+    }
+}
+
+impl Block for Rapl {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Usually only readable by root, see the docs
+        let energy = read_counter(&self.zone.join("energy_uj"))?;
+        let now = Instant::now();
+
+        // The power is only known from the second reading on
+        let power = match self.last_energy {
+            Some((last, time)) => {
+                let joules = energy_delta(last, energy, self.max_energy) as f64 / 1_000_000.0;
+                let seconds = now.duration_since(time).as_secs_f64().max(0.001);
+                format!("{:.1}W", joules / seconds)
+            }
+            None => "-".to_owned(),
+        };
+        self.last_energy = Some((energy, now));
+
+        let values = map!("{power}" => power);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy_delta() {
+        assert_eq!(energy_delta(1_000, 5_000, 10_000), 4_000);
+        assert_eq!(energy_delta(9_000, 500, 10_000), 1_500);
+        assert_eq!(energy_delta(7_000, 7_000, 10_000), 0);
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Reboot {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    check_files: Vec<String>,
+    command: Option<String>,
+    pending: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RebootConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "RebootConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Files whose existence signals a pending reboot
+    #[serde(default = "RebootConfig::default_check_files")]
+    pub check_files: Vec<String>,
+
+    /// Shell command that exits successfully if a reboot is pending
+    #[serde(default = "RebootConfig::default_command")]
+    pub command: Option<String>,
+
+    /// Text to show while a reboot is pending
+    #[serde(default = "RebootConfig::default_message")]
+    pub message: String,
+}
+
+impl RebootConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn default_check_files() -> Vec<String> {
+        vec!["/var/run/reboot-required".to_owned()]
+    }
+
+    fn default_command() -> Option<String> {
+        None
+    }
+
+    fn default_message() -> String {
+        "reboot required".to_owned()
+    }
+}
+
+impl ConfigBlock for Reboot {
+    type Config = RebootConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Reboot {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config)
+                .with_icon("reboot")
+                .with_text(&block_config.message)
+                .with_state(State::Warning),
+            update_interval: block_config.interval,
+            check_files: block_config.check_files,
+            command: block_config.command,
+            pending: false,
+        })
+    }
+}
+
+impl Block for Reboot {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let command_pending = match self.command {
+            Some(ref command) => Command::new("sh")
+                .args(["-c", command])
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false),
+            None => false,
+        };
+        self.pending = command_pending || self.check_files.iter().any(|f| Path::new(f).exists());
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.pending {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
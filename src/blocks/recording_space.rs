@@ -0,0 +1,201 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use nix::sys::statvfs::statvfs;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::obs::{self, ObsStatus};
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{format_duration, format_speed, FormatTemplate, History};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct RecordingSpace {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    path: String,
+    flag_file: Option<String>,
+    obs: Option<Arc<Mutex<ObsStatus>>>,
+    warning: f64,
+    critical: f64,
+    recording: bool,
+    /// The available space since the recording started
+    history: History,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RecordingSpaceConfig {
+    /// The directory recordings are saved to
+    pub path: String,
+
+    /// A file that exists while recording, instead of asking OBS
+    #[serde(default = "RecordingSpaceConfig::default_flag_file")]
+    pub flag_file: Option<String>,
+
+    /// The host obs-websocket is listening on
+    #[serde(default = "RecordingSpaceConfig::default_host")]
+    pub host: String,
+
+    /// The port obs-websocket is listening on
+    #[serde(default = "RecordingSpaceConfig::default_port")]
+    pub port: u16,
+
+    /// The obs-websocket server password, if authentication is enabled
+    #[serde(default = "RecordingSpaceConfig::default_password")]
+    pub password: Option<String>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "RecordingSpaceConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "RecordingSpaceConfig::default_format")]
+    pub format: String,
+
+    /// Remaining recording time in minutes below which the state is warning
+    #[serde(default = "RecordingSpaceConfig::default_warning")]
+    pub warning: f64,
+
+    /// Remaining recording time in minutes below which the state is critical
+    #[serde(default = "RecordingSpaceConfig::default_critical")]
+    pub critical: f64,
+}
+
+impl RecordingSpaceConfig {
+    fn default_flag_file() -> Option<String> {
+        None
+    }
+
+    fn default_host() -> String {
+        "localhost".to_owned()
+    }
+
+    fn default_port() -> u16 {
+        4455
+    }
+
+    fn default_password() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{free} {remaining}".to_owned()
+    }
+
+    fn default_warning() -> f64 {
+        30.0
+    }
+
+    fn default_critical() -> f64 {
+        10.0
+    }
+}
+
+impl RecordingSpace {
+    fn is_recording(&self) -> Result<bool> {
+        if let Some(ref flag_file) = self.flag_file {
+            return Ok(Path::new(flag_file).exists());
+        }
+        let status = self
+            .obs
+            .as_ref()
+            .block_error("recording_space", "no OBS connection")?
+            .lock()
+            .block_error("recording_space", "failed to acquire lock")?;
+        Ok(status.recording.is_some())
+    }
+}
+
+impl ConfigBlock for RecordingSpace {
+    type Config = RecordingSpaceConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        // OBS is only asked without a flag file
+        let obs = match block_config.flag_file {
+            Some(_) => None,
+            None => Some(obs::watch(
+                block_config.host,
+                block_config.port,
+                block_config.password,
+                id.clone(),
+                tx,
+            )),
+        };
+
+        Ok(RecordingSpace {
+            id,
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("recording_space"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("recording_space", "Invalid format specified")?,
+            path: block_config.path,
+            flag_file: block_config.flag_file,
+            obs,
+            warning: block_config.warning,
+            critical: block_config.critical,
+            recording: false,
+            history: History::new(),
+        })
+    }
+}
+
+impl Block for RecordingSpace {
+    fn update(&mut self) -> Result<Option<Update>> {
+        self.recording = self.is_recording()?;
+        if !self.recording {
+            self.history.clear();
+            return Ok(Some(self.update_interval.into()));
+        }
+
+        let statvfs = statvfs(Path::new(&self.path))
+            .block_error("recording_space", "failed to retrieve statvfs")?;
+        let available = (statvfs.blocks_available() as u64) * (statvfs.block_size() as u64);
+
+        self.history.push(Instant::now(), available);
+        // The seconds that the available space lasts at the rate the recording writes
+        let remaining = self.history.seconds_left();
+
+        self.text.set_state(match remaining {
+            Some(seconds) if seconds < self.critical * 60.0 => State::Critical,
+            Some(seconds) if seconds < self.warning * 60.0 => State::Warning,
+            _ => State::Info,
+        });
+        let values = map!(
+            "{free}" => format_speed(available, 3, "B", false),
+            "{remaining}" => remaining.map(format_duration).unwrap_or_default()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.recording {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,264 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::Connection;
+use uuid::Uuid;
+
+use crate::blocks::screen_layout::watch_hotplug;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct Resolution {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    output: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResolutionConfig {
+    /// The output to show, or the primary one if not set
+    #[serde(default = "ResolutionConfig::default_output")]
+    pub output: Option<String>,
+
+    /// Update interval in seconds, in addition to the updates on hotplug events
+    #[serde(
+        default = "ResolutionConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ResolutionConfig::default_format")]
+    pub format: String,
+}
+
+impl ResolutionConfig {
+    fn default_output() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn default_format() -> String {
+        "{width}x{height}@{scale}".to_owned()
+    }
+}
+
+/// The mode of an active output.
+#[derive(Debug, PartialEq)]
+struct OutputMode {
+    name: String,
+    primary: bool,
+    width: i64,
+    height: i64,
+    scale: f64,
+}
+
+/// Extracts the active outputs from the output of `xrandr --current --verbose`. X11 has
+/// no scale factor, but scaling the outputs with `xrandr --scale` has the same effect,
+/// e.g. a scale of 0.5 is the same as a factor of 2.
+fn parse_xrandr(output: &str) -> Vec<OutputMode> {
+    let mut outputs = Vec::new();
+    let mut current: Option<OutputMode> = None;
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            outputs.extend(current.take());
+            // e.g. `eDP-1 connected primary 1920x1080+0+0 (0x46) normal ...`
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let active = words
+                .iter()
+                .take(4)
+                .any(|word| word.contains('+') && word.contains('x'));
+            if words.get(1) == Some(&"connected") && active {
+                current = Some(OutputMode {
+                    name: words[0].to_owned(),
+                    primary: words.get(2) == Some(&"primary"),
+                    width: 0,
+                    height: 0,
+                    scale: 1.0,
+                });
+            }
+            continue;
+        }
+        let output = match current {
+            Some(ref mut output) => output,
+            None => continue,
+        };
+        let line = line.trim();
+        if let Some(transform) = line.strip_prefix("Transform:") {
+            // The first row of the transformation matrix holds the horizontal scale
+            if let Some(Ok(scale)) = transform.split_whitespace().next().map(str::parse::<f64>) {
+                if scale > 0.0 {
+                    output.scale = 1.0 / scale;
+                }
+            }
+        } else if line.contains("*current") {
+            // e.g. `1920x1080 (0x47) 138.700MHz +HSync -VSync *current +preferred`
+            let size = line.split_whitespace().next().unwrap_or("");
+            if let Some((width, height)) = size.split_once('x') {
+                output.width = width.parse().unwrap_or(0);
+                output.height = height.trim_end_matches('i').parse().unwrap_or(0);
+            }
+        }
+    }
+    outputs.extend(current);
+    outputs
+}
+
+/// Formats a scale factor with at most two decimals, e.g. `1.25` or `2`.
+fn format_scale(scale: f64) -> String {
+    let scale = format!("{:.2}", scale);
+    scale.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+impl Resolution {
+    /// The active outputs, as reported by sway or by xrandr on i3, which does not report
+    /// the modes of the outputs.
+    fn outputs(&self) -> Result<Vec<OutputMode>> {
+        let sway_outputs = Connection::new()
+            .and_then(|mut connection| connection.get_outputs())
+            .unwrap_or_default();
+        let outputs: Vec<OutputMode> = sway_outputs
+            .into_iter()
+            .filter(|output| output.active)
+            .filter_map(|output| {
+                let mode = output.current_mode?;
+                Some(OutputMode {
+                    name: output.name,
+                    primary: output.primary,
+                    width: mode.width as i64,
+                    height: mode.height as i64,
+                    scale: output.scale.unwrap_or(1.0),
+                })
+            })
+            .collect();
+        if !outputs.is_empty() {
+            return Ok(outputs);
+        }
+
+        let output = Command::new("xrandr")
+            .args(["--current", "--verbose"])
+            .output()
+            .block_error("resolution", "failed to run xrandr")?;
+        Ok(parse_xrandr(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+impl ConfigBlock for Resolution {
+    type Config = ResolutionConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        watch_hotplug("resolution", id.clone(), tx);
+
+        Ok(Resolution {
+            id,
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("resolution"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("resolution", "Invalid format specified")?,
+            output: block_config.output,
+        })
+    }
+}
+
+impl Block for Resolution {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mut outputs = self.outputs()?;
+        let position = match self.output {
+            Some(ref name) => outputs.iter().position(|output| output.name == *name),
+            // Without a primary output, the first one is the best guess
+            None => Some(
+                outputs
+                    .iter()
+                    .position(|output| output.primary)
+                    .unwrap_or(0),
+            ),
+        };
+
+        match position.filter(|position| *position < outputs.len()) {
+            Some(position) => {
+                let output = outputs.swap_remove(position);
+                let values = map!(
+                    "{name}" => output.name,
+                    "{width}" => output.width.to_string(),
+                    "{height}" => output.height.to_string(),
+                    "{scale}" => format_scale(output.scale)
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+            }
+            None => self.text.set_text("×".to_owned()),
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xrandr() {
+        let output = "\
+Screen 0: minimum 320 x 200, current 4480 x 1440, maximum 16384 x 16384
+eDP-1 connected primary 3840x2160+0+0 (0x46) normal (normal left inverted right x axis y axis) 344mm x 193mm
+\tIdentifier: 0x42
+\tTransform:  2.000000 0.000000 0.000000
+\t            0.000000 2.000000 0.000000
+\t            0.000000 0.000000 1.000000
+  1920x1080 (0x46) 138.700MHz +HSync -VSync *current +preferred
+        h: width  1920 start 1968 end 2000 total 2080 skew    0 clock  66.68KHz
+  1280x720 (0x4a) 74.500MHz -HSync +VSync
+HDMI-1 connected 2560x1440+3840+0 (0x4b) normal (normal left inverted right x axis y axis) 597mm x 336mm
+\tTransform:  1.000000 0.000000 0.000000
+  2560x1440 (0x4b) 241.500MHz +HSync -VSync *current +preferred
+DP-1 disconnected (normal left inverted right x axis y axis)
+  1920x1080 (0x4c) 148.500MHz +HSync +VSync
+";
+        assert_eq!(
+            parse_xrandr(output),
+            vec![
+                OutputMode {
+                    name: "eDP-1".to_owned(),
+                    primary: true,
+                    width: 1920,
+                    height: 1080,
+                    scale: 0.5,
+                },
+                OutputMode {
+                    name: "HDMI-1".to_owned(),
+                    primary: false,
+                    width: 2560,
+                    height: 1440,
+                    scale: 1.0,
+                },
+            ]
+        );
+
+        assert_eq!(format_scale(1.0), "1");
+        assert_eq!(format_scale(1.25), "1.25");
+        assert_eq!(format_scale(1.5), "1.5");
+    }
+}
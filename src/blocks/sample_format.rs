@@ -0,0 +1,204 @@
+use std::process::Command;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::pactl;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// The sample specification of a sink, as listed by `pactl list sinks`.
+#[derive(Debug, Default, PartialEq)]
+struct SinkSpec {
+    name: String,
+    /// `RUNNING`, `IDLE` or `SUSPENDED`
+    state: String,
+    format: String,
+    channels: u32,
+    rate: u32,
+}
+
+pub struct SampleFormat {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SampleFormatConfig {
+    /// Format override
+    #[serde(default = "SampleFormatConfig::default_format")]
+    pub format: String,
+}
+
+impl SampleFormatConfig {
+    fn default_format() -> String {
+        "{format} {rate}Hz".to_owned()
+    }
+}
+
+/// Parses the output of `pactl list sinks` in the C locale.
+fn parse_sinks(output: &str) -> Vec<SinkSpec> {
+    let mut sinks: Vec<SinkSpec> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with("Sink #") {
+            sinks.push(SinkSpec::default());
+            continue;
+        }
+        let sink = match sinks.last_mut() {
+            Some(sink) => sink,
+            None => continue,
+        };
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("Name:") {
+            sink.name = name.trim().to_owned();
+        } else if let Some(state) = line.strip_prefix("State:") {
+            sink.state = state.trim().to_owned();
+        } else if let Some(spec) = line.strip_prefix("Sample Specification:") {
+            // e.g. `s24le 2ch 96000Hz`
+            let mut fields = spec.split_whitespace();
+            sink.format = fields.next().unwrap_or_default().to_uppercase();
+            sink.channels = fields
+                .next()
+                .and_then(|channels| channels.trim_end_matches("ch").parse().ok())
+                .unwrap_or(0);
+            sink.rate = fields
+                .next()
+                .and_then(|rate| rate.trim_end_matches("Hz").parse().ok())
+                .unwrap_or(0);
+        }
+    }
+    sinks
+}
+
+/// Finds the name of the default sink in the output of `pactl info`.
+fn parse_default_sink(output: &str) -> Option<&str> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Default Sink:"))
+        .map(str::trim)
+}
+
+fn pactl(args: &[&str]) -> Result<String> {
+    let output = Command::new("pactl")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .block_error("sample_format", "failed to run pactl")?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "sample_format".to_owned(),
+            format!("`pactl {}` failed", args.join(" ")),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ConfigBlock for SampleFormat {
+    type Config = SampleFormatConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // The format changes with the streams played, and the default sink may change too
+        pactl::subscribe("sample_format", id.clone(), tx, |line| {
+            // e.g. `Event 'change' on sink #42`, but not on `sink-input #42`
+            line.contains(" on sink #") || line.contains(" on server")
+        });
+
+        Ok(SampleFormat {
+            text: TextWidget::new(config).with_icon("volume_full"),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("sample_format", "Invalid format specified")?,
+        })
+    }
+}
+
+impl Block for SampleFormat {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let info = pactl(&["info"])?;
+        let default_sink =
+            parse_default_sink(&info).block_error("sample_format", "there is no default sink")?;
+        let sinks = parse_sinks(&pactl(&["list", "sinks"])?);
+        let sink = sinks
+            .iter()
+            .find(|sink| sink.name == default_sink)
+            .block_error("sample_format", "the default sink was not found")?;
+
+        // The specification of suspended sinks is the one they open with next time,
+        // which is not necessarily the one of the next stream
+        let running = sink.state == "RUNNING";
+        let values = map!(
+            "{format}" => sink.format.clone(),
+            "{rate}" => sink.rate.to_string(),
+            "{channels}" => sink.channels.to_string(),
+            "{state}" => sink.state.to_lowercase()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text
+            .set_state(if running { State::Info } else { State::Idle });
+
+        // Updated whenever a sink or the default sink changes
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sinks() {
+        let output = "\
+Sink #47
+\tState: SUSPENDED
+\tName: alsa_output.pci-0000_00_1f.3.analog-stereo
+\tDescription: Built-in Audio Analog Stereo
+\tDriver: PipeWire
+\tSample Specification: s32le 2ch 48000Hz
+\tChannel Map: front-left,front-right
+
+Sink #63
+\tState: RUNNING
+\tName: alsa_output.usb-FiiO_K5_Pro-00.analog-stereo
+\tDescription: K5 Pro Analog Stereo
+\tSample Specification: s24le 2ch 96000Hz
+";
+        let sinks = parse_sinks(output);
+        assert_eq!(sinks.len(), 2);
+        assert_eq!(
+            sinks[1],
+            SinkSpec {
+                name: "alsa_output.usb-FiiO_K5_Pro-00.analog-stereo".to_owned(),
+                state: "RUNNING".to_owned(),
+                format: "S24LE".to_owned(),
+                channels: 2,
+                rate: 96000,
+            }
+        );
+        assert_eq!(sinks[0].rate, 48000);
+
+        let info = "Server Name: PulseAudio (on PipeWire 1.0.5)\nDefault Sink: alsa_output.usb-FiiO_K5_Pro-00.analog-stereo\nDefault Source: alsa_input.pci\n";
+        assert_eq!(
+            parse_default_sink(info),
+            Some("alsa_output.usb-FiiO_K5_Pro-00.analog-stereo")
+        );
+        assert_eq!(parse_default_sink(""), None);
+    }
+}
@@ -0,0 +1,191 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+pub struct ScreenLayout {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    separator: String,
+    on_click: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScreenLayoutConfig {
+    /// Update interval in seconds, in addition to the updates on hotplug events
+    #[serde(
+        default = "ScreenLayoutConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ScreenLayoutConfig::default_format")]
+    pub format: String,
+
+    /// The text between the names of the outputs
+    #[serde(default = "ScreenLayoutConfig::default_separator")]
+    pub separator: String,
+
+    /// Shell command to run when the block is clicked, e.g. `autorandr --change`
+    #[serde(default = "ScreenLayoutConfig::default_on_click")]
+    pub on_click: Option<String>,
+}
+
+impl ScreenLayoutConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{outputs}".to_owned()
+    }
+
+    fn default_separator() -> String {
+        "+".to_owned()
+    }
+
+    fn default_on_click() -> Option<String> {
+        None
+    }
+}
+
+/// Extracts the output names of the output of `xrandr --listactivemonitors`.
+fn parse_active_monitors(output: &str) -> Vec<&str> {
+    // The first line is the number of monitors, the others look like
+    // " 0: +*eDP-1 1920/344x1080/193+0+0  eDP-1"
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().last())
+        .collect()
+}
+
+/// Sends an update request for the block `id` whenever a monitor is (un)plugged.
+///
+/// There are no X11 bindings available, so the DRM hotplug events udev sees on
+/// (un)plugging a monitor are used instead of RandR screen change notifications.
+/// Without udev, blocks fall back to polling.
+pub fn watch_hotplug(name: &str, id: String, tx: Sender<Task>) {
+    if let Ok(mut monitor) = Command::new("udevadm")
+        .args(["monitor", "--udev", "--subsystem-match=drm"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        let stdout = monitor.stdout.take().unwrap();
+        thread::Builder::new()
+            .name(name.into())
+            .spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(_) => break,
+                    };
+                    if line.starts_with("UDEV") && line.contains(" change ") {
+                        // Give the X server a moment to pick up the new output
+                        thread::sleep(Duration::from_secs(1));
+                        tx.send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                    }
+                }
+                let _ = monitor.wait();
+            })
+            .unwrap();
+    }
+}
+
+impl ConfigBlock for ScreenLayout {
+    type Config = ScreenLayoutConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        watch_hotplug("screen_layout", id.clone(), tx);
+
+        Ok(ScreenLayout {
+            output: ButtonWidget::new(config, &id).with_icon("screen_layout"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("screen_layout", "Invalid format specified")?,
+            separator: block_config.separator,
+            on_click: block_config.on_click,
+        })
+    }
+}
+
+impl Block for ScreenLayout {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let output = Command::new("xrandr")
+            .arg("--listactivemonitors")
+            .output()
+            .block_error("screen_layout", "failed to run xrandr")?;
+        let output = String::from_utf8_lossy(&output.stdout);
+        let outputs = parse_active_monitors(&output);
+
+        let values = map!(
+            "{outputs}" => outputs.join(&self.separator),
+            "{count}" => outputs.len().to_string()
+        );
+        self.output
+            .set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            if let Some(ref cmd) = self.on_click {
+                spawn_child_async("sh", &["-c", cmd])
+                    .block_error("screen_layout", "could not spawn child")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_active_monitors() {
+        let output = "\
+Monitors: 2
+ 0: +*eDP-1 1920/344x1080/193+0+0  eDP-1
+ 1: +HDMI-1 2560/597x1440/336+1920+0  HDMI-1
+";
+        assert_eq!(parse_active_monitors(output), vec!["eDP-1", "HDMI-1"]);
+        assert!(parse_active_monitors("Monitors: 0\n").is_empty());
+    }
+}
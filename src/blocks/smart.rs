@@ -0,0 +1,262 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+#[derive(Debug, Default, PartialEq)]
+struct SmartStatus {
+    /// `Some(true)` if the drive passed its self-assessment
+    passed: Option<bool>,
+    health: String,
+    temperature: Option<u64>,
+    reallocated: Option<u64>,
+}
+
+pub struct Smart {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    status: Arc<Mutex<Option<Result<SmartStatus>>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SmartConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "SmartConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// The drive to query
+    #[serde(default = "SmartConfig::default_device")]
+    pub device: String,
+
+    /// Whether to run smartctl via `sudo -n`
+    #[serde(default = "SmartConfig::default_use_sudo")]
+    pub use_sudo: bool,
+
+    /// Format override
+    #[serde(default = "SmartConfig::default_format")]
+    pub format: String,
+}
+
+impl SmartConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(3600)
+    }
+
+    fn default_device() -> String {
+        "/dev/sda".to_owned()
+    }
+
+    fn default_use_sudo() -> bool {
+        false
+    }
+
+    fn default_format() -> String {
+        "{health}".to_owned()
+    }
+}
+
+/// Runs smartctl with `args` on `device` and returns its output.
+fn smartctl(args: &[&str], device: &str, use_sudo: bool) -> Result<String> {
+    let mut cmd = if use_sudo {
+        let mut cmd = Command::new("sudo");
+        cmd.args(["-n", "smartctl"]);
+        cmd
+    } else {
+        Command::new("smartctl")
+    };
+    let output = cmd
+        .args(args)
+        .arg(device)
+        .output()
+        .block_error("smart", "failed to run smartctl")?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn get_smart_status(device: &str, use_sudo: bool) -> Result<SmartStatus> {
+    let status = parse_smart_status(&smartctl(&["-H", "-A"], device, use_sudo)?);
+    if status.passed.is_none() {
+        return Err(BlockError(
+            "smart".to_owned(),
+            format!("could not read SMART health of {}", device),
+        ));
+    }
+    Ok(status)
+}
+
+/// The temperature of `device` in °C from its SMART attributes, if it reports one.
+pub fn smart_temperature(device: &str, use_sudo: bool) -> Result<Option<u64>> {
+    Ok(parse_smart_status(&smartctl(&["-A"], device, use_sudo)?).temperature)
+}
+
+fn parse_smart_status(output: &str) -> SmartStatus {
+    let mut status = SmartStatus::default();
+
+    for line in output.lines() {
+        // ATA and NVMe drives
+        if let Some(result) = line.strip_prefix("SMART overall-health self-assessment test result:")
+        {
+            status.health = result.trim().to_owned();
+            status.passed = Some(status.health == "PASSED");
+        // SCSI drives
+        } else if let Some(result) = line.strip_prefix("SMART Health Status:") {
+            status.health = result.trim().to_owned();
+            status.passed = Some(status.health == "OK");
+        // NVMe attributes
+        } else if let Some(temp) = line.strip_prefix("Temperature:") {
+            status.temperature = temp.split_whitespace().next().and_then(|t| t.parse().ok());
+        } else {
+            // ATA attribute table: ID# ATTRIBUTE_NAME FLAG VALUE WORST THRESH TYPE UPDATED WHEN_FAILED RAW_VALUE
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 10 {
+                continue;
+            }
+            let raw = columns[9].parse().ok();
+            match columns[1] {
+                "Reallocated_Sector_Ct" => status.reallocated = raw,
+                "Temperature_Celsius" | "Airflow_Temperature_Cel" => {
+                    status.temperature = status.temperature.or(raw)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    status
+}
+
+impl ConfigBlock for Smart {
+    type Config = SmartConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let status = Arc::new(Mutex::new(None));
+
+        // smartctl can take several seconds, so query it off the main thread
+        let status_copy = status.clone();
+        let id_copy = id.clone();
+        let device = block_config.device;
+        let use_sudo = block_config.use_sudo;
+        let interval = block_config.interval;
+        thread::Builder::new()
+            .name("smart".into())
+            .spawn(move || loop {
+                let result = get_smart_status(&device, use_sudo);
+                *status_copy
+                    .lock()
+                    .expect("main thread paniced while holding smart status mutex") = Some(result);
+                tx.send(Task {
+                    id: id_copy.clone(),
+                    update_time: Instant::now(),
+                })
+                .unwrap();
+                thread::sleep(interval);
+            })
+            .unwrap();
+
+        Ok(Smart {
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("smart", "Invalid format specified")?
+                .with_placeholder(&config.placeholder),
+            text: TextWidget::new(config)
+                .with_icon("disk_drive")
+                .with_text("..."),
+            status,
+        })
+    }
+}
+
+impl Block for Smart {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let status = self
+            .status
+            .lock()
+            .block_error("smart", "failed to acquire lock")?;
+
+        match *status {
+            Some(Ok(ref status)) => {
+                let values = map!(
+                    "{health}" => Some(status.health.clone()),
+                    "{temp}" => status.temperature.map(|t| t.to_string()),
+                    "{reallocated}" => status.reallocated.map(|r| r.to_string())
+                );
+                self.text.set_text(self.format.render_optional(&values)?);
+                self.text.set_state(match status.passed {
+                    Some(true) => State::Good,
+                    _ => State::Critical,
+                });
+            }
+            Some(Err(ref e)) => {
+                self.text.set_text(format!("{}", e));
+                self.text.set_state(State::Critical);
+            }
+            None => (),
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ata() {
+        let output = "\
+=== START OF READ SMART DATA SECTION ===
+SMART overall-health self-assessment test result: PASSED
+
+ID# ATTRIBUTE_NAME          FLAG     VALUE WORST THRESH TYPE      UPDATED  WHEN_FAILED RAW_VALUE
+  5 Reallocated_Sector_Ct   0x0033   100   100   010    Pre-fail  Always       -       3
+194 Temperature_Celsius     0x0022   065   052   000    Old_age   Always       -       35 (Min/Max 20/48)
+";
+        assert_eq!(
+            parse_smart_status(output),
+            SmartStatus {
+                passed: Some(true),
+                health: "PASSED".to_owned(),
+                temperature: Some(35),
+                reallocated: Some(3),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_nvme_failed() {
+        let output = "\
+SMART overall-health self-assessment test result: FAILED!
+Temperature:                        41 Celsius
+";
+        let status = parse_smart_status(output);
+        assert_eq!(status.passed, Some(false));
+        assert_eq!(status.temperature, Some(41));
+        assert_eq!(status.reallocated, None);
+    }
+}
@@ -202,10 +202,11 @@ impl SoundDevice for AlsaSoundDevice {
     }
 }
 
+/// A connection to PulseAudio, with a mainloop that is iterated by its owner.
 #[cfg(feature = "pulseaudio")]
-struct PulseAudioConnection {
-    mainloop: Rc<RefCell<Mainloop>>,
-    context: Rc<RefCell<Context>>,
+pub struct PulseAudioConnection {
+    pub mainloop: Rc<RefCell<Mainloop>>,
+    pub context: Rc<RefCell<Context>>,
 }
 
 #[cfg(feature = "pulseaudio")]
@@ -289,7 +290,7 @@ lazy_static! {
 
 #[cfg(feature = "pulseaudio")]
 impl PulseAudioConnection {
-    fn new() -> Result<Self> {
+    pub fn new() -> Result<Self> {
         let mut proplist = Proplist::new().unwrap();
         proplist
             .set_str(properties::APPLICATION_NAME, "i3status-rs")
@@ -334,7 +335,7 @@ impl PulseAudioConnection {
         Ok(connection)
     }
 
-    fn iterate(&mut self, blocking: bool) -> Result<()> {
+    pub fn iterate(&mut self, blocking: bool) -> Result<()> {
         match self.mainloop.borrow_mut().iterate(blocking) {
             IterateResult::Quit(_) | IterateResult::Err(_) => Err(BlockError(
                 "sound".into(),
@@ -0,0 +1,91 @@
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::reply::NodeLayout;
+use swayipc::{Connection, EventType};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::ipc;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct SplitLayout {
+    text: TextWidget,
+    id: String,
+    conn: Connection,
+    format: FormatTemplate,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SplitLayoutConfig {
+    /// Format override
+    #[serde(default = "SplitLayoutConfig::default_format")]
+    pub format: String,
+}
+
+impl SplitLayoutConfig {
+    fn default_format() -> String {
+        "{layout}".to_owned()
+    }
+}
+
+impl ConfigBlock for SplitLayout {
+    type Config = SplitLayoutConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // Layout commands don't cause window events, but bound keys do cause binding events.
+        ipc::subscribe(
+            "split_layout",
+            id.clone(),
+            &[EventType::Window, EventType::Workspace, EventType::Binding],
+            tx,
+            |_| true,
+        )?;
+
+        Ok(SplitLayout {
+            id,
+            text: TextWidget::new(config),
+            conn: Connection::new()
+                .block_error("split_layout", "failed to acquire connect to IPC")?,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("split_layout", "Invalid format specified")?,
+        })
+    }
+}
+
+impl Block for SplitLayout {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let tree = self
+            .conn
+            .get_tree()
+            .block_error("split_layout", "failed to get tree via IPC")?;
+        let (name, icon) = match ipc::focused_layout(&tree) {
+            Some(NodeLayout::SplitH) => ("splith", "layout_splith"),
+            Some(NodeLayout::SplitV) => ("splitv", "layout_splitv"),
+            Some(NodeLayout::Stacked) => ("stacked", "layout_stacked"),
+            Some(NodeLayout::Tabbed) => ("tabbed", "layout_tabbed"),
+            _ => ("", ""),
+        };
+
+        self.text.set_icon(icon);
+        let values = map!("{layout}" => name);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
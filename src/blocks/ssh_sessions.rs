@@ -0,0 +1,160 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct SshSessions {
+    output: ButtonWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    warning: usize,
+    /// Whether the sessions are listed after the count
+    expanded: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SshSessionsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "SshSessionsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "SshSessionsConfig::default_format")]
+    pub format: String,
+
+    /// The number of sessions above which the state is set to warning
+    #[serde(default = "SshSessionsConfig::default_warning")]
+    pub warning: usize,
+}
+
+impl SshSessionsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+
+    fn default_warning() -> usize {
+        1
+    }
+}
+
+/// Extracts the remote sessions, as `user@host`, from the output of `who`. Sessions on
+/// pseudo-terminals without a remote host, or with a local X display like `(:0)`, are
+/// skipped.
+fn parse_who(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let user = fields.next()?;
+            if !fields.next()?.starts_with("pts/") {
+                return None;
+            }
+            let host = line
+                .trim_end()
+                .strip_suffix(')')
+                .and_then(|line| line.rsplit('(').next())?;
+            if host.is_empty() || host.starts_with(':') {
+                return None;
+            }
+            Some(format!("{}@{}", user, host))
+        })
+        .collect()
+}
+
+impl ConfigBlock for SshSessions {
+    type Config = SshSessionsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(SshSessions {
+            output: ButtonWidget::new(config, &id).with_icon("ssh_sessions"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("ssh_sessions", "Invalid format specified")?,
+            warning: block_config.warning,
+            expanded: false,
+        })
+    }
+}
+
+impl Block for SshSessions {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let output = Command::new("who")
+            .env("LC_ALL", "C")
+            .output()
+            .block_error("ssh_sessions", "failed to run who")?;
+        let sessions = parse_who(&String::from_utf8_lossy(&output.stdout));
+
+        let values = map!("{count}" => sessions.len().to_string());
+        let mut text = self.format.render_static_str(&values)?;
+        if self.expanded && !sessions.is_empty() {
+            text = format!("{} {}", text, sessions.join(", "));
+        }
+        self.output.set_text(text);
+        self.output.set_state(if sessions.len() > self.warning {
+            State::Warning
+        } else {
+            State::Idle
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            self.expanded = !self.expanded;
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_who() {
+        let output = "\
+alice    tty1         2021-01-04 09:12
+alice    pts/0        2021-01-04 09:13 (:0)
+bob      pts/1        2021-01-04 10:01 (192.168.1.20)
+carol    pts/2        2021-01-04 11:30 (laptop.example.com)
+";
+        assert_eq!(
+            parse_who(output),
+            vec!["bob@192.168.1.20", "carol@laptop.example.com"]
+        );
+        assert!(parse_who("").is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use std::f64::consts::PI;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::offset::{Local, TimeZone};
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{format_duration, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+/// The Julian date of the epoch J2000, 2000-01-01 12:00 UTC
+const J2000: f64 = 2_451_545.0;
+/// The Julian date of the unix epoch
+const JULIAN_UNIX_EPOCH: f64 = 2_440_587.5;
+/// The length of a lunar month in days
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+/// A new moon, 2000-01-06 18:14 UTC, as Julian date
+const NEW_MOON: f64 = 2_451_550.26;
+
+const MOON_PHASES: [&str; 8] = [
+    "\u{1f311}",
+    "\u{1f312}",
+    "\u{1f313}",
+    "\u{1f314}",
+    "\u{1f315}",
+    "\u{1f316}",
+    "\u{1f317}",
+    "\u{1f318}",
+];
+
+pub struct Sun {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    latitude: f64,
+    longitude: f64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SunConfig {
+    /// Latitude in degrees, positive to the north
+    pub latitude: f64,
+
+    /// Longitude in degrees, positive to the east
+    pub longitude: f64,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "SunConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "SunConfig::default_format")]
+    pub format: String,
+}
+
+impl SunConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{event} {time}".to_owned()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SunTimes {
+    /// The times of sunrise and sunset in seconds since the unix epoch
+    Events(f64, f64),
+    PolarDay,
+    PolarNight,
+}
+
+/// Computes sunrise and sunset on the `n`th day since J2000 with the sunrise equation, see
+/// <https://en.wikipedia.org/wiki/Sunrise_equation>.
+fn sun_day(n: f64, latitude: f64, longitude: f64) -> SunTimes {
+    let (sin, cos) = (|d: f64| d.to_radians().sin(), |d: f64| d.to_radians().cos());
+
+    // Mean solar noon, the solar mean anomaly and the equation of the center
+    let noon = n - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * noon).rem_euclid(360.0);
+    let center = 1.9148 * sin(anomaly) + 0.02 * sin(2.0 * anomaly) + 0.0003 * sin(3.0 * anomaly);
+    // The ecliptic longitude of the sun, which gives the solar transit and declination
+    let ecliptic = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = J2000 + noon + 0.0053 * sin(anomaly) - 0.0069 * sin(2.0 * ecliptic);
+    let declination = (sin(ecliptic) * sin(23.4397)).asin();
+
+    // -0.833° corrects for refraction and the size of the sun
+    let cos_hour_angle =
+        (sin(-0.833) - sin(latitude) * declination.sin()) / (cos(latitude) * declination.cos());
+    if cos_hour_angle > 1.0 {
+        return SunTimes::PolarNight;
+    } else if cos_hour_angle < -1.0 {
+        return SunTimes::PolarDay;
+    }
+    let hour_angle = cos_hour_angle.acos() * 180.0 / PI;
+    let to_unix = |julian: f64| (julian - JULIAN_UNIX_EPOCH) * 86400.0;
+    SunTimes::Events(
+        to_unix(transit - hour_angle / 360.0),
+        to_unix(transit + hour_angle / 360.0),
+    )
+}
+
+/// The next sunrise or sunset after `now`, in seconds since the unix epoch, or whether it
+/// is polar day or night.
+fn next_event(
+    now: f64,
+    latitude: f64,
+    longitude: f64,
+) -> std::result::Result<(&'static str, f64), SunTimes> {
+    let today = ((now / 86400.0 + JULIAN_UNIX_EPOCH) - J2000).round();
+    let mut events = Vec::new();
+    for n in -1..=2 {
+        if let SunTimes::Events(rise, set) = sun_day(today + n as f64, latitude, longitude) {
+            events.push(("sunrise", rise));
+            events.push(("sunset", set));
+        }
+    }
+    events
+        .into_iter()
+        .filter(|(_, time)| *time > now)
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .ok_or_else(|| sun_day(today, latitude, longitude))
+}
+
+/// The phase of the moon at `now`, from 0 for a new moon to 4 for a full moon and 7 for
+/// a waning crescent.
+fn moon_phase(now: f64) -> usize {
+    let julian = now / 86400.0 + JULIAN_UNIX_EPOCH;
+    let age = ((julian - NEW_MOON) / SYNODIC_MONTH).rem_euclid(1.0);
+    (age * 8.0).round() as usize % 8
+}
+
+impl ConfigBlock for Sun {
+    type Config = SunConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        if !(-90.0..=90.0).contains(&block_config.latitude) {
+            return Err(BlockError(
+                "sun".to_owned(),
+                "latitude must be between -90 and 90".to_owned(),
+            ));
+        }
+        Ok(Sun {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("sun", "Invalid format specified")?,
+            latitude: block_config.latitude,
+            longitude: block_config.longitude,
+        })
+    }
+}
+
+impl Block for Sun {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .block_error("sun", "system time is before the unix epoch")?
+            .as_secs_f64();
+
+        let (event, time, at) = match next_event(now, self.latitude, self.longitude) {
+            Ok((event, time)) => (
+                event,
+                format_duration(time - now),
+                Local.timestamp(time as i64, 0).format("%H:%M").to_string(),
+            ),
+            Err(SunTimes::PolarDay) => ("polar day", "-".to_owned(), "-".to_owned()),
+            Err(_) => ("polar night", "-".to_owned(), "-".to_owned()),
+        };
+        self.text.set_icon(match event {
+            "sunrise" | "polar night" => "sunrise",
+            _ => "sunset",
+        });
+
+        let values = map!(
+            "{event}" => event.to_owned(),
+            "{time}" => time,
+            "{at}" => at,
+            "{moon}" => MOON_PHASES[moon_phase(now)].to_owned()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn unix(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> f64 {
+        Utc.ymd(year, month, day)
+            .and_hms(hour, minute, 0)
+            .timestamp() as f64
+    }
+
+    #[test]
+    fn test_sun() {
+        // In Berlin on the summer solstice the sun rises at 4:43 and sets at 21:33 local time
+        let (berlin_lat, berlin_lon) = (52.52, 13.405);
+        let (event, time) = next_event(unix(2026, 6, 21, 0, 0), berlin_lat, berlin_lon).unwrap();
+        assert_eq!(event, "sunrise");
+        assert!((time - unix(2026, 6, 21, 2, 43)).abs() < 180.0);
+        let (event, time) = next_event(unix(2026, 6, 21, 12, 0), berlin_lat, berlin_lon).unwrap();
+        assert_eq!(event, "sunset");
+        assert!((time - unix(2026, 6, 21, 19, 33)).abs() < 180.0);
+
+        // The sun does not set in Tromsø in June, nor rise in December
+        assert_eq!(
+            next_event(unix(2026, 6, 21, 12, 0), 69.65, 18.96),
+            Err(SunTimes::PolarDay)
+        );
+        assert_eq!(
+            next_event(unix(2026, 12, 21, 12, 0), 69.65, 18.96),
+            Err(SunTimes::PolarNight)
+        );
+
+        // A full moon on 2024-01-25, and new moons on 2024-02-09 and 2026-10-10
+        assert_eq!(moon_phase(unix(2024, 1, 25, 17, 54)), 4);
+        assert_eq!(moon_phase(unix(2024, 2, 9, 22, 59)), 0);
+        assert_eq!(moon_phase(unix(2026, 10, 10, 12, 0)), 0);
+    }
+}
@@ -30,6 +30,9 @@ pub struct Temperature {
     format: FormatTemplate,
     chip: Option<String>,
     inputs: Option<Vec<String>>,
+    trend_hysteresis: f64,
+    /// The maximum temperature when the trend last changed
+    reference_max: Option<f64>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -73,6 +76,11 @@ pub struct TemperatureConfig {
     /// Inputs whitelist
     #[serde(default = "TemperatureConfig::default_inputs")]
     pub inputs: Option<Vec<String>>,
+
+    /// Minimum change of the maximum temperature since the trend last changed that counts as a
+    /// trend
+    #[serde(default = "TemperatureConfig::default_trend_hysteresis")]
+    pub trend_hysteresis: f64,
 }
 
 impl TemperatureConfig {
@@ -111,6 +119,25 @@ impl TemperatureConfig {
     fn default_inputs() -> Option<Vec<String>> {
         None
     }
+
+    fn default_trend_hysteresis() -> f64 {
+        1f64
+    }
+}
+
+/// Whether `max` rose or fell by at least `hysteresis` since `reference`, which is only
+/// moved to `max` then, so that a slow drift adds up until it is shown as well.
+fn trend(reference: &mut Option<f64>, max: f64, hysteresis: f64) -> &'static str {
+    let last = *reference.get_or_insert(max);
+    let trend = if max - last >= hysteresis {
+        "↑"
+    } else if last - max >= hysteresis {
+        "↓"
+    } else {
+        return "→";
+    };
+    *reference = Some(max);
+    trend
 }
 
 impl ConfigBlock for Temperature {
@@ -136,6 +163,8 @@ impl ConfigBlock for Temperature {
                 .block_error("temperature", "Invalid format specified for temperature")?,
             chip: block_config.chip,
             inputs: block_config.inputs,
+            trend_hysteresis: block_config.trend_hysteresis,
+            reference_max: None,
         })
     }
 }
@@ -195,9 +224,12 @@ impl Block for Temperature {
             let min: f64 = temperatures.iter().cloned().fold(f64::INFINITY, f64::min);
             let avg: f64 = temperatures.iter().sum::<f64>() / temperatures.len() as f64;
 
-            let values = map!("{average}" => avg,
-                              "{min}" => min,
-                              "{max}" => max);
+            let trend = trend(&mut self.reference_max, max, self.trend_hysteresis);
+
+            let values = map!("{average}" => avg.to_string(),
+                              "{min}" => min.to_string(),
+                              "{max}" => max.to_string(),
+                              "{trend}" => trend.to_string());
 
             self.output = self.format.render_static_str(&values)?;
             if !self.collapsed {
@@ -241,3 +273,19 @@ impl Block for Temperature {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trend_slow_drift() {
+        let mut reference = None;
+        assert_eq!(trend(&mut reference, 50.0, 1.0), "→");
+        assert_eq!(trend(&mut reference, 50.4, 1.0), "→");
+        assert_eq!(trend(&mut reference, 50.8, 1.0), "→");
+        assert_eq!(trend(&mut reference, 51.2, 1.0), "↑");
+        assert_eq!(trend(&mut reference, 51.5, 1.0), "→");
+        assert_eq!(trend(&mut reference, 50.1, 1.0), "↓");
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+
+pub struct Throttle {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    /// The total of the last update, to tell whether the cores throttled since
+    last_count: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "ThrottleConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ThrottleConfig::default_format")]
+    pub format: String,
+}
+
+impl ThrottleConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+}
+
+/// Sums up the thermal throttle events of all cores in `cpus`, or returns `None` if there
+/// are no counters, which is the case for CPUs other than Intel ones.
+fn read_throttle_count(cpus: &Path) -> Result<Option<u64>> {
+    let mut total = None;
+    for entry in read_dir(cpus).block_error("throttle", "failed to read the CPUs")? {
+        let entry = entry.block_error("throttle", "failed to read the CPUs")?;
+        let is_cpu = match entry.file_name().to_str() {
+            Some(name) => name.starts_with("cpu") && name[3..].parse::<u32>().is_ok(),
+            None => false,
+        };
+        // Cores that are offline have no counter
+        let counter = entry.path().join("thermal_throttle/core_throttle_count");
+        if !is_cpu || !counter.exists() {
+            continue;
+        }
+        let count: u64 = read_to_string(&counter)
+            .block_error("throttle", &format!("failed to read {}", counter.display()))?
+            .trim()
+            .parse()
+            .block_error(
+                "throttle",
+                &format!("failed to parse {}", counter.display()),
+            )?;
+        total = Some(total.unwrap_or(0) + count);
+    }
+    Ok(total)
+}
+
+impl ConfigBlock for Throttle {
+    type Config = ThrottleConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        if read_throttle_count(Path::new(CPU_PATH))?.is_none() {
+            return Err(BlockError(
+                "throttle".to_owned(),
+                "the CPU has no thermal throttle counters".to_owned(),
+            ));
+        }
+
+        Ok(Throttle {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("throttle"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("throttle", "Invalid format specified")?,
+            last_count: None,
+        })
+    }
+}
+
+impl Block for Throttle {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let count = read_throttle_count(Path::new(CPU_PATH))?.unwrap_or(0);
+
+        // The counters only go back when cores go offline
+        self.text.set_state(match self.last_count {
+            Some(last) if count > last => State::Warning,
+            _ => State::Idle,
+        });
+        self.last_count = Some(count);
+
+        let values = map!("{count}" => count.to_string());
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_read_throttle_count() {
+        let cpus = TempDir::new().unwrap();
+        assert_eq!(read_throttle_count(cpus.path()).unwrap(), None);
+
+        for (cpu, count) in &[("cpu0", "12\n"), ("cpu1", "30\n")] {
+            cpus.child(cpu)
+                .child("thermal_throttle/core_throttle_count")
+                .write_str(count)
+                .unwrap();
+        }
+        cpus.child("cpufreq/policy0").write_str("").unwrap();
+        assert_eq!(read_throttle_count(cpus.path()).unwrap(), Some(42));
+    }
+}
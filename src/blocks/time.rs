@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use chrono::offset::{Local, Utc};
+use chrono::Timelike;
 use chrono_tz::Tz;
 use crossbeam_channel::Sender;
 use serde_derive::Deserialize;
@@ -8,19 +9,20 @@ use uuid::Uuid;
 
 use crate::blocks::{Block, ConfigBlock, Update};
 use crate::config::Config;
-use crate::de::{deserialize_duration, deserialize_timezone};
+use crate::de::{deserialize_opt_duration, deserialize_timezone};
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::subprocess::spawn_child_async;
+use crate::util::AltFormat;
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
 
 pub struct Time {
     time: ButtonWidget,
     id: String,
-    update_interval: Duration,
-    format: String,
+    update_interval: Option<Duration>,
+    format: AltFormat<String>,
     on_click: Option<String>,
     timezone: Option<Tz>,
 }
@@ -32,12 +34,17 @@ pub struct TimeConfig {
     #[serde(default = "TimeConfig::default_format")]
     pub format: String,
 
-    /// Update interval in seconds
+    /// Alternative format string, toggled by left-clicking the block.
+    #[serde(default = "TimeConfig::default_format_alt")]
+    pub format_alt: Option<String>,
+
+    /// Update interval in seconds. By default, the block updates at the start of every
+    /// second or minute, depending on whether the format shows seconds.
     #[serde(
         default = "TimeConfig::default_interval",
-        deserialize_with = "deserialize_duration"
+        deserialize_with = "deserialize_opt_duration"
     )]
-    pub interval: Duration,
+    pub interval: Option<Duration>,
 
     #[serde(default = "TimeConfig::default_on_click")]
     pub on_click: Option<String>,
@@ -54,8 +61,12 @@ impl TimeConfig {
         "%a %d/%m %R".to_owned()
     }
 
-    fn default_interval() -> Duration {
-        Duration::from_secs(5)
+    fn default_format_alt() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Option<Duration> {
+        None
     }
 
     fn default_on_click() -> Option<String> {
@@ -67,6 +78,36 @@ impl TimeConfig {
     }
 }
 
+/// Whether the strftime format shows seconds, either directly or as part of a composite
+/// specifier like `%T`.
+fn shows_seconds(format: &str) -> bool {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        // Skip padding modifiers like in `%-S`
+        match chars.find(|c| !matches!(c, '-' | '_' | '0')) {
+            Some('S') | Some('T') | Some('X') | Some('c') | Some('r') | Some('s') | Some('+') => {
+                return true
+            }
+            _ => (),
+        }
+    }
+    false
+}
+
+/// The time from `nanos` nanoseconds into the current minute until the start of the next
+/// second or minute.
+fn until_next_tick(nanos: u64, seconds: bool) -> Duration {
+    let tick = if seconds {
+        1_000_000_000
+    } else {
+        60_000_000_000
+    };
+    Duration::from_nanos(tick - nanos % tick)
+}
+
 impl ConfigBlock for Time {
     type Config = TimeConfig;
 
@@ -78,7 +119,7 @@ impl ConfigBlock for Time {
         let i = Uuid::new_v4().to_simple().to_string();
         Ok(Time {
             id: i.clone(),
-            format: block_config.format,
+            format: AltFormat::new(block_config.format, block_config.format_alt),
             time: ButtonWidget::new(config, i.as_str())
                 .with_text("")
                 .with_icon("time"),
@@ -92,18 +133,30 @@ impl ConfigBlock for Time {
 impl Block for Time {
     fn update(&mut self) -> Result<Option<Update>> {
         let time = match self.timezone {
-            Some(tz) => Utc::now().with_timezone(&tz).format(&self.format),
-            None => Local::now().format(&self.format),
+            Some(tz) => Utc::now().with_timezone(&tz).format(self.format.get()),
+            None => Local::now().format(self.format.get()),
         };
         self.time.set_text(format!("{}", time));
-        Ok(Some(self.update_interval.into()))
+
+        match self.update_interval {
+            Some(interval) => Ok(Some(interval.into())),
+            None => {
+                let now = Local::now();
+                let nanos = u64::from(now.second()) * 1_000_000_000 + u64::from(now.nanosecond());
+                Ok(Some(
+                    until_next_tick(nanos, shows_seconds(self.format.get())).into(),
+                ))
+            }
+        }
     }
 
     fn click(&mut self, e: &I3BarEvent) -> Result<()> {
         if let Some(ref name) = e.name {
             if name.as_str() == self.id {
                 if let MouseButton::Left = e.button {
-                    if let Some(ref cmd) = self.on_click {
+                    if self.format.toggle() {
+                        self.update()?;
+                    } else if let Some(ref cmd) = self.on_click {
                         spawn_child_async("sh", &["-c", cmd])
                             .block_error("time", "could not spawn child")?;
                     }
@@ -121,3 +174,27 @@ impl Block for Time {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_update() {
+        assert!(!shows_seconds("%a %d/%m %R"));
+        assert!(!shows_seconds("100%% %M"));
+        assert!(shows_seconds("%H:%M:%S"));
+        assert!(shows_seconds("%-S"));
+        assert!(shows_seconds("%T"));
+
+        assert_eq!(
+            until_next_tick(12_250_000_000, false),
+            Duration::from_millis(47_750)
+        );
+        assert_eq!(
+            until_next_tick(12_250_000_000, true),
+            Duration::from_millis(750)
+        );
+        assert_eq!(until_next_tick(0, false), Duration::from_secs(60));
+    }
+}
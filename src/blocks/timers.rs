@@ -0,0 +1,225 @@
+use std::process::Command;
+use std::time::Duration;
+
+use chrono::offset::{Local, TimeZone};
+use chrono::NaiveDateTime;
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{format_duration, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct Timers {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    user: bool,
+    units: Vec<String>,
+    at_jobs: bool,
+    /// Whether any timer is scheduled, otherwise the block is hidden
+    pending: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TimersConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "TimersConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "TimersConfig::default_format")]
+    pub format: String,
+
+    /// Whether to show the timers of the user's service manager instead of the system ones
+    #[serde(default = "TimersConfig::default_user")]
+    pub user: bool,
+
+    /// Patterns of the timers to consider, e.g. `backup-*`, or all of them if empty
+    #[serde(default = "TimersConfig::default_units")]
+    pub units: Vec<String>,
+
+    /// Whether to consider the jobs queued with `at` as well
+    #[serde(default = "TimersConfig::default_at_jobs")]
+    pub at_jobs: bool,
+}
+
+impl TimersConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{next} {unit}".to_owned()
+    }
+
+    fn default_user() -> bool {
+        false
+    }
+
+    fn default_units() -> Vec<String> {
+        Vec::new()
+    }
+
+    fn default_at_jobs() -> bool {
+        false
+    }
+}
+
+/// Parses the output of `systemctl list-timers --no-legend` into the next elapse and name
+/// of each scheduled timer. Lines start with the next elapse, e.g.
+/// `Thu 2026-10-15 00:00:00 CEST 9h left ... logrotate.timer logrotate.service`, which is
+/// `n/a` or `-` for timers that are not scheduled.
+fn parse_timers(output: &str) -> Vec<(NaiveDateTime, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let next = NaiveDateTime::parse_from_str(
+                &format!("{} {}", fields.get(1)?, fields.get(2)?),
+                "%Y-%m-%d %H:%M:%S",
+            )
+            .ok()?;
+            let unit = fields.iter().find(|field| field.ends_with(".timer"))?;
+            Some((next, unit.trim_end_matches(".timer").to_owned()))
+        })
+        .collect()
+}
+
+/// Parses the output of `atq` in the C locale, e.g. `3\tThu Oct 15 10:00:00 2026 a user`.
+fn parse_atq(output: &str) -> Vec<(NaiveDateTime, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (job, time) = line.split_once('\t')?;
+            // The date is followed by the queue and the user
+            let time: Vec<&str> = time.split_whitespace().take(5).collect();
+            let next =
+                NaiveDateTime::parse_from_str(&time.join(" "), "%a %b %e %H:%M:%S %Y").ok()?;
+            Some((next, format!("at job {}", job.trim())))
+        })
+        .collect()
+}
+
+fn run(command: &mut Command) -> Result<String> {
+    let output = command
+        .env("LC_ALL", "C")
+        .output()
+        .block_error("timers", "failed to run command")?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "timers".to_owned(),
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ConfigBlock for Timers {
+    type Config = TimersConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Timers {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("timers"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("timers", "Invalid format specified")?,
+            user: block_config.user,
+            units: block_config.units,
+            at_jobs: block_config.at_jobs,
+            pending: false,
+        })
+    }
+}
+
+impl Block for Timers {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mut systemctl = Command::new("systemctl");
+        if self.user {
+            systemctl.arg("--user");
+        }
+        systemctl
+            .args(["list-timers", "--no-legend", "--no-pager"])
+            .args(&self.units);
+        let mut scheduled = parse_timers(&run(&mut systemctl)?);
+        if self.at_jobs {
+            scheduled.extend(parse_atq(&run(&mut Command::new("atq"))?));
+        }
+
+        // Both print local times
+        let now = Local::now();
+        let soonest = scheduled
+            .into_iter()
+            .filter_map(|(next, unit)| Some((Local.from_local_datetime(&next).earliest()?, unit)))
+            .min_by_key(|(next, _)| *next);
+        self.pending = soonest.is_some();
+        if let Some((next, unit)) = soonest {
+            let seconds = (next - now).num_seconds().max(0) as f64;
+            let values = map!(
+                "{next}" => format_duration(seconds),
+                "{unit}" => unit
+            );
+            self.text.set_text(self.format.render_static_str(&values)?);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.pending {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scheduled() {
+        let timers = "\
+Thu 2026-10-15 00:00:00 CEST 9h left      Wed 2026-10-14 00:00:03 CEST 14h ago  logrotate.timer    logrotate.service
+Thu 2026-10-15 09:30:00 CEST 19h          -                           -        backup-home.timer  backup-home.service
+n/a                          n/a          Wed 2026-10-14 08:12:40 CEST 6h ago   oneshot.timer      oneshot.service
+";
+        let parsed = parse_timers(timers);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            parsed[1],
+            (
+                NaiveDateTime::parse_from_str("2026-10-15 09:30:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+                "backup-home".to_owned()
+            )
+        );
+
+        let atq = "3\tThu Oct 15 10:00:00 2026 a user\n12\tFri Oct  2 08:05:00 2026 b user\n";
+        let parsed = parse_atq(atq);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            parsed[1],
+            (
+                NaiveDateTime::parse_from_str("2026-10-02 08:05:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+                "at job 12".to_owned()
+            )
+        );
+    }
+}
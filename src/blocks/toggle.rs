@@ -23,8 +23,12 @@ pub struct Toggle {
     command_state: String,
     icon_on: String,
     icon_off: String,
+    state_on: State,
+    state_off: State,
     update_interval: Option<Duration>,
     toggled: bool,
+    /// Whether the last toggle command failed, which is shown until one succeeds
+    failed: bool,
     id: String,
 }
 
@@ -52,6 +56,14 @@ pub struct ToggleConfig {
     #[serde(default = "ToggleConfig::default_icon_off")]
     pub icon_off: String,
 
+    /// State when toggled on, e.g. "Good"
+    #[serde(default)]
+    pub state_on: State,
+
+    /// State when toggled off
+    #[serde(default)]
+    pub state_off: State,
+
     /// Text to display in i3bar for this block
     pub text: Option<String>,
 }
@@ -82,13 +94,31 @@ impl ConfigBlock for Toggle {
             command_state: block_config.command_state,
             icon_on: block_config.icon_on,
             icon_off: block_config.icon_off,
+            state_on: block_config.state_on,
+            state_off: block_config.state_off,
             id,
             toggled: false,
+            failed: false,
             update_interval: block_config.interval,
         })
     }
 }
 
+impl Toggle {
+    /// Sets the icon and state of whether the toggle is on or off, or critical while the
+    /// last toggle command failed.
+    fn show_toggled(&mut self) {
+        let (icon, state) = if self.toggled {
+            (self.icon_on.as_str(), self.state_on)
+        } else {
+            (self.icon_off.as_str(), self.state_off)
+        };
+        self.text.set_icon(icon);
+        self.text
+            .set_state(if self.failed { State::Critical } else { state });
+    }
+}
+
 impl Block for Toggle {
     fn update(&mut self) -> Result<Option<Update>> {
         let output = Command::new(env::var("SHELL").unwrap_or_else(|_| "sh".to_owned()))
@@ -97,18 +127,8 @@ impl Block for Toggle {
             .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
             .unwrap_or_else(|e| e.to_string());
 
-        self.text.set_icon(match output.trim_start() {
-            "" => {
-                self.toggled = false;
-                self.icon_off.as_str()
-            }
-            _ => {
-                self.toggled = true;
-                self.icon_on.as_str()
-            }
-        });
-
-        self.text.set_state(State::Idle);
+        self.toggled = !output.trim_start().is_empty();
+        self.show_toggled();
 
         Ok(self.update_interval.map(|d| d.into()))
     }
@@ -131,23 +151,22 @@ impl Block for Toggle {
                     .output()
                     .block_error("toggle", "failed to run toggle command")?;
 
-                if output.status.success() {
-                    self.text.set_state(State::Idle);
+                self.failed = !output.status.success();
+                if !self.failed {
                     self.toggled = !self.toggled;
-                    self.text.set_icon(if self.toggled {
-                        self.icon_on.as_str()
-                    } else {
-                        self.icon_off.as_str()
-                    })
-                } else {
-                    self.text.set_state(State::Critical);
-                };
+                }
+                self.show_toggled();
             }
         }
 
         Ok(())
     }
 
+    // The click only assumes that the command worked, so the state is read again
+    fn update_on_click(&self) -> bool {
+        true
+    }
+
     fn id(&self) -> &str {
         &self.id
     }
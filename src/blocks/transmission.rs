@@ -0,0 +1,280 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::{Backoff, Task};
+use crate::util::{format_speed, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// The torrent statuses of Transmission that count as active
+const STATUS_DOWNLOADING: u64 = 4;
+const STATUS_SEEDING: u64 = 6;
+
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+
+pub struct Transmission {
+    text: TextWidget,
+    id: String,
+    backoff: Backoff,
+    format: FormatTemplate,
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    /// The session ID Transmission requires to be sent along with every request
+    session_id: String,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TransmissionConfig {
+    /// URL of the RPC endpoint
+    #[serde(default = "TransmissionConfig::default_url")]
+    pub url: String,
+
+    /// User name, if the RPC endpoint requires authentication
+    #[serde(default = "TransmissionConfig::default_username")]
+    pub username: Option<String>,
+
+    /// Password, if the RPC endpoint requires authentication
+    #[serde(default = "TransmissionConfig::default_password")]
+    pub password: Option<String>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "TransmissionConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "TransmissionConfig::default_format")]
+    pub format: String,
+}
+
+impl TransmissionConfig {
+    fn default_url() -> String {
+        "http://localhost:9091/transmission/rpc".to_owned()
+    }
+
+    fn default_username() -> Option<String> {
+        None
+    }
+
+    fn default_password() -> Option<String> {
+        None
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{active} {down} {up}".to_owned()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Response {
+    arguments: Arguments,
+}
+
+#[derive(Deserialize, Debug)]
+struct Arguments {
+    torrents: Vec<Torrent>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Torrent {
+    status: u64,
+    rate_download: u64,
+    rate_upload: u64,
+}
+
+/// Splits the output of `curl --include` into the status code, headers and body.
+fn parse_http_response(output: &str) -> Option<(u32, &str, &str)> {
+    let mut parts = output.splitn(2, "\r\n\r\n");
+    let headers = parts.next()?;
+    let body = parts.next().unwrap_or("");
+    let status = headers.split_whitespace().nth(1)?.parse().ok()?;
+    Some((status, headers, body))
+}
+
+/// Finds the session ID that comes with a `409 Conflict` response.
+fn parse_session_id(headers: &str) -> Option<&str> {
+    headers.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.eq_ignore_ascii_case(SESSION_ID_HEADER) {
+            Some(value.trim())
+        } else {
+            None
+        }
+    })
+}
+
+impl Transmission {
+    /// Sends the RPC request, returning nothing if Transmission cannot be reached.
+    fn request(&self) -> Result<Option<String>> {
+        let mut command = Command::new("curl");
+        command.args([
+            "--silent",
+            "--include",
+            "--max-time",
+            "3",
+            "--header",
+            &format!("{}: {}", SESSION_ID_HEADER, self.session_id),
+            "--data",
+            r#"{"method":"torrent-get","arguments":{"fields":["status","rateDownload","rateUpload"]}}"#,
+        ]);
+        if let Some(ref username) = self.username {
+            command.args([
+                "--user",
+                &format!("{}:{}", username, self.password.as_deref().unwrap_or("")),
+            ]);
+        }
+        let output = command
+            .arg(&self.url)
+            .output()
+            .block_error("transmission", "failed to run curl")?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+    }
+
+    /// Fetches the torrents, doing the session ID handshake if needed.
+    fn torrents(&mut self) -> Result<Option<Vec<Torrent>>> {
+        // The first request of a session, or one after Transmission restarted, is
+        // answered with a new session ID to retry with
+        for _ in 0..2 {
+            let output = match self.request()? {
+                Some(output) => output,
+                None => return Ok(None),
+            };
+            let (status, headers, body) = parse_http_response(&output)
+                .block_error("transmission", "unexpected curl output")?;
+            match status {
+                200 => {
+                    let response: Response = serde_json::from_str(body)
+                        .block_error("transmission", "failed to parse RPC response")?;
+                    return Ok(Some(response.arguments.torrents));
+                }
+                409 => {
+                    self.session_id = parse_session_id(headers)
+                        .block_error("transmission", "no session ID in response")?
+                        .to_owned();
+                }
+                401 => {
+                    return Err(BlockError(
+                        "transmission".to_owned(),
+                        "authentication failed".to_owned(),
+                    ))
+                }
+                code => {
+                    return Err(BlockError(
+                        "transmission".to_owned(),
+                        format!("unexpected response status {}", code),
+                    ))
+                }
+            }
+        }
+        Err(BlockError(
+            "transmission".to_owned(),
+            "session ID was not accepted".to_owned(),
+        ))
+    }
+}
+
+impl ConfigBlock for Transmission {
+    type Config = TransmissionConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Transmission {
+            id: Uuid::new_v4().to_simple().to_string(),
+            backoff: Backoff::new(block_config.interval),
+            text: TextWidget::new(config).with_icon("transmission"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("transmission", "Invalid format specified")?,
+            url: block_config.url,
+            username: block_config.username,
+            password: block_config.password,
+            session_id: String::new(),
+        })
+    }
+}
+
+impl Block for Transmission {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let torrents = match self.torrents()? {
+            Some(torrents) => torrents,
+            // Transmission not running is not worth an error, e.g. on a laptop
+            None => {
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Idle);
+                return Ok(Some(self.backoff.failure().into()));
+            }
+        };
+
+        let active = torrents
+            .iter()
+            .filter(|t| t.status == STATUS_DOWNLOADING || t.status == STATUS_SEEDING)
+            .count();
+        let down: u64 = torrents.iter().map(|t| t.rate_download).sum();
+        let up: u64 = torrents.iter().map(|t| t.rate_upload).sum();
+
+        let values = map!(
+            "{active}" => active.to_string(),
+            "{down}" => format_speed(down, 3, "K", false),
+            "{up}" => format_speed(up, 3, "K", false)
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text
+            .set_state(if torrents.iter().any(|t| t.status == STATUS_DOWNLOADING) {
+                State::Good
+            } else {
+                State::Idle
+            });
+
+        Ok(Some(self.backoff.success().into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_response() {
+        let output = "HTTP/1.1 409 Conflict\r\n\
+                      Server: Transmission\r\n\
+                      X-Transmission-Session-Id: 0Pg3ZbyLCzCCvCDs\r\n\
+                      \r\n\
+                      <h1>409: Conflict</h1>";
+        let (status, headers, body) = parse_http_response(output).unwrap();
+        assert_eq!(status, 409);
+        assert_eq!(parse_session_id(headers), Some("0Pg3ZbyLCzCCvCDs"));
+        assert_eq!(body, "<h1>409: Conflict</h1>");
+
+        let (status, headers, _) =
+            parse_http_response("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(parse_session_id(headers), None);
+        assert_eq!(parse_http_response(""), None);
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::{Backoff, Task};
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// A package manager and the shell command that lists its pending updates.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManager {
+    pub name: String,
+    pub command: String,
+}
+
+pub struct Updates {
+    output: ButtonWidget,
+    id: String,
+    backoff: Backoff,
+    format: FormatTemplate,
+    format_up_to_date: FormatTemplate,
+    managers: Vec<PackageManager>,
+    /// The last known number of updates of each manager, kept while its command fails
+    counts: Vec<Option<usize>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UpdatesConfig {
+    /// The package managers to check, as `{ name, command }` tables
+    pub managers: Vec<PackageManager>,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "UpdatesConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "UpdatesConfig::default_format")]
+    pub format: String,
+
+    /// Alternative format override for when no updates are available
+    #[serde(default = "UpdatesConfig::default_format")]
+    pub format_up_to_date: String,
+}
+
+impl UpdatesConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60 * 10)
+    }
+
+    fn default_format() -> String {
+        "{total}".to_owned()
+    }
+}
+
+/// Counts the updates a command listed, one per line.
+fn count_updates(output: &str) -> usize {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+}
+
+/// Runs the command of `manager`, which fails if it cannot be run or exits unsuccessfully.
+fn check(manager: &PackageManager) -> Result<usize> {
+    let output = Command::new("sh")
+        .env("LC_ALL", "C")
+        .args(["-c", &manager.command])
+        .output()
+        .block_error("updates", &format!("failed to run {}", manager.command))?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "updates".to_owned(),
+            format!("`{}` failed with {}", manager.command, output.status),
+        ));
+    }
+    Ok(count_updates(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Lists the updates of each manager that has any, e.g. `pacman 3 flatpak 1`. Managers whose
+/// number is unknown are shown as `placeholder`.
+fn breakdown(managers: &[PackageManager], counts: &[Option<usize>], placeholder: &str) -> String {
+    managers
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| **count != Some(0))
+        .map(|(manager, count)| match count {
+            Some(count) => format!("{} {}", manager.name, count),
+            None => format!("{} {}", manager.name, placeholder),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl ConfigBlock for Updates {
+    type Config = UpdatesConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(Updates {
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("updates", "Invalid format specified")?
+                .with_placeholder(&config.placeholder),
+            format_up_to_date: FormatTemplate::from_string(&block_config.format_up_to_date)
+                .block_error("updates", "Invalid format_up_to_date specified")?
+                .with_placeholder(&config.placeholder),
+            output: ButtonWidget::new(config, &id).with_icon("update"),
+            id,
+            backoff: Backoff::new(block_config.interval),
+            counts: vec![None; block_config.managers.len()],
+            managers: block_config.managers,
+        })
+    }
+}
+
+impl Block for Updates {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // One failing manager does not hide the updates of the others
+        let mut failed = 0;
+        for (manager, count) in self.managers.iter().zip(self.counts.iter_mut()) {
+            match check(manager) {
+                Ok(updates) => *count = Some(updates),
+                Err(_) => failed += 1,
+            }
+        }
+
+        let total: usize = self.counts.iter().flatten().sum();
+        let names: Vec<String> = self
+            .managers
+            .iter()
+            .map(|manager| format!("{{{}}}", manager.name))
+            .collect();
+        let mut values: HashMap<&str, Option<usize>> = names
+            .iter()
+            .map(String::as_str)
+            .zip(self.counts.iter().copied())
+            .collect();
+        values.insert("{total}", Some(total));
+
+        let text = if total == 0 {
+            self.format_up_to_date.render_optional(&values)?
+        } else {
+            self.format.render_optional(&values)?
+        };
+        self.output.set_text(text);
+        let breakdown = breakdown(&self.managers, &self.counts, self.format.placeholder());
+        self.output.set_short_text(if breakdown.is_empty() {
+            None
+        } else {
+            Some(breakdown)
+        });
+        self.output.set_state(if failed > 0 {
+            State::Warning
+        } else if total > 0 {
+            State::Info
+        } else {
+            State::Idle
+        });
+
+        // Most likely there is no network connection if all of them fail
+        if failed > 0 && failed == self.managers.len() {
+            Ok(Some(self.backoff.failure().into()))
+        } else {
+            Ok(Some(self.backoff.success().into()))
+        }
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.output]
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if event.matches_name(self.id()) && event.button == MouseButton::Left {
+            self.update()?;
+        }
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_updates() {
+        assert_eq!(count_updates(""), 0);
+        assert_eq!(
+            count_updates("firefox 90.0-1 -> 91.0-1\nlinux 5.13.1-1 -> 5.13.2-1\n\n"),
+            2
+        );
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let managers: Vec<PackageManager> = ["pacman", "flatpak", "snap"]
+            .iter()
+            .map(|name| PackageManager {
+                name: name.to_string(),
+                command: String::new(),
+            })
+            .collect();
+        assert_eq!(
+            breakdown(&managers, &[Some(3), Some(0), None], "N/A"),
+            "pacman 3 snap N/A"
+        );
+        assert_eq!(
+            breakdown(&managers, &[Some(0), Some(0), Some(0)], "N/A"),
+            ""
+        );
+    }
+}
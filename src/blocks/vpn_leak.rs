@@ -0,0 +1,143 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct VpnLeak {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    vpn_interface: String,
+    address: String,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VpnLeakConfig {
+    /// The interface of the VPN tunnel, e.g. `tun0` or `wg0`
+    pub vpn_interface: String,
+
+    /// The public address whose route is checked
+    #[serde(default = "VpnLeakConfig::default_address")]
+    pub address: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "VpnLeakConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "VpnLeakConfig::default_format")]
+    pub format: String,
+}
+
+impl VpnLeakConfig {
+    fn default_address() -> String {
+        "1.1.1.1".to_owned()
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{interface}".to_owned()
+    }
+}
+
+/// Finds the interface in the output of `ip route get <address>`, e.g.
+/// `1.1.1.1 via 192.168.1.1 dev wlan0 src 192.168.1.23 uid 1000`. The kernel evaluates the
+/// routing rules for this, so routes of other tables, like those of `wg-quick`, count too.
+fn route_interface(output: &str) -> Option<String> {
+    let mut fields = output.split_whitespace();
+    fields.find(|field| *field == "dev")?;
+    fields.next().map(String::from)
+}
+
+impl ConfigBlock for VpnLeak {
+    type Config = VpnLeakConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(VpnLeak {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("vpn_leak", "Invalid format specified")?
+                .with_placeholder(&config.placeholder),
+            text: TextWidget::new(config).with_icon("vpn_off"),
+            vpn_interface: block_config.vpn_interface,
+            address: block_config.address,
+        })
+    }
+}
+
+impl Block for VpnLeak {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Fails while there is no route at all, e.g. with `Network is unreachable`
+        let output = Command::new("ip")
+            .args(["route", "get", &self.address])
+            .output()
+            .block_error("vpn_leak", "failed to run ip")?;
+        let interface = if output.status.success() {
+            route_interface(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            None
+        };
+
+        // Without any route to the internet nothing can leak either
+        let (icon, state) = match interface {
+            Some(ref interface) if *interface == self.vpn_interface => ("vpn_on", State::Good),
+            Some(_) => ("vpn_off", State::Critical),
+            None => ("vpn_off", State::Idle),
+        };
+        self.text.set_icon(icon);
+        self.text.set_state(state);
+        let values = map!("{interface}" => interface);
+        self.text.set_text(self.format.render_optional(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_interface() {
+        assert_eq!(
+            route_interface(
+                "1.1.1.1 via 192.168.1.1 dev wlan0 src 192.168.1.23 uid 1000 \n    cache \n"
+            ),
+            Some("wlan0".to_owned())
+        );
+        // wg-quick routes everything through table 51820 with policy rules
+        assert_eq!(
+            route_interface("1.1.1.1 dev wg0 table 51820 src 10.64.0.2 uid 1000 \n    cache \n"),
+            Some("wg0".to_owned())
+        );
+        assert_eq!(route_interface(""), None);
+    }
+}
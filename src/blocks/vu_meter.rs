@@ -0,0 +1,201 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::sound::PulseAudioConnection;
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::pulse::def::BufferAttr;
+use crate::pulse::sample::{Spec, SAMPLE_FLOAT32};
+use crate::pulse::stream::{flags, PeekResult, State as StreamState, Stream};
+use crate::scheduler::Task;
+use crate::util::{format_percent_bar, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+/// How many peaks per second PulseAudio reports
+const PEAK_RATE: u32 = 25;
+
+pub struct VuMeter {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    /// The highest peak since the last update, from 0 to 1
+    peak: Arc<Mutex<f32>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VuMeterConfig {
+    /// The PulseAudio source to measure, by default the monitor of the default sink
+    #[serde(default = "VuMeterConfig::default_device")]
+    pub device: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "VuMeterConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "VuMeterConfig::default_format")]
+    pub format: String,
+}
+
+impl VuMeterConfig {
+    fn default_device() -> String {
+        "@DEFAULT_MONITOR@".to_owned()
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_millis(200)
+    }
+
+    fn default_format() -> String {
+        "{bar}".to_owned()
+    }
+}
+
+/// The highest of the peaks in `data`, which holds native endian floats.
+fn max_peak(data: &[u8]) -> f32 {
+    data.chunks_exact(4)
+        .map(|sample| f32::from_ne_bytes([sample[0], sample[1], sample[2], sample[3]]).abs())
+        .fold(0.0, f32::max)
+}
+
+/// Records the peaks of `device` into `peak` until the connection fails. PulseAudio computes
+/// the peaks itself, so only a few samples per second are transferred.
+fn record_peaks(device: &str, peak: &Mutex<f32>) -> Result<()> {
+    let mut connection = PulseAudioConnection::new()?;
+    let spec = Spec {
+        format: SAMPLE_FLOAT32,
+        rate: PEAK_RATE,
+        channels: 1,
+    };
+    let mut stream = Stream::new(&mut connection.context.borrow_mut(), "peak", &spec, None)
+        .block_error("vu_meter", "failed to create pulseaudio stream")?;
+    // Deliver every peak right away
+    let attr = BufferAttr {
+        maxlength: u32::MAX,
+        tlength: u32::MAX,
+        prebuf: u32::MAX,
+        minreq: u32::MAX,
+        fragsize: 4,
+    };
+    stream
+        .connect_record(
+            Some(device),
+            Some(&attr),
+            flags::PEAK_DETECT | flags::ADJUST_LATENCY | flags::DONT_MOVE,
+        )
+        .block_error("vu_meter", "failed to connect pulseaudio stream")?;
+
+    loop {
+        connection.iterate(true)?;
+        match stream.get_state() {
+            StreamState::Ready => (),
+            StreamState::Failed | StreamState::Terminated => {
+                return Err(BlockError(
+                    "vu_meter".to_owned(),
+                    "pulseaudio stream failed/terminated".to_owned(),
+                ))
+            }
+            _ => continue,
+        }
+
+        loop {
+            let data_peak = match stream
+                .peek()
+                .block_error("vu_meter", "failed to read pulseaudio stream")?
+            {
+                PeekResult::Empty => break,
+                PeekResult::Hole(_) => 0.0,
+                PeekResult::Data(data) => max_peak(data),
+            };
+            stream
+                .discard()
+                .block_error("vu_meter", "failed to read pulseaudio stream")?;
+            let mut peak = peak.lock().unwrap();
+            *peak = peak.max(data_peak);
+        }
+    }
+}
+
+impl ConfigBlock for VuMeter {
+    type Config = VuMeterConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        let peak = Arc::new(Mutex::new(0.0));
+        let peak_copy = peak.clone();
+        let device = block_config.device;
+        thread::Builder::new()
+            .name("vu_meter".into())
+            .spawn(move || loop {
+                // The connection fails e.g. when the sound server restarts, so try again later
+                let _ = record_peaks(&device, &peak_copy);
+                *peak_copy.lock().unwrap() = 0.0;
+                thread::sleep(Duration::from_secs(5));
+            })
+            .unwrap();
+
+        Ok(VuMeter {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("vu_meter"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("vu_meter", "Invalid format specified")?,
+            peak,
+        })
+    }
+}
+
+impl Block for VuMeter {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let peak = std::mem::take(
+            &mut *self
+                .peak
+                .lock()
+                .block_error("vu_meter", "failed to acquire lock")?,
+        );
+        let level = (peak * 100.0).clamp(0.0, 100.0);
+
+        let values = map!(
+            "{bar}" => format_percent_bar(level),
+            "{level}" => format!("{:.0}%", level)
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_peak() {
+        let data: Vec<u8> = [0.25f32, -0.75, 0.5]
+            .iter()
+            .flat_map(|sample| sample.to_ne_bytes().to_vec())
+            .collect();
+        assert_eq!(max_peak(&data), 0.75);
+        assert_eq!(max_peak(&[]), 0.0);
+    }
+}
@@ -12,7 +12,7 @@ use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
-use crate::scheduler::Task;
+use crate::scheduler::{Backoff, Task};
 use crate::util::FormatTemplate;
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
@@ -60,10 +60,52 @@ pub struct Weather {
     format: String,
     weather_keys: HashMap<String, String>,
     service: WeatherService,
-    update_interval: Duration,
+    backoff: Backoff,
     autolocate: bool,
 }
 
+/// The conditions whose icon can be overridden, and the icons they are shown with.
+const CONDITION_ICONS: &[(&str, &str)] = &[
+    ("clear", "weather_sun"),
+    ("clear_night", "weather_moon"),
+    ("clouds", "weather_clouds"),
+    ("clouds_night", "weather_clouds_night"),
+    ("fog", "weather_fog"),
+    ("rain", "weather_rain"),
+    ("snow", "weather_snow"),
+    ("thunder", "weather_thunder"),
+    ("default", "weather_default"),
+];
+
+/// Maps an OpenWeatherMap condition code to an icon, see
+/// https://openweathermap.org/weather-conditions
+fn condition_icon(code: Option<u64>, night: bool) -> &'static str {
+    match code {
+        Some(200..=299) => "weather_thunder",
+        Some(300..=399) | Some(500..=599) => "weather_rain",
+        Some(600..=699) => "weather_snow",
+        Some(700..=799) => "weather_fog",
+        Some(800) if night => "weather_moon",
+        Some(800) => "weather_sun",
+        Some(801..=804) if night => "weather_clouds_night",
+        Some(801..=804) => "weather_clouds",
+        _ => "weather_default",
+    }
+}
+
+/// Whether it is night at the location. The icon names of OpenWeatherMap end with `n` at
+/// night, otherwise the time of the measurement is compared to sunrise and sunset.
+fn is_night(json: &serde_json::value::Value) -> bool {
+    if let Some(icon) = json.pointer("/weather/0/icon").and_then(|v| v.as_str()) {
+        return icon.ends_with('n');
+    }
+    let time = |pointer| json.pointer(pointer).and_then(|v| v.as_i64());
+    match (time("/dt"), time("/sys/sunrise"), time("/sys/sunset")) {
+        (Some(now), Some(sunrise), Some(sunset)) => now < sunrise || now >= sunset,
+        _ => false,
+    }
+}
+
 fn malformed_json_error() -> Error {
     BlockError("weather".to_string(), "Malformed JSON.".to_string())
 }
@@ -260,14 +302,8 @@ impl Weather {
                     }
                 }
 
-                self.weather.set_icon(match raw_weather.as_str() {
-                    "Clear" => "weather_sun",
-                    "Rain" | "Drizzle" => "weather_rain",
-                    "Clouds" | "Fog" | "Mist" => "weather_clouds",
-                    "Thunderstorm" => "weather_thunder",
-                    "Snow" => "weather_snow",
-                    _ => "weather_default",
-                });
+                let code = json.pointer("/weather/0/id").and_then(|v| v.as_u64());
+                self.weather.set_icon(condition_icon(code, is_night(&json)));
 
                 self.weather_keys = map_to_owned!("{weather}" => raw_weather,
                                   "{temp}" => format!("{:.0}", raw_temp),
@@ -308,6 +344,9 @@ pub struct WeatherConfig {
     pub service: WeatherService,
     #[serde(default = "WeatherConfig::default_autolocate")]
     pub autolocate: bool,
+    /// Icons to show instead of the theme's ones, by condition
+    #[serde(default = "WeatherConfig::default_icons")]
+    pub icons: HashMap<String, String>,
 }
 
 impl WeatherConfig {
@@ -322,6 +361,10 @@ impl WeatherConfig {
     fn default_autolocate() -> bool {
         false
     }
+
+    fn default_icons() -> HashMap<String, String> {
+        HashMap::new()
+    }
 }
 
 impl ConfigBlock for Weather {
@@ -329,17 +372,31 @@ impl ConfigBlock for Weather {
 
     fn new(
         block_config: Self::Config,
-        config: Config,
+        mut config: Config,
         _tx_update_request: Sender<Task>,
     ) -> Result<Self> {
         let id = Uuid::new_v4().to_simple().to_string();
+
+        // The overrides only apply to this block, which has its own copy of the config
+        for (condition, glyph) in block_config.icons {
+            let icon = CONDITION_ICONS
+                .iter()
+                .find(|(name, _)| *name == condition)
+                .map(|(_, icon)| *icon)
+                .block_error(
+                    "weather",
+                    &format!("unknown weather condition '{}'", condition),
+                )?;
+            config.icons.insert(icon.to_owned(), glyph);
+        }
+
         Ok(Weather {
             id: id.clone(),
             weather: ButtonWidget::new(config, &id),
             format: block_config.format,
             weather_keys: HashMap::new(),
             service: block_config.service,
-            update_interval: block_config.interval,
+            backoff: Backoff::new(block_config.interval),
             autolocate: block_config.autolocate,
         })
     }
@@ -352,11 +409,12 @@ impl Block for Weather {
         // weather information, which is likely due to internet connectivity.
         if self.weather_keys.keys().len() == 0 {
             self.weather.set_text("×".to_string());
+            Ok(Some(self.backoff.failure().into()))
         } else {
             let fmt = FormatTemplate::from_string(&self.format)?;
             self.weather.set_text(fmt.render(&self.weather_keys));
+            Ok(Some(self.backoff.success().into()))
         }
-        Ok(Some(self.update_interval.into()))
     }
 
     fn view(&self) -> Vec<&dyn I3BarWidget> {
@@ -376,3 +434,25 @@ impl Block for Weather {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_icon() {
+        assert_eq!(condition_icon(Some(211), false), "weather_thunder");
+        assert_eq!(condition_icon(Some(301), false), "weather_rain");
+        assert_eq!(condition_icon(Some(741), true), "weather_fog");
+        assert_eq!(condition_icon(Some(800), false), "weather_sun");
+        assert_eq!(condition_icon(Some(800), true), "weather_moon");
+        assert_eq!(condition_icon(Some(803), true), "weather_clouds_night");
+        assert_eq!(condition_icon(Some(900), false), "weather_default");
+        assert_eq!(condition_icon(None, false), "weather_default");
+
+        let json = serde_json::json!({ "weather": [{ "icon": "01n" }] });
+        assert!(is_night(&json));
+        let json = serde_json::json!({ "dt": 1000, "sys": { "sunrise": 500, "sunset": 2000 } });
+        assert!(!is_night(&json));
+    }
+}
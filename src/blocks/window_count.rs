@@ -0,0 +1,80 @@
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::{Connection, EventType};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::ipc;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct WindowCount {
+    text: TextWidget,
+    id: String,
+    conn: Connection,
+    format: FormatTemplate,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WindowCountConfig {
+    /// Format override
+    #[serde(default = "WindowCountConfig::default_format")]
+    pub format: String,
+}
+
+impl WindowCountConfig {
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+}
+
+impl ConfigBlock for WindowCount {
+    type Config = WindowCountConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        ipc::subscribe(
+            "window_count",
+            id.clone(),
+            &[EventType::Window, EventType::Workspace],
+            tx,
+            |_| true,
+        )?;
+
+        Ok(WindowCount {
+            id,
+            text: TextWidget::new(config),
+            conn: Connection::new()
+                .block_error("window_count", "failed to acquire connect to IPC")?,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("window_count", "Invalid format specified")?,
+        })
+    }
+}
+
+impl Block for WindowCount {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let count = ipc::focused_workspace(&mut self.conn, "window_count")?
+            .map(|workspace| ipc::count_windows(&workspace))
+            .unwrap_or(0);
+
+        let values = map!("{count}" => count);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
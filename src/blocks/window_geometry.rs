@@ -0,0 +1,186 @@
+use std::sync::{Arc, Mutex};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::reply::{Event, Node, NodeType, Rect};
+use swayipc::{Connection, EventType};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::ipc;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+/// The size and position of a window on the screen, without its decoration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Geometry {
+    x: i64,
+    y: i64,
+    width: i64,
+    height: i64,
+}
+
+impl Geometry {
+    /// The window is at `window_rect` within the container at `rect`. Containers holding
+    /// other containers have no window of their own, so the whole container is used.
+    fn new(rect: &Rect, window_rect: &Rect) -> Geometry {
+        if window_rect.width == 0 || window_rect.height == 0 {
+            return Geometry {
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+            };
+        }
+        Geometry {
+            x: rect.x + window_rect.x,
+            y: rect.y + window_rect.y,
+            width: window_rect.width,
+            height: window_rect.height,
+        }
+    }
+}
+
+/// The geometry of the focused window, or `None` if e.g. an empty workspace is focused.
+fn focused_geometry(conn: &mut Connection) -> Option<Geometry> {
+    let focused: Node = conn.get_tree().ok()?.find_focused(|n| {
+        n.focused && (n.node_type == NodeType::Con || n.node_type == NodeType::FloatingCon)
+    })?;
+    Some(Geometry::new(&focused.rect, &focused.window_rect))
+}
+
+pub struct WindowGeometry {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    geometry: Arc<Mutex<Option<Geometry>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WindowGeometryConfig {
+    /// Format override
+    #[serde(default = "WindowGeometryConfig::default_format")]
+    pub format: String,
+}
+
+impl WindowGeometryConfig {
+    fn default_format() -> String {
+        "{width}x{height}".to_owned()
+    }
+}
+
+impl ConfigBlock for WindowGeometry {
+    type Config = WindowGeometryConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let mut conn =
+            Connection::new().block_error("window_geometry", "failed to connect to IPC")?;
+        let geometry_original = Arc::new(Mutex::new(focused_geometry(&mut conn)));
+        let geometry = geometry_original.clone();
+
+        // Window events tell about focus changes and moves, but not about resizes, which
+        // are usually done with bindings, so the tree is asked again after either
+        ipc::subscribe(
+            "window_geometry",
+            id.clone(),
+            &[EventType::Window, EventType::Binding],
+            tx,
+            move |event| match event {
+                Event::Window(_) | Event::Binding(_) => {
+                    let new_geometry = focused_geometry(&mut conn);
+                    let mut geometry = geometry_original.lock().unwrap();
+                    if *geometry == new_geometry {
+                        return false;
+                    }
+                    *geometry = new_geometry;
+                    true
+                }
+                _ => unreachable!(),
+            },
+        )?;
+
+        Ok(WindowGeometry {
+            id,
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("window_geometry", "Invalid format specified")?,
+            geometry,
+        })
+    }
+}
+
+impl Block for WindowGeometry {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let geometry = *self
+            .geometry
+            .lock()
+            .block_error("window_geometry", "failed to acquire lock")?;
+        if let Some(geometry) = geometry {
+            let values = map!(
+                "{width}" => geometry.width.to_string(),
+                "{height}" => geometry.height.to_string(),
+                "{x}" => geometry.x.to_string(),
+                "{y}" => geometry.y.to_string()
+            );
+            self.text.set_text(self.format.render_static_str(&values)?);
+        }
+
+        // Updated whenever the focused window changes
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        match *self.geometry.lock().unwrap() {
+            Some(_) => vec![&self.text],
+            None => vec![],
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i64, y: i64, width: i64, height: i64) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_geometry() {
+        // A window with a title bar of 20 pixels and borders of 2 pixels
+        assert_eq!(
+            Geometry::new(&rect(960, 0, 960, 1080), &rect(2, 20, 956, 1058)),
+            Geometry {
+                x: 962,
+                y: 20,
+                width: 956,
+                height: 1058,
+            }
+        );
+        // A focused split container
+        assert_eq!(
+            Geometry::new(&rect(0, 0, 960, 1080), &rect(0, 0, 0, 0)),
+            Geometry {
+                x: 0,
+                y: 0,
+                width: 960,
+                height: 1080,
+            }
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::{Connection, EventType};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::ipc;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct WindowTitle {
+    text: TextWidget,
+    id: String,
+    conn: Connection,
+    class: String,
+    format: FormatTemplate,
+    found: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WindowTitleConfig {
+    /// The window class (or app id on sway) of the application to track
+    pub class: String,
+
+    /// Format override
+    #[serde(default = "WindowTitleConfig::default_format")]
+    pub format: String,
+}
+
+impl WindowTitleConfig {
+    fn default_format() -> String {
+        "{title}".to_owned()
+    }
+}
+
+impl ConfigBlock for WindowTitle {
+    type Config = WindowTitleConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // Titles of unfocused windows change as well, so every window event
+        // may be relevant.
+        ipc::subscribe("window_title", id.clone(), &[EventType::Window], tx, |_| {
+            true
+        })?;
+
+        Ok(WindowTitle {
+            id,
+            text: TextWidget::new(config),
+            conn: Connection::new()
+                .block_error("window_title", "failed to acquire connect to IPC")?,
+            class: block_config.class,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("window_title", "Invalid format specified")?,
+            found: false,
+        })
+    }
+}
+
+impl Block for WindowTitle {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let tree = self
+            .conn
+            .get_tree()
+            .block_error("window_title", "failed to get tree via IPC")?;
+        let title = ipc::find_window(&tree, &self.class).map(|window| {
+            window
+                .window_properties
+                .as_ref()
+                .and_then(|props| props.title.clone())
+                .or_else(|| window.name.clone())
+                .unwrap_or_default()
+        });
+
+        self.found = title.is_some();
+        if let Some(title) = title {
+            let values = map!("{title}" => title);
+            self.text.set_text(self.format.render_static_str(&values)?);
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.found {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,192 @@
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{format_duration, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Wireguard {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    interface: String,
+    peer: Option<String>,
+    warning: u64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WireguardConfig {
+    /// The WireGuard interface
+    #[serde(default = "WireguardConfig::default_interface")]
+    pub interface: String,
+
+    /// The public key of the peer, or the peer with the latest handshake if unset
+    #[serde(default = "WireguardConfig::default_peer")]
+    pub peer: Option<String>,
+
+    /// Age of the handshake in seconds, from which state is set to warning
+    #[serde(default = "WireguardConfig::default_warning")]
+    pub warning: u64,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "WireguardConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "WireguardConfig::default_format")]
+    pub format: String,
+}
+
+impl WireguardConfig {
+    fn default_interface() -> String {
+        "wg0".to_owned()
+    }
+
+    fn default_peer() -> Option<String> {
+        None
+    }
+
+    fn default_warning() -> u64 {
+        // Handshakes are renewed every two minutes while data is sent
+        180
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{age}".to_owned()
+    }
+}
+
+/// Finds the time of the latest handshake with `peer`, or with any peer, in the output of
+/// `wg show <interface> latest-handshakes`. Every line holds the public key of a peer and
+/// the seconds since the epoch, which are 0 if there was no handshake yet.
+fn latest_handshake(output: &str, peer: Option<&str>) -> Option<u64> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let key = fields.next()?;
+            let time: u64 = fields.next()?.parse().ok()?;
+            match peer {
+                Some(peer) if peer != key => None,
+                _ => Some(time),
+            }
+        })
+        .max()
+        .filter(|time| *time > 0)
+}
+
+/// Formats the age of a handshake, with seconds when it is younger than a minute.
+fn format_age(seconds: u64) -> String {
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format_duration(seconds as f64)
+    }
+}
+
+impl ConfigBlock for Wireguard {
+    type Config = WireguardConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Wireguard {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("wireguard", "Invalid format specified")?
+                .with_placeholder(&config.placeholder),
+            text: TextWidget::new(config).with_icon("vpn_on"),
+            interface: block_config.interface,
+            peer: block_config.peer,
+            warning: block_config.warning,
+        })
+    }
+}
+
+impl Block for Wireguard {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let output = Command::new("wg")
+            .args(["show", &self.interface, "latest-handshakes"])
+            .output()
+            .block_error("wireguard", "failed to run wg")?;
+        // wg needs CAP_NET_ADMIN and fails without it as well as for unknown interfaces
+        if !output.status.success() {
+            return Err(BlockError(
+                "wireguard".to_owned(),
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+        let handshake = latest_handshake(
+            &String::from_utf8_lossy(&output.stdout),
+            self.peer.as_deref(),
+        );
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .block_error("wireguard", "the clock is before 1970")?
+            .as_secs();
+        let age = handshake.map(|time| now.saturating_sub(time));
+
+        let values = map!(
+            "{age}" => age.map(format_age)
+        );
+        self.text.set_text(self.format.render_optional(&values)?);
+        self.text.set_state(match age {
+            None => State::Critical,
+            Some(age) if age >= self.warning => State::Warning,
+            Some(_) => State::Good,
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_handshake() {
+        let output = "\
+xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=\t1700000100
+TrMvSoP4jYQlY6RIzBgbssQqY3vxI2Pi+y71lOWWXX0=\t1700000040
+gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=\t0
+";
+        assert_eq!(latest_handshake(output, None), Some(1700000100));
+        assert_eq!(
+            latest_handshake(output, Some("TrMvSoP4jYQlY6RIzBgbssQqY3vxI2Pi+y71lOWWXX0=")),
+            Some(1700000040)
+        );
+        assert_eq!(
+            latest_handshake(output, Some("gN65BkIKy1eCE9pP1wdc8ROUtkHLF2PfAqYdyYBz6EA=")),
+            None
+        );
+        assert_eq!(latest_handshake("", None), None);
+        assert_eq!(format_age(42), "42s");
+        assert_eq!(format_age(150), "3m");
+    }
+}
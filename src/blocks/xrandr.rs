@@ -1,3 +1,4 @@
+use std::cmp;
 use std::process::Command;
 use std::str::FromStr;
 use std::time::Duration;
@@ -58,6 +59,8 @@ pub struct Xrandr {
     icons: bool,
     resolution: bool,
     step_width: u32,
+    min_brightness: u32,
+    output: Option<String>,
     current_idx: usize,
 
     #[allow(dead_code)]
@@ -85,6 +88,14 @@ pub struct XrandrConfig {
     /// The steps brightness is in/decreased for the selected screen (When greater than 50 it gets limited to 50)
     #[serde(default = "XrandrConfig::default_step_width")]
     pub step_width: u32,
+
+    /// The lowest brightness the selected screen can be dimmed to, in percent
+    #[serde(default = "XrandrConfig::default_min_brightness")]
+    pub min_brightness: u32,
+
+    /// Only show and control this output, e.g. `HDMI-1`
+    #[serde(default = "XrandrConfig::default_output")]
+    pub output: Option<String>,
 }
 
 impl XrandrConfig {
@@ -103,6 +114,14 @@ impl XrandrConfig {
     fn default_step_width() -> u32 {
         5 as u32
     }
+
+    fn default_min_brightness() -> u32 {
+        0
+    }
+
+    fn default_output() -> Option<String> {
+        None
+    }
 }
 
 macro_rules! unwrap_or_continue {
@@ -247,6 +266,8 @@ impl ConfigBlock for Xrandr {
             icons: block_config.icons,
             resolution: block_config.resolution,
             step_width,
+            min_brightness: cmp::min(block_config.min_brightness, 100),
+            output: block_config.output,
             monitors: Vec::new(),
             config,
         })
@@ -255,8 +276,14 @@ impl ConfigBlock for Xrandr {
 
 impl Block for Xrandr {
     fn update(&mut self) -> Result<Option<Update>> {
-        if let Some(am) = Xrandr::get_active_monitors()? {
-            if let Some(mm) = Xrandr::get_monitor_metrics(&am)? {
+        if let Some(mut am) = Xrandr::get_active_monitors()? {
+            if let Some(ref output) = self.output {
+                am.retain(|name| name == output);
+            }
+            if am.is_empty() {
+                self.monitors.clear();
+                self.text.set_text(String::from("×"));
+            } else if let Some(mm) = Xrandr::get_monitor_metrics(&am)? {
                 self.monitors = mm;
                 self.display()?;
             }
@@ -274,7 +301,7 @@ impl Block for Xrandr {
             if name.as_str() == self.id {
                 match e.button {
                     MouseButton::Left => {
-                        if self.current_idx < self.monitors.len() - 1 {
+                        if self.current_idx + 1 < self.monitors.len() {
                             self.current_idx += 1;
                         } else {
                             self.current_idx = 0;
@@ -283,17 +310,24 @@ impl Block for Xrandr {
                     mb => {
                         use LogicalDirection::*;
                         match self.config.scrolling.to_logical_direction(mb) {
+                            // The brightness is kept between `min_brightness` and 100%
                             Some(Up) => {
                                 if let Some(monitor) = self.monitors.get_mut(self.current_idx) {
-                                    if monitor.brightness <= (100 - self.step_width) {
-                                        monitor.set_brightness(self.step_width as i32);
+                                    if monitor.brightness < 100 {
+                                        let step =
+                                            cmp::min(self.step_width, 100 - monitor.brightness);
+                                        monitor.set_brightness(step as i32);
                                     }
                                 }
                             }
                             Some(Down) => {
                                 if let Some(monitor) = self.monitors.get_mut(self.current_idx) {
-                                    if monitor.brightness >= self.step_width {
-                                        monitor.set_brightness(-(self.step_width as i32));
+                                    if monitor.brightness > self.min_brightness {
+                                        let step = cmp::min(
+                                            self.step_width,
+                                            monitor.brightness - self.min_brightness,
+                                        );
+                                        monitor.set_brightness(-(step as i32));
                                     }
                                 }
                             }
@@ -0,0 +1,280 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use dbus::blocking::Connection;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+#[derive(Copy, Clone, Debug, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Driver {
+    #[default]
+    PipeWire,
+    Jack,
+}
+
+/// The state of the audio graph at one point in time.
+#[derive(Debug, Default, PartialEq)]
+struct Stats {
+    xruns: u64,
+    rate: u64,
+    quantum: u64,
+}
+
+/// The latest stats, and whether there were new xruns since the previous ones.
+type Sample = Result<(Stats, bool)>;
+
+pub struct Xruns {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    sample: Arc<Mutex<Option<Sample>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct XrunsConfig {
+    /// The sound server to ask, "pipewire" or "jack"
+    #[serde(default)]
+    pub driver: Driver,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "XrunsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "XrunsConfig::default_format")]
+    pub format: String,
+}
+
+impl XrunsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{xruns} {quantum}/{rate}".to_owned()
+    }
+}
+
+/// Parses the last iteration of `pw-top --batch-mode`. The errors of all nodes are summed up,
+/// and the rate and quantum are those of the first running driver; followers are listed
+/// below their driver with a `+`.
+fn parse_pw_top(output: &str) -> Stats {
+    let last = output
+        .rsplit_terminator('\n')
+        // The header of every iteration, whose first column is the state just like for nodes
+        .take_while(|line| !line.contains("QUANT"))
+        .collect::<Vec<_>>();
+    let mut stats = Stats::default();
+    for line in last.iter().rev() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 9 {
+            continue;
+        }
+        let number = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+        stats.xruns += number(8);
+        if stats.rate == 0 && fields[0] == "R" && !line.contains(" + ") {
+            stats.quantum = number(2);
+            stats.rate = number(3);
+        }
+    }
+    stats
+}
+
+/// The latency of one quantum in milliseconds.
+fn latency(stats: &Stats) -> String {
+    match stats.rate {
+        0 => "-".to_owned(),
+        rate => format!("{:.1}", stats.quantum as f64 * 1000.0 / rate as f64),
+    }
+}
+
+fn read_stats(driver: Driver, dbus_conn: Option<&Connection>) -> Result<Stats> {
+    match driver {
+        Driver::PipeWire => {
+            let output = Command::new("pw-top")
+                .args(["--batch-mode", "--iterations", "2"])
+                .output()
+                .block_error("xruns", "failed to run pw-top")?;
+            Ok(parse_pw_top(&String::from_utf8_lossy(&output.stdout)))
+        }
+        Driver::Jack => {
+            // jackdbus, which most JACK setups nowadays use
+            let proxy = dbus_conn
+                .block_error("xruns", "no D-Bus connection")?
+                .with_proxy(
+                    "org.jackaudio.service",
+                    "/org/jackaudio/Controller",
+                    Duration::from_millis(1000),
+                );
+            let call = |method: &str| -> Result<u64> {
+                let (value,): (u32,) =
+                    proxy
+                        .method_call("org.jackaudio.JackControl", method, ())
+                        .block_error("xruns", &format!("failed to call {} on jackdbus", method))?;
+                Ok(value.into())
+            };
+            Ok(Stats {
+                xruns: call("GetXruns")?,
+                rate: call("GetSampleRate")?,
+                quantum: call("GetBufferSize")?,
+            })
+        }
+    }
+}
+
+impl ConfigBlock for Xruns {
+    type Config = XrunsConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let dbus_conn = match block_config.driver {
+            Driver::Jack => Some(
+                Connection::new_session()
+                    .block_error("xruns", "failed to establish D-Bus connection")?,
+            ),
+            Driver::PipeWire => None,
+        };
+        let sample = Arc::new(Mutex::new(None));
+
+        // pw-top samples for about a second, so the stats are read off the main thread
+        {
+            let id = id.clone();
+            let sample = sample.clone();
+            let driver = block_config.driver;
+            let interval = block_config.interval;
+            thread::Builder::new()
+                .name("xruns".into())
+                .spawn(move || {
+                    let mut last_xruns = None;
+                    loop {
+                        let result = read_stats(driver, dbus_conn.as_ref()).map(|stats| {
+                            // Only new xruns are worth a warning, not those from before the
+                            // bar started
+                            let new_xruns = matches!(last_xruns, Some(last) if stats.xruns > last);
+                            last_xruns = Some(stats.xruns);
+                            (stats, new_xruns)
+                        });
+                        *sample.lock().unwrap() = Some(result);
+                        tx.send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                        thread::sleep(interval);
+                    }
+                })
+                .unwrap();
+        }
+
+        Ok(Xruns {
+            id,
+            text: TextWidget::new(config).with_icon("xruns"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("xruns", "Invalid format specified")?,
+            sample,
+        })
+    }
+}
+
+impl Block for Xruns {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let sample = self
+            .sample
+            .lock()
+            .block_error("xruns", "failed to acquire lock")?;
+
+        match *sample {
+            Some(Ok((ref stats, new_xruns))) => {
+                let values = map!(
+                    "{xruns}" => stats.xruns.to_string(),
+                    "{rate}" => stats.rate.to_string(),
+                    "{quantum}" => stats.quantum.to_string(),
+                    "{latency}" => latency(stats)
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(if new_xruns {
+                    State::Warning
+                } else {
+                    State::Idle
+                });
+            }
+            Some(Err(ref e)) => {
+                self.text.set_text(format!("{}", e));
+                self.text.set_state(State::Critical);
+            }
+            None => (),
+        }
+
+        // Updated by the sampling thread
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pw_top() {
+        let output = "\
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+S   28      0      0    ---     ---   ---   ---     0                  Dummy-Driver
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+S   28      0      0    ---     ---   ---   ---     0                  Dummy-Driver
+R   45   1024  48000  21.3us  12.0us  0.00  0.00    3    S32LE 2 48000 alsa_output.pci
+R   67      0      0  10.1us   8.2us  0.00  0.00    1    F32LE 2 48000  + Firefox
+";
+        let stats = parse_pw_top(output);
+        assert_eq!(
+            stats,
+            Stats {
+                xruns: 4,
+                rate: 48000,
+                quantum: 1024
+            }
+        );
+        assert_eq!(latency(&stats), "21.3");
+
+        // Suspended nodes may be listed between running ones
+        let output = "\
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+R   45   1024  48000  21.3us  12.0us  0.00  0.00    3    S32LE 2 48000 alsa_output.pci
+S   52      0      0    ---     ---   ---   ---     0                  alsa_output.hdmi
+R   60    256  48000  11.0us   5.0us  0.00  0.00    2    S24LE 2 48000 alsa_output.usb
+";
+        assert_eq!(
+            parse_pw_top(output),
+            Stats {
+                xruns: 5,
+                rate: 48000,
+                quantum: 1024
+            }
+        );
+        assert_eq!(parse_pw_top(""), Stats::default());
+    }
+}
@@ -0,0 +1,161 @@
+use std::fs::read_to_string;
+use std::path::Path;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{format_speed, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+/// The sizes in bytes from the `mm_stat` file of a zram device.
+#[derive(Debug, PartialEq)]
+struct MmStat {
+    /// The uncompressed size of the stored data
+    orig: u64,
+    /// The compressed size of the stored data
+    compressed: u64,
+    /// The memory used for storing the data, including fragmentation and metadata
+    used: u64,
+}
+
+impl MmStat {
+    fn parse(mm_stat: &str) -> Option<Self> {
+        let mut fields = mm_stat.split_whitespace().map(|field| field.parse().ok());
+        Some(MmStat {
+            orig: fields.next()??,
+            compressed: fields.next()??,
+            used: fields.next()??,
+        })
+    }
+}
+
+pub struct Zram {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    device: String,
+    available: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ZramConfig {
+    /// The zram device in `/sys/block` to show
+    #[serde(default = "ZramConfig::default_device")]
+    pub device: String,
+
+    /// Update interval in seconds
+    #[serde(
+        default = "ZramConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ZramConfig::default_format")]
+    pub format: String,
+}
+
+impl ZramConfig {
+    fn default_device() -> String {
+        "zram0".to_owned()
+    }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{compressed}/{orig} ({ratio})".to_owned()
+    }
+}
+
+impl ConfigBlock for Zram {
+    type Config = ZramConfig;
+
+    fn new(block_config: Self::Config, config: Config, _tx: Sender<Task>) -> Result<Self> {
+        Ok(Zram {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("zram"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("zram", "Invalid format specified")?,
+            device: block_config.device,
+            available: false,
+        })
+    }
+}
+
+impl Block for Zram {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let device_path = Path::new("/sys/block").join(&self.device);
+        // The device only exists while the zram module is loaded
+        self.available = device_path.exists();
+        if !self.available {
+            return Ok(Some(self.update_interval.into()));
+        }
+
+        let mm_stat = read_to_string(device_path.join("mm_stat")).block_error(
+            "zram",
+            &format!("failed to read mm_stat of {}", self.device),
+        )?;
+        let stat = MmStat::parse(&mm_stat).block_error("zram", "failed to parse mm_stat")?;
+        let swappiness = read_to_string("/proc/sys/vm/swappiness").unwrap_or_default();
+
+        let values = map!(
+            "{orig}" => format_speed(stat.orig, 3, "B", false),
+            "{compressed}" => format_speed(stat.compressed, 3, "B", false),
+            "{used}" => format_speed(stat.used, 3, "B", false),
+            // Nothing is stored yet without compressed data
+            "{ratio}" => match stat.compressed {
+                0 => String::new(),
+                compressed => format!("{:.1}", stat.orig as f64 / compressed as f64),
+            },
+            "{swappiness}" => swappiness.trim().to_owned()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.available {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mm_stat() {
+        assert_eq!(
+            MmStat::parse(
+                " 905592832 246441089 258519040        0 258519040    17665    30451     1938     0\n"
+            ),
+            Some(MmStat {
+                orig: 905592832,
+                compressed: 246441089,
+                used: 258519040,
+            })
+        );
+        assert_eq!(MmStat::parse("905592832 246441089"), None);
+    }
+}
@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 use std::ops::Deref;
 use std::path::Path;
 use std::str::FromStr;
+use std::time::Duration;
 
 use serde::de::{Deserialize, Deserializer, Error};
 use serde_derive::Deserialize;
@@ -27,16 +28,53 @@ pub struct Config {
     /// motion which is undesired for sliders. Use "natural" to invert this.
     #[serde(default = "Scrolling::default", rename = "scrolling")]
     pub scrolling: Scrolling,
+    /// Whether to draw a border around blocks in the color of their state.
+    #[serde(default)]
+    pub border: bool,
+    /// Whether icons are placed in front of or after the text, "left" or "right".
+    #[serde(default)]
+    pub icon_position: IconPosition,
+    /// Whether critical blocks blink by alternating between their critical and idle colors.
+    #[serde(default)]
+    pub blink_critical: bool,
+    /// Whether critical blocks are marked as urgent, for i3bar to show them in its urgent colors.
+    #[serde(default)]
+    pub urgent_on_critical: bool,
+    /// What format placeholders whose value is unavailable are rendered as.
+    #[serde(default = "Config::default_placeholder")]
+    pub placeholder: String,
+    /// How long after a click a second one on the same widget counts as a double-click.
+    #[serde(
+        default = "Config::default_double_click_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub double_click_interval: Duration,
     #[serde(rename = "block", deserialize_with = "deserialize_blocks")]
     pub blocks: Vec<(String, value::Value)>,
 }
 
+impl Config {
+    fn default_double_click_interval() -> Duration {
+        Duration::from_millis(300)
+    }
+
+    pub(crate) fn default_placeholder() -> String {
+        "N/A".to_owned()
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             icons: icons::default(),
             theme: Theme::default(),
             scrolling: Scrolling::default(),
+            border: false,
+            icon_position: IconPosition::default(),
+            blink_critical: false,
+            urgent_on_critical: false,
+            placeholder: Config::default_placeholder(),
+            double_click_interval: Config::default_double_click_interval(),
             blocks: Vec::new(),
         }
     }
@@ -51,6 +89,12 @@ impl From<LegacyConfig> for Config {
                 .and_then(|s| Theme::from_name(s.as_str()))
                 .unwrap_or_default(),
             scrolling: legacy_config.scrolling,
+            border: false,
+            icon_position: IconPosition::default(),
+            blink_critical: false,
+            urgent_on_critical: false,
+            placeholder: Config::default_placeholder(),
+            double_click_interval: Config::default_double_click_interval(),
             blocks: legacy_config.blocks,
         }
     }
@@ -116,7 +160,28 @@ impl Default for Scrolling {
     }
 }
 
-fn deserialize_blocks<'de, D>(deserializer: D) -> Result<Vec<(String, value::Value)>, D::Error>
+#[derive(Deserialize, Copy, Clone, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IconPosition {
+    #[default]
+    Left,
+    Right,
+}
+
+impl IconPosition {
+    /// The `full_text` of a widget showing `icon` next to `text`. Icons carry
+    /// their own padding, so the text is padded on the other side only.
+    pub fn render(self, icon: &str, text: &str) -> String {
+        match self {
+            IconPosition::Left => format!("{}{} ", icon, text),
+            IconPosition::Right => format!(" {}{}", text, icon),
+        }
+    }
+}
+
+pub(crate) fn deserialize_blocks<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(String, value::Value)>, D::Error>
 where
     D: Deserializer<'de>,
 {
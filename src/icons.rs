@@ -19,15 +19,38 @@ lazy_static! {
         "bat_quarter" => " BAT ",
         "bat_three_quarters" => " BAT ",
         "bluetooth" => " BT",
+        "card_profile" => " AUDIO ",
+        "charge_threshold" => " THRESH ",
+        "ci" => " CI ",
         "cogs" => " LOAD ",
         "cpu" => " CPU ",
+        "cpu_vulnerabilities" => " CPU ",
+        "departures" => " ",
         "disk_drive" => " DISK ",
+        "display_profile" => " PROFILE ",
+        "dns" => " DNS ",
         "docker" => " DOCKER ",
+        "failed_units" => " FAILED ",
+        "fan" => " FAN ",
+        "file_count" => " FILES ",
+        "focused_process" => " PROC ",
+        "git" => " GIT ",
         "github" => " GITHUB ",
         "gpu" => " GPU ",
+        "group" => " + ",
         "headphones" => " HEAD",
+        "idle_inhibitor_off" => " IDLE ",
+        "idle_inhibitor_on" => " CAFE ",
         "joystick" => " JOY",
+        "kbd_backlight" => " KBD ",
+        "kernel" => " KERNEL ",
         "keyboard" => " KBD",
+        "layout_splith" => " H ",
+        "layout_splitv" => " V ",
+        "layout_stacked" => " S ",
+        "layout_tabbed" => " T ",
+        "locked" => " LOCKED ",
+        "log_watch" => " LOG ",
         "mail" => " ",
         "memory_mem" => " MEM ",
         "memory_swap" => " SWAP ",
@@ -42,16 +65,40 @@ lazy_static! {
         "net_vpn" => " VPN",
         "net_wired" => " ETH",
         "net_wireless" => " WLAN",
+        "nextcloud" => " NC ",
+        "nextcloud_error" => " NC! ",
+        "nextcloud_sync" => " NC~ ",
+        "night_light" => " NIGHT ",
+        "night_light_off" => " DAY ",
         "notification" => " NOTIF ",
+        "notification_off" => " DND ",
+        "obs" => " OBS ",
         "phone" => " PHONE ",
         "phone_disconnected" => " PHONE ",
         "ping" => " PING ",
         "pomodoro" => " POMODORO ",
+        "privacy" => " REC ",
+        "process" => " PROC ",
+        "profile_balanced" => " BAL ",
+        "profile_performance" => " PERF ",
+        "profile_power_saver" => " SAVE ",
+        "rapl" => " PWR ",
+        "reboot" => " REBOOT ",
+        "recording_space" => " REC ",
+        "resolution" => " RES ",
+        "screen_layout" => " SCREEN ",
+        "ssh_sessions" => " SSH ",
+        "sunrise" => " RISE ",
+        "sunset" => " SET ",
         "tasks" => " TSK ",
         "thermometer" => " TEMP ",
+        "throttle" => " THROTTLE ",
         "time" => " ",
+        "timers" => " ",
         "toggle_off" => " OFF ",
         "toggle_on" => " ON ",
+        "transmission" => " TORRENT ",
+        "unlocked" => " UNLOCKED ",
         "update" => " UPD ",
         "uptime" => " UP ",
         "volume_empty" => " VOL ",
@@ -62,13 +109,21 @@ lazy_static! {
         "microphone_full" => " MIC ",
         "microphone_half" => " MIC ",
         "microphone_muted" => " MIC MUTED ",
+        "vpn_off" => " NOVPN ",
+        "vpn_on" => " VPN ",
+        "vu_meter" => " VU ",
         "weather_clouds" => " CLOUDY ",
+        "weather_clouds_night" => " CLOUDY ",
         "weather_default" => " WEATHER ",
+        "weather_fog" => " FOG ",
+        "weather_moon" => " CLEAR ",
         "weather_rain" => " RAIN ",
         "weather_snow" => " SNOW ",
         "weather_sun" => " SUNNY ",
         "weather_thunder" => " STORM ",
-        "xrandr" => " SCREEN "
+        "xrandr" => " SCREEN ",
+        "xruns" => " AUDIO ",
+        "zram" => " ZRAM "
     };
 
     // FontAwesome 4
@@ -87,15 +142,38 @@ lazy_static! {
         "bat_quarter" => " \u{f243} ",
         "bat_three_quarters" => " \u{f241} ",
         "bluetooth" => " \u{f294}",
+        "card_profile" => " \u{f025} ",
+        "charge_threshold" => " \u{f0e7} ",
+        "ci" => " \u{f085} ",
         "cogs" => " \u{f085} ",
         "cpu" => " \u{f0e4} ",
+        "cpu_vulnerabilities" => " \u{f132} ",
+        "departures" => " \u{f207} ",
         "disk_drive" => " \u{f0a0} ",
+        "display_profile" => " \u{f109} ",
+        "dns" => " \u{f233} ",
         "docker" => " \u{f21a} ",
+        "failed_units" => " \u{f071} ",
+        "fan" => " \u{f013} ",
+        "file_count" => " \u{f0ae} ",
+        "focused_process" => " \u{f0e4} ",
+        "git" => " \u{f126} ",
         "github" => " \u{f09b} ",
         "gpu" => " \u{f26c} ",
+        "group" => " \u{f0c9} ",
         "headphones" => " \u{f025}",
+        "idle_inhibitor_off" => " \u{f070} ",
+        "idle_inhibitor_on" => " \u{f0f4} ",
         "joystick" => " \u{f11b}",
+        "kbd_backlight" => " \u{f11c} ",
+        "kernel" => " \u{f17c} ",
         "keyboard" => " \u{f11c}",
+        "layout_splith" => " \u{f07e} ",
+        "layout_splitv" => " \u{f07d} ",
+        "layout_stacked" => " \u{f0c9} ",
+        "layout_tabbed" => " \u{f0db} ",
+        "locked" => " \u{f023} ",
+        "log_watch" => " \u{f15c} ",
         "mail" => " \u{f0e0} ",
         "memory_mem" => " \u{f2db} ",
         "memory_swap" => " \u{f0a0} ",
@@ -112,17 +190,41 @@ lazy_static! {
         "net_vpn" => " \u{f023} ",
         "net_wired" => " \u{f0ac} ",
         "net_wireless" => " \u{f1eb} ",
+        "nextcloud" => " \u{f0c2} ",
+        "nextcloud_error" => " \u{f071} ",
+        "nextcloud_sync" => " \u{f021} ",
+        "night_light" => " \u{f186} ",
+        "night_light_off" => " \u{f185} ",
         "notification" => " \u{f0a2} ",
+        "notification_off" => " \u{f1f7} ",
+        "obs" => " \u{f03d} ",
         "phone" => " \u{f10b} ",
         "phone_disconnected" => " \u{1f4f5} ",
         "ping" => " \u{21ba} ",
         "pomodoro" => " \u{1f345} ",
+        "privacy" => " \u{f03d} ",
+        "process" => " \u{f21e} ",
+        "profile_balanced" => " \u{f24e} ",
+        "profile_performance" => " \u{f0e7} ",
+        "profile_power_saver" => " \u{f06c} ",
+        "rapl" => " \u{f0e7} ",
+        "reboot" => " \u{f01e} ",
+        "recording_space" => " \u{f0a0} ",
+        "resolution" => " \u{f108} ",
+        "screen_layout" => " \u{f108} ",
+        "ssh_sessions" => " \u{f120} ",
+        "sunrise" => " \u{f185} ",
+        "sunset" => " \u{f186} ",
         "tasks" => " \u{f0ae} ",
         "thermometer" => " \u{f2c8} ",
+        "throttle" => " \u{f06d} ",
         "time" => " \u{f017} ",
+        "timers" => " \u{f253} ",
         "toggle_off" => " \u{f204} ",
         "toggle_on" => " \u{f205} ",
+        "transmission" => " \u{f019} ",
         "unknown" => " \u{f128} ",
+        "unlocked" => " \u{f09c} ",
         "update" => " \u{f062} ", // Same as time symbol.
         "uptime" => " \u{f017} ",
         "volume_empty" => " \u{f026} ",
@@ -133,13 +235,21 @@ lazy_static! {
         "microphone_full" => " \u{f130} ",
         "microphone_half" => " \u{f130} ",
         "microphone_muted" => " \u{f131} ",
+        "vpn_off" => " \u{f09c} ",
+        "vpn_on" => " \u{f023} ",
+        "vu_meter" => " \u{f028} ",
         "weather_clouds" => " \u{f0c2} ",
+        "weather_clouds_night" => " \u{f0c2} ",
         "weather_default" => " \u{f0c2} ", // Cloud symbol as default
+        "weather_fog" => " \u{f0c2} ",
+        "weather_moon" => " \u{f186} ",
         "weather_rain" => " \u{f043} ",
         "weather_snow" => " \u{f2dc} ",
         "weather_sun" => " \u{f185} ",
         "weather_thunder" => " \u{f0e7} ",
-        "xrandr" => " \u{f26c} "
+        "xrandr" => " \u{f26c} ",
+        "xruns" => " \u{f028} ",
+        "zram" => " \u{f0a0} "
     };
 
     // FontAwesome 5
@@ -158,15 +268,38 @@ lazy_static! {
         "bat_quarter" => " \u{f243} ",
         "bat_three_quarters" => " \u{f241} ",
         "bluetooth" => " \u{f294}",
+        "card_profile" => " \u{f025} ",
+        "charge_threshold" => " \u{f0e7} ",
+        "ci" => " \u{f085} ",
         "cogs" => " \u{f085} ",
         "cpu" => " \u{f3fd} ",
+        "cpu_vulnerabilities" => " \u{f3ed} ",
+        "departures" => " \u{f207} ",
         "disk_drive" => " \u{f8b5} ",
+        "display_profile" => " \u{f109} ",
+        "dns" => " \u{f233} ",
         "docker" => " \u{f21a} ",
+        "failed_units" => " \u{f071} ",
+        "fan" => " \u{f863} ",
+        "file_count" => " \u{f0ae} ",
+        "focused_process" => " \u{f3fd} ",
+        "git" => " \u{f126} ",
         "github" => " \u{f09b} ",
         "gpu" => " \u{f26c} ",
+        "group" => " \u{f0c9} ",
         "headphones" => " \u{f025}",
+        "idle_inhibitor_off" => " \u{f070} ",
+        "idle_inhibitor_on" => " \u{f0f4} ",
         "joystick" => " \u{f11b}",
+        "kbd_backlight" => " \u{f11c} ",
+        "kernel" => " \u{f17c} ",
         "keyboard" => " \u{f11c}",
+        "layout_splith" => " \u{f337} ",
+        "layout_splitv" => " \u{f338} ",
+        "layout_stacked" => " \u{f0c9} ",
+        "layout_tabbed" => " \u{f0db} ",
+        "locked" => " \u{f023} ",
+        "log_watch" => " \u{f15c} ",
         "mail" => " \u{f0e0} ",
         "memory_mem" => " \u{f2db} ",
         "memory_swap" => " \u{f0a0} ",
@@ -183,17 +316,41 @@ lazy_static! {
         "net_vpn" => " \u{f023} ",
         "net_wired" => " \u{f6ff} ",
         "net_wireless" => " \u{f1eb} ",
+        "nextcloud" => " \u{f0c2} ",
+        "nextcloud_error" => " \u{f071} ",
+        "nextcloud_sync" => " \u{f2f1} ",
+        "night_light" => " \u{f186} ",
+        "night_light_off" => " \u{f185} ",
         "notification" => " \u{f0f3} ",
+        "notification_off" => " \u{f1f6} ",
+        "obs" => " \u{f03d} ",
         "phone" => " \u{f3cd} ",
         "phone_disconnected" => " \u{1f4f5} ",
         "ping" => " \u{f362} ",
         "pomodoro" => " \u{1f345} ",
+        "privacy" => " \u{f03d} ",
+        "process" => " \u{f21e} ",
+        "profile_balanced" => " \u{f24e} ",
+        "profile_performance" => " \u{f0e7} ",
+        "profile_power_saver" => " \u{f06c} ",
+        "rapl" => " \u{f0e7} ",
+        "reboot" => " \u{f2f9} ",
+        "recording_space" => " \u{f0a0} ",
+        "resolution" => " \u{f108} ",
+        "screen_layout" => " \u{f108} ",
+        "ssh_sessions" => " \u{f120} ",
+        "sunrise" => " \u{f185} ",
+        "sunset" => " \u{f186} ",
         "tasks" => " \u{f0ae} ",
         "thermometer" => " \u{f2c8} ",
+        "throttle" => " \u{f06d} ",
         "time" => " \u{f017} ",
+        "timers" => " \u{f253} ",
         "toggle_off" => " \u{f204} ",
         "toggle_on" => " \u{f205} ",
+        "transmission" => " \u{f019} ",
         "unknown" => " \u{f128} ",
+        "unlocked" => " \u{f3c1} ",
         "update" => " \u{f062} ",
         "uptime" => " \u{f2f2} ",
         "volume_empty" => " \u{f026} ",
@@ -204,13 +361,21 @@ lazy_static! {
         "microphone_half" => " \u{f130} ",
         "microphone_empty" => " \u{f130} ",
         "microphone_muted" => " \u{f131} ",
+        "vpn_off" => " \u{f3c1} ",
+        "vpn_on" => " \u{f023} ",
+        "vu_meter" => " \u{f028} ",
         "weather_clouds" => " \u{f0c2} ",
+        "weather_clouds_night" => " \u{f6c3} ",
         "weather_default" => " \u{f0c2} ", // Cloud symbol as default
+        "weather_fog" => " \u{f75f} ",
+        "weather_moon" => " \u{f186} ",
         "weather_rain" => " \u{f043} ",
         "weather_snow" => " \u{f2dc} ",
         "weather_sun" => " \u{f185} ",
         "weather_thunder" => " \u{f0e7} ",
-        "xrandr" => " \u{f26c} "
+        "xrandr" => " \u{f26c} ",
+        "xruns" => " \u{f028} ",
+        "zram" => " \u{f0a0} "
     };
 
     pub static ref MATERIAL: Map<String, String> = map_to_owned! {
@@ -223,15 +388,38 @@ lazy_static! {
         "bat_quarter" => " \u{e1a5} ",
         "bat_three_quarters" => " \u{e1a5} ",
         "bluetooth" => " \u{e1a7}",
+        "card_profile" => " \u{e310} ",
+        "charge_threshold" => " \u{e1a3} ",
+        "ci" => " \u{e869} ",
         "cogs" => " \u{e8b8} ",
         "cpu" => " \u{e640} ",
+        "cpu_vulnerabilities" => " \u{e32a} ",
+        "departures" => " \u{e530} ",
         "disk_drive" => " \u{e1db} ",
+        "display_profile" => " \u{e31e} ",
+        "dns" => " \u{e875} ",
         "docker" => " \u{e532} ",
+        "failed_units" => " \u{e002} ",
+        "fan" => " \u{e332} ",
+        "file_count" => " \u{e8f9} ",
+        "focused_process" => " \u{e322} ",
+        "git" => " \u{e8d4} ",
         "github" => " \u{e86f} ",
         "gpu" => " \u{e333} ",
+        "group" => " \u{e5d2} ",
         "headphones" => " \u{e60f}",
+        "idle_inhibitor_off" => " \u{e8f5} ",
+        "idle_inhibitor_on" => " \u{e541} ",
         "joystick" => " \u{e30f}",
+        "kbd_backlight" => " \u{e312} ",
+        "kernel" => " \u{e322} ",
         "keyboard" => " \u{e312}",
+        "layout_splith" => " \u{e8e4} ",
+        "layout_splitv" => " \u{e8e5} ",
+        "layout_stacked" => " \u{e8ef} ",
+        "layout_tabbed" => " \u{e8d8} ",
+        "locked" => " \u{e897} ",
+        "log_watch" => " \u{e873} ",
         "mail" => " \u{e0be} ",
         "memory_mem" => " \u{e322} ",
         "memory_swap" => " \u{e8d4} ",
@@ -241,22 +429,52 @@ lazy_static! {
         "music_pause" => " \u{e034} ",
         "music_play" => " \u{e037} ",
         "music_prev" => " \u{e045} ",
+        "nextcloud" => " \u{e2bf} ",
+        "nextcloud_error" => " \u{e629} ",
+        "nextcloud_sync" => " \u{e627} ",
+        "night_light" => " \u{e3a8} ",
+        "night_light_off" => " \u{e430} ",
         "notification" => " \u{e7f7} ",
+        "notification_off" => " \u{e7f6} ",
+        "obs" => " \u{e04b} ",
         "phone" => " \u{e324} ",
         "phone_disconnected" => " \u{1f4f5} ",
+        "ping" => " \u{e8d4} ",
         "pomodoro" => " \u{1f345} ",
+        "privacy" => " \u{e04b} ",
+        "process" => " \u{e8b9} ",
+        "profile_balanced" => " \u{e1a4} ",
+        "profile_performance" => " \u{e3e7} ",
+        "profile_power_saver" => " \u{e1a5} ",
+        "rapl" => " \u{ea0b} ",
+        "reboot" => " \u{e5d5} ",
+        "recording_space" => " \u{e1db} ",
+        "resolution" => " \u{e30c} ",
+        "screen_layout" => " \u{e30c} ",
+        "ssh_sessions" => " \u{e31b} ",
+        "sunrise" => " \u{e430} ",
+        "sunset" => " \u{e3a8} ",
         "tasks" => " \u{e8f9} ",
         "thermometer" => " \u{f2c8} ", // TODO
+        "throttle" => " \u{e80e} ",
         "time" => " \u{e192} ",
+        "timers" => " \u{e88b} ",
         "toggle_off" => " \u{e836} ",
         "toggle_on" => " \u{e837} ",
+        "transmission" => " \u{e2c4} ",
+        "unlocked" => " \u{e898} ",
         "update" => " \u{e8d7} ",
         "uptime" => " \u{e192} ", // Same as time symbol.
         "volume_empty" => " \u{e04e} ",
         "volume_full" => " \u{e050} ",
         "volume_half" => " \u{e04d} ",
         "volume_muted" => " \u{e04e} \u{e04f} ",
-        "xrandr" => " \u{e31e} "
+        "vpn_off" => " \u{e898} ",
+        "vpn_on" => " \u{e897} ",
+        "vu_meter" => " \u{e050} ",
+        "xrandr" => " \u{e31e} ",
+        "xruns" => " \u{e050} ",
+        "zram" => " \u{e8d4} "
     };
 }
 
@@ -3,6 +3,7 @@ use std::io;
 use std::option::Option;
 use std::string::*;
 use std::thread;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 use serde::{de, Deserializer};
@@ -26,9 +27,17 @@ pub struct I3BarEvent {
     pub instance: Option<String>,
     pub x: u64,
     pub y: u64,
+    /// Position of the click relative to the block, if the bar reports it
+    pub relative_x: Option<u64>,
+    /// Width of the block, if the bar reports it
+    pub width: Option<u64>,
 
     #[serde(deserialize_with = "deserialize_mousebutton")]
     pub button: MouseButton,
+
+    /// Whether this is the second click of a double-click, see `DoubleClicks`
+    #[serde(skip)]
+    pub double_click: bool,
 }
 
 impl I3BarEvent {
@@ -40,6 +49,40 @@ impl I3BarEvent {
     }
 }
 
+/// Detects double-clicks, i.e. two clicks with the same button on the same widget within
+/// `interval`. Only the second click is marked, so the first one is handled as usual.
+pub struct DoubleClicks {
+    interval: Duration,
+    last: Option<(I3BarEvent, Instant)>,
+}
+
+impl DoubleClicks {
+    pub fn new(interval: Duration) -> Self {
+        DoubleClicks {
+            interval,
+            last: None,
+        }
+    }
+
+    pub fn detect(&mut self, event: &mut I3BarEvent, now: Instant) {
+        event.double_click = match self.last {
+            Some((ref last, time)) => {
+                last.name == event.name
+                    && last.instance == event.instance
+                    && last.button == event.button
+                    && now.duration_since(time) <= self.interval
+            }
+            None => false,
+        };
+        // A third click starts over instead of being another double-click
+        self.last = if event.double_click {
+            None
+        } else {
+            Some((event.clone(), now))
+        };
+    }
+}
+
 pub fn process_events(sender: Sender<I3BarEvent>) {
     thread::Builder::new()
         .name("input".into())
@@ -92,3 +135,37 @@ where
 
     deserializer.deserialize_any(MouseButtonVisitor)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_clicks() {
+        let click = |name: &str| I3BarEvent {
+            name: Some(name.to_owned()),
+            instance: None,
+            x: 0,
+            y: 0,
+            relative_x: None,
+            width: None,
+            button: MouseButton::Left,
+            double_click: false,
+        };
+        let mut double_clicks = DoubleClicks::new(Duration::from_millis(300));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut detect = |name, ms| {
+            let mut event = click(name);
+            double_clicks.detect(&mut event, at(ms));
+            event.double_click
+        };
+
+        assert!(!detect("play", 0));
+        assert!(detect("play", 200));
+        assert!(!detect("play", 300));
+        assert!(!detect("next", 400));
+        assert!(!detect("play", 500));
+        assert!(!detect("play", 900));
+    }
+}
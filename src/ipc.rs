@@ -0,0 +1,188 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use swayipc::reply::{Event, Node, NodeLayout, NodeType, WindowChange};
+use swayipc::{Connection, EventType};
+
+use crate::errors::*;
+use crate::scheduler::Task;
+
+/// Spawns a new thread subscribed to the given i3/sway IPC events. The `handler` is called for
+/// every received event, and an update of the block `id` is requested whenever it returns `true`.
+pub fn subscribe<F>(
+    name: &str,
+    id: String,
+    events: &[EventType],
+    tx: Sender<Task>,
+    mut handler: F,
+) -> Result<()>
+where
+    F: FnMut(Event) -> bool + Send + 'static,
+{
+    // Fail early when there is no IPC socket to connect to at all.
+    Connection::new().block_error(name, "failed to acquire connect to IPC")?;
+
+    let events = events.to_vec();
+    thread::Builder::new()
+        .name(name.into())
+        .spawn(move || {
+            for event in Connection::new().unwrap().subscribe(&events).unwrap() {
+                if handler(event.unwrap()) {
+                    tx.send(Task {
+                        id: id.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+                }
+            }
+        })
+        .unwrap();
+
+    Ok(())
+}
+
+/// The ID of a window and its process. Sway reports the process directly, while on i3 it
+/// is read from the `_NET_WM_PID` property of the X11 window.
+fn window_pid(node: &Node) -> Option<(i64, i32)> {
+    let pid = node.pid.or_else(|| {
+        let output = Command::new("xprop")
+            .args(["-id", &node.window?.to_string(), "_NET_WM_PID"])
+            .output()
+            .ok()?;
+        parse_xprop_pid(&String::from_utf8_lossy(&output.stdout))
+    })?;
+    Some((node.id, pid))
+}
+
+/// Parses the output of `xprop _NET_WM_PID`, e.g. `_NET_WM_PID(CARDINAL) = 1234`.
+fn parse_xprop_pid(output: &str) -> Option<i32> {
+    output.split(" = ").nth(1)?.trim().parse().ok()
+}
+
+/// The ID of the focused window and its process, if it has one.
+pub type FocusedWindow = Arc<Mutex<Option<(i64, i32)>>>;
+
+/// Spawns a new thread following which window has focus, and returns its ID and process,
+/// kept up to date by the thread. An update of the block `id` is requested whenever the
+/// focus changes, or the focused window is closed.
+pub fn watch_focused_pid(name: &str, id: String, tx: Sender<Task>) -> Result<FocusedWindow> {
+    let focused = Connection::new()
+        .block_error(name, "failed to acquire connect to IPC")?
+        .get_tree()
+        .block_error(name, "failed to get tree via IPC")?
+        .find_focused(|n| n.node_type == NodeType::Con || n.node_type == NodeType::FloatingCon);
+    let focused = Arc::new(Mutex::new(focused.as_ref().and_then(window_pid)));
+    let focused_original = focused.clone();
+
+    subscribe(
+        name,
+        id,
+        &[EventType::Window],
+        tx,
+        move |event| match event {
+            Event::Window(e) => match e.change {
+                WindowChange::Focus => {
+                    *focused_original.lock().unwrap() = window_pid(&e.container);
+                    true
+                }
+                WindowChange::Close => {
+                    let mut focused = focused_original.lock().unwrap();
+                    match *focused {
+                        Some((id, _)) if id == e.container.id => {
+                            *focused = None;
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                _ => false,
+            },
+            _ => unreachable!(),
+        },
+    )?;
+
+    Ok(focused)
+}
+
+/// Returns the workspace that currently has focus, if any.
+pub fn focused_workspace(conn: &mut Connection, name: &str) -> Result<Option<Node>> {
+    Ok(conn
+        .get_tree()
+        .block_error(name, "failed to get tree via IPC")?
+        .find_focused(|n| n.node_type == NodeType::Workspace))
+}
+
+/// Returns the number of windows (i.e. leaf containers) below the given node.
+pub fn count_windows(node: &Node) -> usize {
+    node.nodes
+        .iter()
+        .chain(node.floating_nodes.iter())
+        .map(|n| {
+            if n.nodes.is_empty() && n.floating_nodes.is_empty() {
+                1
+            } else {
+                count_windows(n)
+            }
+        })
+        .sum()
+}
+
+/// Returns the first window below the given node whose X11 class or instance, or Wayland app
+/// id, matches `class` (case-insensitively).
+pub fn find_window<'a>(node: &'a Node, class: &str) -> Option<&'a Node> {
+    let matches = |name: &Option<String>| match name {
+        Some(name) => name.eq_ignore_ascii_case(class),
+        None => false,
+    };
+    let is_match = match node.window_properties {
+        Some(ref props) => matches(&props.class) || matches(&props.instance),
+        None => false,
+    } || matches(&node.app_id);
+    if is_match {
+        return Some(node);
+    }
+    node.nodes
+        .iter()
+        .chain(node.floating_nodes.iter())
+        .find_map(|n| find_window(n, class))
+}
+
+/// Returns the layout new windows are placed in next to the focused one, which is the layout
+/// of the focused container or, if it is a window, of its parent.
+pub fn focused_layout(node: &Node) -> Option<&NodeLayout> {
+    let mut parent = None;
+    let mut node = node;
+    loop {
+        if node.focused {
+            if node.nodes.is_empty() && node.floating_nodes.is_empty() {
+                return parent.map(|p: &Node| &p.layout).or(Some(&node.layout));
+            }
+            return Some(&node.layout);
+        }
+        let focus = *node.focus.first()?;
+        let next = node
+            .nodes
+            .iter()
+            .chain(node.floating_nodes.iter())
+            .find(|n| n.id == focus)?;
+        parent = Some(node);
+        node = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xprop_pid() {
+        assert_eq!(
+            parse_xprop_pid("_NET_WM_PID(CARDINAL) = 1234\n"),
+            Some(1234)
+        );
+        assert_eq!(parse_xprop_pid("_NET_WM_PID:  not found.\n"), None);
+    }
+}
@@ -12,9 +12,12 @@ mod config;
 mod errors;
 mod icons;
 mod input;
+mod ipc;
+mod pactl;
 mod scheduler;
 mod subprocess;
 mod themes;
+mod websocket;
 mod widget;
 mod widgets;
 
@@ -23,7 +26,7 @@ use cpuprofiler::PROFILER;
 
 use std::collections::HashMap;
 use std::ops::DerefMut;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use clap::{crate_authors, crate_description, crate_version, App, Arg, ArgMatches};
 use crossbeam_channel::{select, Receiver, Sender};
@@ -32,7 +35,7 @@ use crate::blocks::create_block;
 use crate::blocks::Block;
 use crate::config::{load_config, Config};
 use crate::errors::*;
-use crate::input::{process_events, I3BarEvent};
+use crate::input::{process_events, DoubleClicks, I3BarEvent};
 use crate::scheduler::{Task, UpdateScheduler};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::text::TextWidget;
@@ -227,16 +230,31 @@ fn run(matches: &ArgMatches) -> Result<()> {
     let mut ttnu = crossbeam_channel::after(Duration::from_millis(0));
 
     let one_shot = matches.is_present("one-shot");
+
+    // Blinking widgets alternate between their two looks on every tick
+    let blink_ticker = if config.blink_critical {
+        crossbeam_channel::tick(Duration::from_millis(500))
+    } else {
+        crossbeam_channel::never()
+    };
+    let mut blink_off = false;
+    let mut double_clicks = DoubleClicks::new(config.double_click_interval);
     loop {
         // We use the message passing concept of channel selection
         // to avoid busy wait
         select! {
             // Receive click events
-            recv(rx_clicks) -> res => if let Ok(event) = res {
+            recv(rx_clicks) -> res => if let Ok(mut event) = res {
+                    double_clicks.detect(&mut event, Instant::now());
                     for block in block_map.values_mut() {
+                        // The widgets are checked before the click, which may change them
+                        let update = block.update_on_click() && util::is_clicked(*block, &event);
                         block.click(&event)?;
+                        if update {
+                            block.update()?;
+                        }
                     }
-                    util::print_blocks(&order, &block_map, &config)?;
+                    util::print_blocks(&order, &block_map, &config, blink_off)?;
             },
             // Receive async update requests
             recv(rx_update_requests) -> request => if let Ok(req) = request {
@@ -245,13 +263,19 @@ fn run(matches: &ArgMatches) -> Result<()> {
                     .get_mut(&req.id)
                     .internal_error("scheduler", "could not get required block")?
                     .update()?;
-                util::print_blocks(&order, &block_map, &config)?;
+                util::print_blocks(&order, &block_map, &config, blink_off)?;
             },
             // Receive update timer events
             recv(ttnu) -> _ => {
                 scheduler.do_scheduled_updates(&mut block_map)?;
                 // redraw the blocks, state changed
-                util::print_blocks(&order, &block_map, &config)?;
+                util::print_blocks(&order, &block_map, &config, blink_off)?;
+            },
+            recv(blink_ticker) -> _ => if util::is_blinking(&block_map) {
+                blink_off = !blink_off;
+                util::print_blocks(&order, &block_map, &config, blink_off)?;
+            } else {
+                blink_off = false;
             },
         }
 
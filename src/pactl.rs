@@ -0,0 +1,58 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+
+use crate::scheduler::Task;
+
+/// How long to wait before running `pactl subscribe` again after it exited, e.g. because the
+/// sound server was restarted
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Spawns a new thread following the events of `pactl subscribe`, e.g. `Event 'change' on
+/// sink #42`. An update of the block `id` is requested for every event `filter` accepts, and
+/// whenever `pactl subscribe` was restarted, as events may have been missed meanwhile.
+pub fn subscribe<F>(name: &str, id: String, tx: Sender<Task>, filter: F)
+where
+    F: Fn(&str) -> bool + Send + 'static,
+{
+    thread::Builder::new()
+        .name(name.into())
+        .spawn(move || {
+            let update = || {
+                tx.send(Task {
+                    id: id.clone(),
+                    update_time: Instant::now(),
+                })
+                .unwrap();
+            };
+            let mut restarted = false;
+            loop {
+                if let Ok(mut child) = Command::new("pactl")
+                    .arg("subscribe")
+                    .env("LC_ALL", "C")
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                {
+                    if restarted {
+                        update();
+                    }
+                    let stdout = child.stdout.take().unwrap();
+                    for line in BufReader::new(stdout).lines() {
+                        match line {
+                            Ok(line) if filter(&line) => update(),
+                            Ok(_) => (),
+                            Err(_) => break,
+                        }
+                    }
+                    let _ = child.wait();
+                }
+                restarted = true;
+                thread::sleep(RESTART_DELAY);
+            }
+        })
+        .unwrap();
+}
@@ -40,6 +40,42 @@ impl cmp::Ord for Task {
     }
 }
 
+/// The longest delay `Backoff` grows to, unless the regular interval is even longer.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Exponential backoff for blocks that poll something that can persistently
+/// fail, like an external command that needs network access.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    interval: Duration,
+    failures: u32,
+}
+
+impl Backoff {
+    pub fn new(interval: Duration) -> Self {
+        Backoff {
+            interval,
+            failures: 0,
+        }
+    }
+
+    /// Resets the backoff after a successful update and returns the regular interval.
+    pub fn success(&mut self) -> Duration {
+        self.failures = 0;
+        self.interval
+    }
+
+    /// Returns the delay until the next attempt after a failed update. The
+    /// interval doubles with every consecutive failure, up to `MAX_BACKOFF`.
+    pub fn failure(&mut self) -> Duration {
+        self.failures = self.failures.saturating_add(1).min(16);
+        let cap = cmp::max(self.interval, MAX_BACKOFF);
+        self.interval
+            .checked_mul(1 << self.failures)
+            .map_or(cap, |delay| cmp::min(delay, cap))
+    }
+}
+
 pub struct UpdateScheduler {
     schedule: BinaryHeap<Task>,
 }
@@ -125,3 +161,24 @@ impl UpdateScheduler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(Duration::from_secs(60));
+        assert_eq!(backoff.failure(), Duration::from_secs(120));
+        assert_eq!(backoff.failure(), Duration::from_secs(240));
+        for _ in 0..10 {
+            backoff.failure();
+        }
+        assert_eq!(backoff.failure(), MAX_BACKOFF);
+        assert_eq!(backoff.success(), Duration::from_secs(60));
+        assert_eq!(backoff.failure(), Duration::from_secs(120));
+
+        let mut backoff = Backoff::new(Duration::from_secs(3600));
+        assert_eq!(backoff.failure(), Duration::from_secs(3600));
+    }
+}
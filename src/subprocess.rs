@@ -5,8 +5,18 @@ use std::thread;
 /// Spawns a new child process. This closes stdin and stdout, and returns to the caller after the
 /// child has been started, while a background thread waits for the child to exit.
 pub fn spawn_child_async(name: &str, args: &[&str]) -> io::Result<()> {
+    spawn_child_async_with_env(name, args, &[])
+}
+
+/// Like `spawn_child_async`, with additional environment variables for the child.
+pub fn spawn_child_async_with_env(
+    name: &str,
+    args: &[&str],
+    env: &[(&str, &str)],
+) -> io::Result<()> {
     let mut child = Command::new(name)
         .args(args)
+        .envs(env.iter().cloned())
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .spawn()?;
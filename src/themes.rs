@@ -23,6 +23,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#111111".to_owned(),
         alternating_tint_fg: "#111111".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref SOLARIZED_DARK: Theme = Theme {
@@ -41,6 +46,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref SOLARIZED_LIGHT: Theme = Theme {
@@ -59,6 +69,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref MODERN: Theme = Theme {
@@ -77,6 +92,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref PLAIN: Theme = Theme {
@@ -95,6 +115,11 @@ lazy_static! {
         separator_fg: "#a9a9a9".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref BAD_WOLF: Theme = Theme {
@@ -113,6 +138,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref GRUVBOX_LIGHT: Theme = Theme {
@@ -131,6 +161,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref GRUVBOX_DARK: Theme = Theme {
@@ -149,6 +184,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#000000".to_owned(),
         alternating_tint_fg: "#000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 
     pub static ref SPACE_VILLAIN: Theme = Theme {
@@ -167,6 +207,11 @@ lazy_static! {
         separator_fg: "auto".to_owned(),
         alternating_tint_bg: "#00000000".to_owned(),
         alternating_tint_fg: "#00000000".to_owned(),
+        idle_border: None,
+        info_border: None,
+        good_border: None,
+        warning_border: None,
+        critical_border: None,
     };
 }
 
@@ -188,6 +233,16 @@ pub struct Theme {
     pub separator_fg: String,
     pub alternating_tint_bg: String,
     pub alternating_tint_fg: String,
+    #[serde(default)]
+    pub idle_border: Option<String>,
+    #[serde(default)]
+    pub info_border: Option<String>,
+    #[serde(default)]
+    pub good_border: Option<String>,
+    #[serde(default)]
+    pub warning_border: Option<String>,
+    #[serde(default)]
+    pub critical_border: Option<String>,
 }
 
 impl Default for Theme {
@@ -249,6 +304,11 @@ pub struct ThemeOverrides {
     separator_fg: Option<String>,
     alternating_tint_bg: Option<String>,
     alternating_tint_fg: Option<String>,
+    idle_border: Option<String>,
+    info_border: Option<String>,
+    good_border: Option<String>,
+    warning_border: Option<String>,
+    critical_border: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -269,26 +329,39 @@ impl ThemeConfig {
             None
         }?;
         if let Some(overrides) = self.overrides {
-            theme.idle_bg = overrides.idle_bg.unwrap_or(theme.idle_bg);
-            theme.idle_fg = overrides.idle_fg.unwrap_or(theme.idle_fg);
-            theme.info_bg = overrides.info_bg.unwrap_or(theme.info_bg);
-            theme.info_fg = overrides.info_fg.unwrap_or(theme.info_fg);
-            theme.good_bg = overrides.good_bg.unwrap_or(theme.good_bg);
-            theme.good_fg = overrides.good_fg.unwrap_or(theme.good_fg);
-            theme.warning_bg = overrides.warning_bg.unwrap_or(theme.warning_bg);
-            theme.warning_fg = overrides.warning_fg.unwrap_or(theme.warning_fg);
-            theme.critical_bg = overrides.critical_bg.unwrap_or(theme.critical_bg);
-            theme.critical_fg = overrides.critical_fg.unwrap_or(theme.critical_fg);
-            theme.separator = overrides.separator.unwrap_or(theme.separator);
-            theme.separator_bg = overrides.separator_bg.unwrap_or(theme.separator_bg);
-            theme.separator_fg = overrides.separator_fg.unwrap_or(theme.separator_fg);
-            theme.alternating_tint_bg = overrides
-                .alternating_tint_bg
-                .unwrap_or(theme.alternating_tint_bg);
-            theme.alternating_tint_fg = overrides
-                .alternating_tint_fg
-                .unwrap_or(theme.alternating_tint_fg);
+            theme = overrides.apply(theme);
         }
         Some(theme)
     }
 }
+
+impl ThemeOverrides {
+    /// Replaces the colors of `theme` with the overridden ones.
+    pub fn apply(self, mut theme: Theme) -> Theme {
+        theme.idle_bg = self.idle_bg.unwrap_or(theme.idle_bg);
+        theme.idle_fg = self.idle_fg.unwrap_or(theme.idle_fg);
+        theme.info_bg = self.info_bg.unwrap_or(theme.info_bg);
+        theme.info_fg = self.info_fg.unwrap_or(theme.info_fg);
+        theme.good_bg = self.good_bg.unwrap_or(theme.good_bg);
+        theme.good_fg = self.good_fg.unwrap_or(theme.good_fg);
+        theme.warning_bg = self.warning_bg.unwrap_or(theme.warning_bg);
+        theme.warning_fg = self.warning_fg.unwrap_or(theme.warning_fg);
+        theme.critical_bg = self.critical_bg.unwrap_or(theme.critical_bg);
+        theme.critical_fg = self.critical_fg.unwrap_or(theme.critical_fg);
+        theme.separator = self.separator.unwrap_or(theme.separator);
+        theme.separator_bg = self.separator_bg.unwrap_or(theme.separator_bg);
+        theme.separator_fg = self.separator_fg.unwrap_or(theme.separator_fg);
+        theme.alternating_tint_bg = self
+            .alternating_tint_bg
+            .unwrap_or(theme.alternating_tint_bg);
+        theme.alternating_tint_fg = self
+            .alternating_tint_fg
+            .unwrap_or(theme.alternating_tint_fg);
+        theme.idle_border = self.idle_border.or(theme.idle_border);
+        theme.info_border = self.info_border.or(theme.info_border);
+        theme.good_border = self.good_border.or(theme.good_border);
+        theme.warning_border = self.warning_border.or(theme.warning_border);
+        theme.critical_border = self.critical_border.or(theme.critical_border);
+        theme
+    }
+}
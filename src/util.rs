@@ -1,5 +1,5 @@
 use num_traits::{clamp, ToPrimitive};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Display;
 use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
@@ -7,6 +7,7 @@ use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::prelude::v1::String;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
 use regex::Regex;
 use serde::de::DeserializeOwned;
@@ -15,6 +16,8 @@ use serde_json::value::Value;
 use crate::blocks::Block;
 use crate::config::Config;
 use crate::errors::*;
+use crate::input::I3BarEvent;
+use crate::widget::I3BarWidget;
 
 pub const USR_SHARE_PATH: &str = "/usr/share/i3status-rust";
 
@@ -212,10 +215,41 @@ impl PrintState {
     }
 }
 
+/// Whether any widget is blinking, see `Config::blink_critical`.
+pub fn is_blinking(block_map: &HashMap<String, &mut dyn Block>) -> bool {
+    block_map.values().any(|block| {
+        block
+            .view()
+            .iter()
+            .any(|w| w.get_blink_rendered().is_some())
+    })
+}
+
+/// Whether the click `event` was on one of the widgets of `block`.
+pub fn is_clicked(block: &dyn Block, event: &I3BarEvent) -> bool {
+    match event.name {
+        Some(ref name) => block
+            .view()
+            .iter()
+            .any(|w| w.get_rendered()["name"].as_str() == Some(name.as_str())),
+        None => false,
+    }
+}
+
+/// The output of `widget` and the JSON it was rendered from.
+fn render_widget(widget: &dyn I3BarWidget, blink_off: bool) -> (String, &Value) {
+    match widget.get_blink_rendered() {
+        Some(blink) if blink_off => (blink.to_string(), blink),
+        _ => (widget.to_string(), widget.get_rendered()),
+    }
+}
+
+/// Prints the blocks in `order`. Blinking widgets are shown blinked off if `blink_off` is set.
 pub fn print_blocks(
     order: &[String],
     block_map: &HashMap<String, &mut dyn Block>,
     config: &Config,
+    blink_off: bool,
 ) -> Result<()> {
     let mut state = PrintState {
         has_predecessor: false,
@@ -231,8 +265,8 @@ pub fn print_blocks(
         if widgets.is_empty() {
             continue;
         }
-        let first = widgets[0];
-        let color = first.get_rendered()["background"]
+        let (first, first_rendered) = render_widget(widgets[0], blink_off);
+        let color = first_rendered["background"]
             .as_str()
             .internal_error("util", "couldn't get background color")?;
 
@@ -264,18 +298,15 @@ pub fn print_blocks(
             if state.has_predecessor { "," } else { "" },
             separator.to_string()
         );
-        print!("{}", first.to_string());
+        print!("{}", first);
         state.set_last_bg(color.to_owned());
         state.set_predecessor(true);
 
         for widget in widgets.iter().skip(1) {
-            print!(
-                "{}{}",
-                if state.has_predecessor { "," } else { "" },
-                widget.to_string()
-            );
+            let (output, rendered) = render_widget(*widget, blink_off);
+            print!("{}{}", if state.has_predecessor { "," } else { "" }, output);
             state.set_last_bg(String::from(
-                widget.get_rendered()["background"]
+                rendered["background"]
                     .as_str()
                     .internal_error("util", "couldn't get background color")?,
             ));
@@ -305,6 +336,130 @@ pub fn color_to_rgba(color: (u8, u8, u8, u8)) -> String {
     )
 }
 
+/// Formats a duration in seconds with its two largest units, e.g. `2d 5h`.
+pub fn format_duration(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// The rate at which a value changes per second, fitted by least squares to samples of
+/// seconds and values, e.g. of the available space of a disk. Negative while it decreases.
+fn fill_rate(samples: &[(f64, f64)]) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let n = samples.len() as f64;
+    let mean_t = samples.iter().map(|(t, _)| t).sum::<f64>() / n;
+    let mean_b = samples.iter().map(|(_, b)| b).sum::<f64>() / n;
+    let covariance: f64 = samples
+        .iter()
+        .map(|(t, b)| (t - mean_t) * (b - mean_b))
+        .sum();
+    let variance: f64 = samples.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    if variance == 0.0 {
+        None
+    } else {
+        Some(covariance / variance)
+    }
+}
+
+/// The latest samples of a value, e.g. of the available space of a disk, for estimating how
+/// fast it changes.
+#[derive(Debug, Clone)]
+pub struct History {
+    samples: VecDeque<(Instant, u64)>,
+    /// Samples that are older are dropped, otherwise only the latest `HISTORY_LEN` are kept
+    max_age: Option<Duration>,
+}
+
+/// Enough samples to smooth out the noise of single updates
+const HISTORY_LEN: usize = 30;
+
+impl Default for History {
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+impl History {
+    /// Keeps the latest 30 samples.
+    pub fn new() -> Self {
+        History {
+            samples: VecDeque::with_capacity(HISTORY_LEN),
+            max_age: None,
+        }
+    }
+
+    /// Keeps the samples that are at most `max_age` older than the latest one instead.
+    pub fn within(max_age: Duration) -> Self {
+        History {
+            samples: VecDeque::new(),
+            max_age: Some(max_age),
+        }
+    }
+
+    pub fn push(&mut self, time: Instant, value: u64) {
+        match self.max_age {
+            Some(max_age) => {
+                while matches!(self.samples.front(), Some((sample, _)) if time - *sample > max_age)
+                {
+                    self.samples.pop_front();
+                }
+            }
+            None if self.samples.len() == HISTORY_LEN => {
+                self.samples.pop_front();
+            }
+            None => (),
+        }
+        self.samples.push_back((time, value));
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The rate at which the value changes per second, negative while it decreases.
+    pub fn rate(&self) -> Option<f64> {
+        let &(start, _) = self.samples.front()?;
+        let samples: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .map(|(time, value)| (time.duration_since(start).as_secs_f64(), *value as f64))
+            .collect();
+        fill_rate(&samples)
+    }
+
+    /// The seconds until the latest value reaches zero at the current rate, if it decreases.
+    pub fn seconds_left(&self) -> Option<f64> {
+        let &(_, value) = self.samples.back()?;
+        match self.rate() {
+            Some(rate) if rate < 0.0 => Some(value as f64 / -rate),
+            _ => None,
+        }
+    }
+}
+
+/// The item after `current` in `items`, wrapping around, or the first one if `current` is
+/// none of them, e.g. to switch to the next profile on click.
+pub fn cycle_next<'a, T, U>(items: &'a [T], current: Option<&U>) -> Option<&'a T>
+where
+    T: PartialEq<U>,
+    U: ?Sized,
+{
+    let position = current.and_then(|current| items.iter().position(|item| item == current));
+    match position {
+        Some(position) => items.get((position + 1) % items.len()),
+        None => items.first(),
+    }
+}
+
 pub fn format_percent_bar(percent: f32) -> String {
     let percent = percent.min(100.0);
     let percent = percent.max(0.0);
@@ -379,10 +534,91 @@ pub fn add_colors(a: &str, b: &str) -> ::std::result::Result<String, Box<dyn std
     )))
 }
 
+fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    (hue, saturation, max)
+}
+
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> (u8, u8, u8) {
+    let c = value * saturation;
+    let x = c * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = value - c;
+    let channel = |v: f64| ((v + m) * 255.0).round() as u8;
+
+    (channel(r), channel(g), channel(b))
+}
+
+/// Interpolates between the colors `start` (at 0%) and `end` (at 100%) in HSV
+/// space, taking the shorter way around the hue circle.
+pub fn color_gradient(
+    start: &str,
+    end: &str,
+    percent: f64,
+) -> ::std::result::Result<String, Box<dyn std::error::Error>> {
+    let (r_s, g_s, b_s, a_s) = color_from_rgba(start)?;
+    let (r_e, g_e, b_e, a_e) = color_from_rgba(end)?;
+    let (mut h_s, s_s, v_s) = rgb_to_hsv(r_s, g_s, b_s);
+    let (mut h_e, s_e, v_e) = rgb_to_hsv(r_e, g_e, b_e);
+
+    // Grays have no hue of their own
+    if s_s == 0.0 {
+        h_s = h_e;
+    } else if s_e == 0.0 {
+        h_e = h_s;
+    }
+
+    let t = clamp(percent, 0.0, 100.0) / 100.0;
+    let mix = |a: f64, b: f64| a + (b - a) * t;
+    let mut hue_delta = h_e - h_s;
+    if hue_delta > 180.0 {
+        hue_delta -= 360.0;
+    } else if hue_delta < -180.0 {
+        hue_delta += 360.0;
+    }
+    let hue = (h_s + hue_delta * t).rem_euclid(360.0);
+    let (r, g, b) = hsv_to_rgb(hue, mix(s_s, s_e), mix(v_s, v_e));
+
+    Ok(color_to_rgba((
+        r,
+        g,
+        b,
+        mix(a_s as f64, a_e as f64).round() as u8,
+    )))
+}
+
+#[derive(Debug, Clone)]
+enum FormatToken {
+    Str(String, Option<Box<FormatToken>>),
+    Var(String, Option<Box<FormatToken>>),
+}
+
 #[derive(Debug, Clone)]
-pub enum FormatTemplate {
-    Str(String, Option<Box<FormatTemplate>>),
-    Var(String, Option<Box<FormatTemplate>>),
+pub struct FormatTemplate {
+    tokens: FormatToken,
+    /// What unavailable values are rendered as, see `Config::placeholder`
+    placeholder: String,
 }
 
 impl FormatTemplate {
@@ -392,43 +628,79 @@ impl FormatTemplate {
         //valid var tokens: {} containing any amount of alphanumericals
         let re = Regex::new(r"\{[a-zA-Z0-9_-]+?\}").internal_error("util", "invalid regex")?;
 
-        let mut token_vec: Vec<FormatTemplate> = vec![];
+        let mut token_vec: Vec<FormatToken> = vec![];
         let mut start: usize = 0;
 
         for re_match in re.find_iter(&s) {
             if re_match.start() != start {
                 let str_vec: Vec<u8> = (&s_as_bytes)[start..re_match.start()].to_vec();
-                token_vec.push(FormatTemplate::Str(
+                token_vec.push(FormatToken::Str(
                     String::from_utf8(str_vec)
                         .internal_error("util", "failed to convert string from UTF8")?,
                     None,
                 ));
             }
-            token_vec.push(FormatTemplate::Var(re_match.as_str().to_string(), None));
+            token_vec.push(FormatToken::Var(re_match.as_str().to_string(), None));
             start = re_match.end();
         }
         let str_vec: Vec<u8> = (&s_as_bytes)[start..].to_vec();
-        token_vec.push(FormatTemplate::Str(
+        token_vec.push(FormatToken::Str(
             String::from_utf8(str_vec)
                 .internal_error("util", "failed to convert string from UTF8")?,
             None,
         ));
-        let mut template: FormatTemplate = match token_vec.pop() {
+        let mut template: FormatToken = match token_vec.pop() {
             Some(token) => token,
-            _ => FormatTemplate::Str("".to_string(), None),
+            _ => FormatToken::Str("".to_string(), None),
         };
         while let Some(token) = token_vec.pop() {
             template = match token {
-                FormatTemplate::Str(s, _) => FormatTemplate::Str(s, Some(Box::new(template))),
-                FormatTemplate::Var(s, _) => FormatTemplate::Var(s, Some(Box::new(template))),
+                FormatToken::Str(s, _) => FormatToken::Str(s, Some(Box::new(template))),
+                FormatToken::Var(s, _) => FormatToken::Var(s, Some(Box::new(template))),
             }
         }
-        Ok(template)
+        Ok(FormatTemplate {
+            tokens: template,
+            placeholder: Config::default_placeholder(),
+        })
+    }
+
+    /// Sets what unavailable values are rendered as by `render_optional`, usually the
+    /// `placeholder` of the `Config` the block was created with.
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_owned();
+        self
+    }
+
+    pub fn placeholder(&self) -> &str {
+        &self.placeholder
     }
 
-    // TODO: Make this function tail-recursive for compiler optimization, also only use the version below, static_str
     pub fn render<T: Display>(&self, vars: &HashMap<String, T>) -> String {
-        use self::FormatTemplate::*;
+        self.tokens.render(vars)
+    }
+
+    pub fn render_static_str<T: Display>(&self, vars: &HashMap<&str, T>) -> Result<String> {
+        self.tokens.render_static_str(vars)
+    }
+
+    /// Like `render_static_str`, but values that are `None` are rendered as the placeholder.
+    pub fn render_optional<T: Display>(&self, vars: &HashMap<&str, Option<T>>) -> Result<String> {
+        let vars: HashMap<&str, String> = vars
+            .iter()
+            .map(|(key, value)| match value {
+                Some(value) => (*key, value.to_string()),
+                None => (*key, self.placeholder.clone()),
+            })
+            .collect();
+        self.tokens.render_static_str(&vars)
+    }
+}
+
+impl FormatToken {
+    // TODO: Make this function tail-recursive for compiler optimization, also only use the version below, static_str
+    fn render<T: Display>(&self, vars: &HashMap<String, T>) -> String {
+        use self::FormatToken::*;
         let mut rendered = String::new();
         match *self {
             Str(ref s, ref next) => {
@@ -451,8 +723,8 @@ impl FormatTemplate {
         rendered
     }
 
-    pub fn render_static_str<T: Display>(&self, vars: &HashMap<&str, T>) -> Result<String> {
-        use self::FormatTemplate::*;
+    fn render_static_str<T: Display>(&self, vars: &HashMap<&str, T>) -> Result<String> {
+        use self::FormatToken::*;
         let mut rendered = String::new();
         match *self {
             Str(ref s, ref next) => {
@@ -478,13 +750,65 @@ impl FormatTemplate {
     }
 }
 
+/// A format together with an optional `format_alt`, which blocks can let the
+/// user switch to by clicking them.
+#[derive(Debug, Clone)]
+pub struct AltFormat<T> {
+    format: T,
+    format_alt: Option<T>,
+    show_alt: bool,
+}
+
+impl<T> AltFormat<T> {
+    pub fn new(format: T, format_alt: Option<T>) -> Self {
+        AltFormat {
+            format,
+            format_alt,
+            show_alt: false,
+        }
+    }
+
+    /// The format that is currently selected.
+    pub fn get(&self) -> &T {
+        match self.format_alt {
+            Some(ref format_alt) if self.show_alt => format_alt,
+            _ => &self.format,
+        }
+    }
+
+    /// Switches between `format` and `format_alt`. Returns `false` if there is
+    /// no `format_alt` to switch to.
+    pub fn toggle(&mut self) -> bool {
+        self.show_alt = !self.show_alt && self.format_alt.is_some();
+        self.format_alt.is_some()
+    }
+}
+
 macro_rules! if_debug {
     ($x:block) => (if cfg!(debug_assertions) $x)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::util::{color_from_rgba, has_command};
+    use crate::util::{
+        color_from_rgba, color_gradient, cycle_next, fill_rate, format_duration, has_command,
+        AltFormat, FormatTemplate, History,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_alt_format_toggle() {
+        let mut format = AltFormat::new("a", Some("b"));
+        assert_eq!(*format.get(), "a");
+        assert!(format.toggle());
+        assert_eq!(*format.get(), "b");
+        assert!(format.toggle());
+        assert_eq!(*format.get(), "a");
+
+        let mut format = AltFormat::new("a", None);
+        assert!(!format.toggle());
+        assert_eq!(*format.get(), "a");
+    }
 
     #[test]
     // we assume sh is always available
@@ -527,4 +851,96 @@ mod tests {
         let rgba = color_from_rgba(invalid);
         assert!(rgba.is_err());
     }
+
+    #[test]
+    fn test_color_gradient() {
+        let gradient = |percent| color_gradient("#00FF00", "#FF0000", percent).unwrap();
+        assert_eq!(gradient(0.0), "#00FF00FF");
+        assert_eq!(gradient(50.0), "#FFFF00FF");
+        assert_eq!(gradient(100.0), "#FF0000FF");
+        assert_eq!(gradient(150.0), "#FF0000FF");
+        // The hue takes the shorter way, via magenta rather than green
+        assert_eq!(
+            color_gradient("#FF0000", "#0000FF", 50.0).unwrap(),
+            "#FF00FFFF"
+        );
+        assert!(color_gradient("invalid", "#FF0000", 50.0).is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(90.0), "2m");
+        assert_eq!(format_duration(3.0 * 3600.0 + 600.0), "3h 10m");
+        assert_eq!(format_duration(2.0 * 86400.0 + 5.0 * 3600.0), "2d 5h");
+    }
+
+    #[test]
+    fn test_cycle_next() {
+        let profiles = vec!["power-saver".to_owned(), "balanced".to_owned()];
+        assert_eq!(
+            cycle_next(&profiles, Some("power-saver")).unwrap(),
+            "balanced"
+        );
+        assert_eq!(
+            cycle_next(&profiles, Some("balanced")).unwrap(),
+            "power-saver"
+        );
+        assert_eq!(
+            cycle_next(&profiles, Some("custom")).unwrap(),
+            "power-saver"
+        );
+        assert_eq!(cycle_next(&profiles, None::<&str>).unwrap(), "power-saver");
+        assert_eq!(cycle_next::<String, str>(&[], None), None);
+        assert_eq!(cycle_next(&[60, 80, 100], Some(&100)), Some(&60));
+        assert_eq!(cycle_next(&[60, 80, 100], Some(&95)), Some(&60));
+    }
+
+    #[test]
+    fn test_history() {
+        let start = Instant::now();
+        // Writing 1 MB per second
+        let mut history = History::new();
+        for i in 0..40 {
+            history.push(start + Duration::from_secs(i), 139_000_000 - i * 1_000_000);
+        }
+        assert_eq!(history.samples.len(), 30);
+        assert!((history.rate().unwrap() + 1_000_000.0).abs() < 0.001);
+        assert!((history.seconds_left().unwrap() - 100.0).abs() < 0.001);
+
+        // Nothing is known from a single sample, or while nothing is written
+        let mut history = History::within(Duration::from_secs(60));
+        history.push(start, 1_000);
+        assert_eq!(history.rate(), None);
+        history.push(start + Duration::from_secs(10), 1_000);
+        assert_eq!(history.seconds_left(), None);
+        history.push(start + Duration::from_secs(65), 500);
+        assert_eq!(history.samples.len(), 2);
+    }
+
+    #[test]
+    fn test_fill_rate() {
+        assert_eq!(fill_rate(&[(0.0, 100.0)]), None);
+        assert_eq!(
+            fill_rate(&[(0.0, 100.0), (10.0, 80.0), (20.0, 60.0)]),
+            Some(-2.0)
+        );
+        assert_eq!(fill_rate(&[(0.0, 100.0), (10.0, 100.0)]), Some(0.0));
+    }
+
+    #[test]
+    fn test_format_empty_values() {
+        // Values that are empty on purpose, e.g. of inactive lock keys, stay empty
+        let format = FormatTemplate::from_string("{caps}{num} - {title}").unwrap();
+        let values = map!("{caps}" => "CAPS ", "{num}" => "", "{title}" => "Song");
+        assert_eq!(format.render_static_str(&values).unwrap(), "CAPS  - Song");
+    }
+
+    #[test]
+    fn test_format_unavailable_values() {
+        let format = FormatTemplate::from_string("{temp} {reallocated}").unwrap();
+        let values = map!("{temp}" => Some(40), "{reallocated}" => None);
+        assert_eq!(format.render_optional(&values).unwrap(), "40 N/A");
+        let format = format.with_placeholder("-");
+        assert_eq!(format.render_optional(&values).unwrap(), "40 -");
+    }
 }
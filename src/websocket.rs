@@ -0,0 +1,352 @@
+//! A minimal WebSocket client, so that blocks talking to local services like obs-websocket
+//! do not need a WebSocket crate. It supports no TLS, no extensions, and whole messages
+//! only. Also provides the base64 and SHA-256 functions that handshakes and authentication
+//! commonly require.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+
+use uuid::Uuid;
+
+use crate::errors::*;
+
+/// The largest frame, and the largest message made of several frames, that is accepted.
+/// The length of a frame is sent by the peer, so it must not decide how much is allocated.
+const MAX_MESSAGE_SIZE: u64 = 1 << 20;
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xa;
+
+pub struct WebSocket {
+    /// The name of the block using the connection, for errors
+    name: String,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl WebSocket {
+    /// Connects to `ws://host:port/` and asks for the subprotocol `protocol`.
+    pub fn connect(name: &str, host: &str, port: u16, protocol: &str) -> Result<Self> {
+        let stream = TcpStream::connect((host, port))
+            .block_error(name, &format!("failed to connect to {}:{}", host, port))?;
+        let mut writer = stream
+            .try_clone()
+            .block_error(name, "failed to clone TCP stream")?;
+        let mut reader = BufReader::new(stream);
+
+        write!(
+            writer,
+            "GET / HTTP/1.1\r\n\
+             Host: {}:{}\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\n\
+             Sec-WebSocket-Version: 13\r\n\
+             Sec-WebSocket-Protocol: {}\r\n\r\n",
+            host,
+            port,
+            base64(Uuid::new_v4().as_bytes()),
+            protocol
+        )
+        .block_error(name, "failed to send WebSocket handshake")?;
+
+        let mut status_line = String::new();
+        reader
+            .read_line(&mut status_line)
+            .block_error(name, "failed to read WebSocket handshake")?;
+        if status_line.split_whitespace().nth(1) != Some("101") {
+            return Err(BlockError(
+                name.to_owned(),
+                format!("WebSocket handshake rejected: {}", status_line.trim()),
+            ));
+        }
+        // Skip the remaining response headers
+        loop {
+            let mut header = String::new();
+            reader
+                .read_line(&mut header)
+                .block_error(name, "failed to read WebSocket handshake")?;
+            if header.trim().is_empty() {
+                break;
+            }
+        }
+
+        Ok(WebSocket {
+            name: name.to_owned(),
+            reader,
+            writer,
+        })
+    }
+
+    /// Sends a text message.
+    pub fn send(&mut self, message: &str) -> Result<()> {
+        let frame = encode_frame(OPCODE_TEXT, message.as_bytes(), Uuid::new_v4().as_bytes());
+        self.write(&frame)
+    }
+
+    /// Reads the next text or binary message, answering pings along the way.
+    pub fn receive(&mut self) -> Result<Vec<u8>> {
+        let mut message = Vec::new();
+        loop {
+            let mut header = [0; 2];
+            self.read_exact(&mut header)?;
+            let fin = header[0] & 0x80 != 0;
+            let opcode = header[0] & 0x0f;
+            let mut len = u64::from(header[1] & 0x7f);
+            if len == 126 {
+                let mut ext = [0; 2];
+                self.read_exact(&mut ext)?;
+                len = u64::from(u16::from_be_bytes(ext));
+            } else if len == 127 {
+                let mut ext = [0; 8];
+                self.read_exact(&mut ext)?;
+                len = u64::from_be_bytes(ext);
+            }
+            // Compared this way round, as the length sent by the peer may be close to u64::MAX
+            if len > MAX_MESSAGE_SIZE - message.len() as u64 {
+                return Err(BlockError(
+                    self.name.clone(),
+                    format!("WebSocket message larger than {} bytes", MAX_MESSAGE_SIZE),
+                ));
+            }
+            let mut payload = vec![0; len as usize];
+            self.read_exact(&mut payload)?;
+
+            match opcode {
+                // Text, binary and continuation frames
+                0x0..=0x2 => {
+                    message.extend_from_slice(&payload);
+                    if fin {
+                        return Ok(message);
+                    }
+                }
+                OPCODE_CLOSE => {
+                    return Err(BlockError(
+                        self.name.clone(),
+                        "the server closed the WebSocket connection".to_owned(),
+                    ))
+                }
+                OPCODE_PING => {
+                    let frame = encode_frame(OPCODE_PONG, &payload, Uuid::new_v4().as_bytes());
+                    self.write(&frame)?
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn write(&mut self, frame: &[u8]) -> Result<()> {
+        self.writer
+            .write_all(frame)
+            .block_error(&self.name, "failed to write to WebSocket")
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.reader
+            .read_exact(buf)
+            .block_error(&self.name, "failed to read from WebSocket")
+    }
+}
+
+/// Builds a single frame. Frames sent by a client always have to be masked, with the first
+/// four bytes of `mask`.
+fn encode_frame(opcode: u8, payload: &[u8], mask: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(0x80 | len as u8),
+        len if len <= 0xffff => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    let mask = &mask[..4];
+    frame.extend_from_slice(mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    frame
+}
+
+pub fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([
+                block[4 * i],
+                block[4 * i + 1],
+                block[4 * i + 2],
+                block[4 * i + 3],
+            ]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (x, y) in h.iter_mut().zip(&[a, b, c, d, e, f, g, hh]) {
+            *x = x.wrapping_add(*y);
+        }
+    }
+
+    let mut digest = [0; 32];
+    for (i, x) in h.iter().enumerate() {
+        digest[4 * i..4 * i + 4].copy_from_slice(&x.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            base64(&sha256(b"")),
+            "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="
+        );
+        assert_eq!(
+            base64(&sha256(b"abc")),
+            "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0="
+        );
+    }
+
+    #[test]
+    fn test_encode_frame() {
+        let mask = [1, 2, 3, 4];
+        assert_eq!(
+            encode_frame(OPCODE_TEXT, b"Hi", &mask),
+            vec![0x81, 0x82, 1, 2, 3, 4, b'H' ^ 1, b'i' ^ 2]
+        );
+        let frame = encode_frame(OPCODE_TEXT, &[0; 300], &mask);
+        assert_eq!(frame[1..4], [0x80 | 126, 0x01, 0x2c]);
+        assert_eq!(frame.len(), 4 + 4 + 300);
+    }
+
+    /// Accepts a single connection, completes the handshake and sends `frames`.
+    fn serve(frames: Vec<u8>) -> (u16, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let mut stream = stream;
+            stream
+                .write_all(b"HTTP/1.1 101 Switching Protocols\r\n\r\n")
+                .unwrap();
+            stream.write_all(&frames).unwrap();
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn test_receive() {
+        // A message in two frames, then a frame that claims to be 4 GiB long
+        let mut frames = vec![0x01, 3, b'f', b'o', b'o', 0x80, 3, b'b', b'a', b'r'];
+        frames.extend_from_slice(&[0x81, 127, 0, 0, 0, 1, 0, 0, 0, 0]);
+        let (port, server) = serve(frames);
+
+        let mut ws = WebSocket::connect("test", "127.0.0.1", port, "test").unwrap();
+        assert_eq!(ws.receive().unwrap(), b"foobar");
+        assert!(ws.receive().is_err());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_receive_huge_continuation() {
+        // The length of the continuation frame overflows when added to the first frame's
+        let mut frames = vec![0x01, 3, b'f', b'o', b'o', 0x80, 127];
+        frames.extend_from_slice(&(u64::MAX - 1).to_be_bytes());
+        let (port, server) = serve(frames);
+
+        let mut ws = WebSocket::connect("test", "127.0.0.1", port, "test").unwrap();
+        assert!(ws.receive().is_err());
+        server.join().unwrap();
+    }
+}
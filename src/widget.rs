@@ -5,10 +5,12 @@ use serde::de::{Deserialize, IntoDeserializer};
 use serde_derive::Deserialize;
 use serde_json::value::Value;
 
+use crate::config::Config;
 use crate::themes::Theme;
 
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Copy, Clone, Deserialize, Default)]
 pub enum State {
+    #[default]
     Idle,
     Info,
     Good,
@@ -27,6 +29,49 @@ impl State {
             Critical => (&theme.critical_bg, &theme.critical_fg),
         }
     }
+
+    /// The border color for this state if borders are enabled, falling back
+    /// to the foreground color when the theme has none.
+    pub fn border(self, config: &Config) -> Option<&String> {
+        use self::State::*;
+        if !config.border {
+            return None;
+        }
+        let theme = &config.theme;
+        let border = match self {
+            Idle => &theme.idle_border,
+            Info => &theme.info_border,
+            Good => &theme.good_border,
+            Warning => &theme.warning_border,
+            Critical => &theme.critical_border,
+        };
+        Some(border.as_ref().unwrap_or_else(|| self.theme_keys(theme).1))
+    }
+
+    /// Whether a widget in this state is marked as urgent, which only critical widgets are,
+    /// and only if `urgent_on_critical` is enabled.
+    pub fn urgent(self, config: &Config) -> bool {
+        matches!(self, State::Critical) && config.urgent_on_critical
+    }
+
+    /// How a widget that is rendered as `rendered` looks while it blinks off. Only
+    /// critical widgets blink, and only if `blink_critical` is enabled.
+    pub fn blink(self, rendered: &Value, config: &Config) -> Option<Value> {
+        match self {
+            State::Critical if config.blink_critical => {
+                let idle = State::Idle;
+                let (key_bg, key_fg) = idle.theme_keys(&config.theme);
+                let mut blink = rendered.clone();
+                blink["background"] = json!(key_bg);
+                blink["color"] = json!(key_fg);
+                if let Some(border) = idle.border(config) {
+                    blink["border"] = json!(border);
+                }
+                Some(blink)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl FromStr for State {
@@ -38,7 +83,105 @@ impl FromStr for State {
     }
 }
 
+/// The minimum width of a widget, either as a number of characters or as a
+/// sample text that i3bar measures the width of.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum MinWidth {
+    Columns(usize),
+    Sample(String),
+}
+
+impl MinWidth {
+    /// The value for the `min_width` field of a widget that shows `icon` in
+    /// front of its text.
+    pub fn render(&self, icon: &str) -> String {
+        match self {
+            MinWidth::Columns(n) => format!("{}{} ", icon, "0".repeat(*n)),
+            MinWidth::Sample(sample) => format!("{}{} ", icon, sample),
+        }
+    }
+}
+
+/// A foreground color for the values in `min..max`, which takes precedence
+/// over the color of the widget's state.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColorRange {
+    pub min: f64,
+    pub max: f64,
+    pub color: String,
+}
+
+impl ColorRange {
+    /// The color of the first range containing `value`, if any.
+    pub fn find(ranges: &[ColorRange], value: f64) -> Option<String> {
+        ranges
+            .iter()
+            .find(|range| range.min <= value && value < range.max)
+            .map(|range| range.color.clone())
+    }
+}
+
 pub trait I3BarWidget {
     fn to_string(&self) -> String;
     fn get_rendered(&self) -> &Value;
+
+    /// How the widget looks while it blinks off, if it is blinking.
+    fn get_blink_rendered(&self) -> Option<&Value> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_range() {
+        let ranges = vec![
+            ColorRange {
+                min: 0.0,
+                max: 50.0,
+                color: "#00ff00".to_owned(),
+            },
+            ColorRange {
+                min: 50.0,
+                max: 101.0,
+                color: "#ff0000".to_owned(),
+            },
+        ];
+        assert_eq!(ColorRange::find(&ranges, 0.0), Some("#00ff00".to_owned()));
+        assert_eq!(ColorRange::find(&ranges, 50.0), Some("#ff0000".to_owned()));
+        assert_eq!(ColorRange::find(&ranges, 100.0), Some("#ff0000".to_owned()));
+        assert_eq!(ColorRange::find(&ranges, -1.0), None);
+    }
+
+    #[test]
+    fn test_blink() {
+        let mut config = Config::default();
+        let rendered = json!({
+            "full_text": "disk full",
+            "background": config.theme.critical_bg,
+            "color": config.theme.critical_fg
+        });
+        assert!(State::Critical.blink(&rendered, &config).is_none());
+
+        config.blink_critical = true;
+        let blink = State::Critical.blink(&rendered, &config).unwrap();
+        assert_eq!(blink["full_text"], "disk full");
+        assert_eq!(blink["background"], json!(config.theme.idle_bg));
+        assert_eq!(blink["color"], json!(config.theme.idle_fg));
+        assert!(State::Warning.blink(&rendered, &config).is_none());
+    }
+
+    #[test]
+    fn test_urgent() {
+        let mut config = Config::default();
+        assert!(!State::Critical.urgent(&config));
+
+        config.urgent_on_critical = true;
+        assert!(State::Critical.urgent(&config));
+        assert!(!State::Warning.urgent(&config));
+    }
 }
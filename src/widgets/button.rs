@@ -2,16 +2,21 @@ use serde_json::value::Value;
 
 use super::super::widget::I3BarWidget;
 use crate::config::Config;
-use crate::widget::State;
+use crate::widget::{MinWidth, State};
 
 #[derive(Clone, Debug)]
 pub struct ButtonWidget {
     content: Option<String>,
+    short_text: Option<String>,
     icon: Option<String>,
     state: State,
+    color: Option<String>,
+    min_width: Option<MinWidth>,
+    markup: bool,
     id: String,
     rendered: Value,
     cached_output: Option<String>,
+    blink_rendered: Option<Value>,
     config: Config,
 }
 
@@ -19,8 +24,12 @@ impl ButtonWidget {
     pub fn new(config: Config, id: &str) -> Self {
         ButtonWidget {
             content: None,
+            short_text: None,
             icon: None,
             state: State::Idle,
+            color: None,
+            min_width: None,
+            markup: true,
             id: String::from(id),
             rendered: json!({
                 "full_text": "",
@@ -32,6 +41,7 @@ impl ButtonWidget {
             }),
             config,
             cached_output: None,
+            blink_rendered: None,
         }
     }
 
@@ -59,11 +69,30 @@ impl ButtonWidget {
         self
     }
 
+    pub fn with_min_width(mut self, min_width: Option<MinWidth>) -> Self {
+        self.min_width = min_width;
+        self.update();
+        self
+    }
+
+    /// Whether the text is interpreted as pango markup, which it is by default.
+    pub fn with_markup(mut self, markup: bool) -> Self {
+        self.markup = markup;
+        self.update();
+        self
+    }
+
     pub fn set_text<S: Into<String>>(&mut self, content: S) {
         self.content = Some(content.into());
         self.update();
     }
 
+    /// Sets the text i3bar shows instead of the full text if the bar runs out of space.
+    pub fn set_short_text(&mut self, short_text: Option<String>) {
+        self.short_text = short_text;
+        self.update();
+    }
+
     pub fn set_icon(&mut self, name: &str) {
         self.icon = self.config.icons.get(name).cloned();
         self.update();
@@ -74,21 +103,44 @@ impl ButtonWidget {
         self.update();
     }
 
+    /// Overrides the foreground color of the state, until it is reset with `None`.
+    pub fn set_color(&mut self, color: Option<String>) {
+        self.color = color;
+        self.update();
+    }
+
     fn update(&mut self) {
         let (key_bg, key_fg) = self.state.theme_keys(&self.config.theme);
 
         self.rendered = json!({
-            "full_text": format!("{}{} ",
-                                self.icon.clone().unwrap_or_else(|| String::from(" ")),
-                                self.content.clone().unwrap_or_else(|| String::from(""))),
+            "full_text": self.config.icon_position.render(
+                self.icon.as_deref().unwrap_or(" "),
+                self.content.as_deref().unwrap_or("")),
             "separator": false,
             "name": self.id.clone(),
             "separator_block_width": 0,
             "background": key_bg,
-            "color": key_fg,
-            "markup": "pango"
+            "color": self.color.as_ref().unwrap_or(key_fg),
+            "markup": if self.markup { "pango" } else { "none" }
         });
+        if let Some(ref short_text) = self.short_text {
+            self.rendered["short_text"] = json!(self
+                .config
+                .icon_position
+                .render(self.icon.as_deref().unwrap_or(" "), short_text));
+        }
+        if let Some(ref min_width) = self.min_width {
+            let icon = self.icon.clone().unwrap_or_else(|| String::from(" "));
+            self.rendered["min_width"] = json!(min_width.render(&icon));
+        }
+        if let Some(border) = self.state.border(&self.config) {
+            self.rendered["border"] = json!(border);
+        }
+        if self.state.urgent(&self.config) {
+            self.rendered["urgent"] = json!(true);
+        }
 
+        self.blink_rendered = self.state.blink(&self.rendered, &self.config);
         self.cached_output = Some(self.rendered.to_string());
     }
 }
@@ -103,4 +155,8 @@ impl I3BarWidget for ButtonWidget {
     fn get_rendered(&self) -> &Value {
         &self.rendered
     }
+
+    fn get_blink_rendered(&self) -> Option<&Value> {
+        self.blink_rendered.as_ref()
+    }
 }
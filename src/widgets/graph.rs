@@ -12,6 +12,7 @@ pub struct GraphWidget {
     state: State,
     rendered: Value,
     cached_output: Option<String>,
+    blink_rendered: Option<Value>,
     config: Config,
 }
 #[allow(dead_code)]
@@ -30,6 +31,7 @@ impl GraphWidget {
             }),
             config,
             cached_output: None,
+            blink_rendered: None,
         }
     }
 
@@ -93,15 +95,22 @@ impl GraphWidget {
         let (key_bg, key_fg) = self.state.theme_keys(&self.config.theme);
 
         self.rendered = json!({
-            "full_text": format!("{}{} ",
-                                self.icon.clone().unwrap_or_else(|| String::from(" ")),
-                                self.content.clone().unwrap_or_else(|| String::from(""))),
+            "full_text": self.config.icon_position.render(
+                self.icon.as_deref().unwrap_or(" "),
+                self.content.as_deref().unwrap_or("")),
             "separator": false,
             "separator_block_width": 0,
             "background": key_bg.to_owned(),
             "color": key_fg.to_owned()
         });
+        if let Some(border) = self.state.border(&self.config) {
+            self.rendered["border"] = json!(border);
+        }
+        if self.state.urgent(&self.config) {
+            self.rendered["urgent"] = json!(true);
+        }
 
+        self.blink_rendered = self.state.blink(&self.rendered, &self.config);
         self.cached_output = Some(self.rendered.to_string());
     }
 }
@@ -116,4 +125,8 @@ impl I3BarWidget for GraphWidget {
     fn get_rendered(&self) -> &Value {
         &self.rendered
     }
+
+    fn get_blink_rendered(&self) -> Option<&Value> {
+        self.blink_rendered.as_ref()
+    }
 }
@@ -1,14 +1,26 @@
 use std::time::{Duration, Instant};
 
+use serde_derive::Deserialize;
 use serde_json::value::Value;
 
 use crate::config::Config;
 use crate::errors::*;
 use crate::widget::{I3BarWidget, State};
 
+/// The way the visible part moves through the text. Moving left, the text scrolls in from
+/// the right.
+#[derive(Copy, Clone, Debug, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationDirection {
+    #[default]
+    Left,
+    Right,
+}
+
 #[derive(Clone, Debug)]
 pub struct RotatingTextWidget {
     rotation_pos: usize,
+    rotation_direction: RotationDirection,
     max_width: usize,
     dynamic_width: bool,
     rotation_interval: Duration,
@@ -17,31 +29,29 @@ pub struct RotatingTextWidget {
     content: String,
     icon: Option<String>,
     state: State,
+    name: Option<String>,
     rendered: Value,
     cached_output: Option<String>,
+    blink_rendered: Option<Value>,
     config: Config,
     pub rotating: bool,
 }
 
 #[allow(dead_code)]
 impl RotatingTextWidget {
-    pub fn new(
-        interval: Duration,
-        speed: Duration,
-        max_width: usize,
-        dynamic_width: bool,
-        config: Config,
-    ) -> RotatingTextWidget {
+    pub fn new(max_width: usize, dynamic_width: bool, config: Config) -> RotatingTextWidget {
         RotatingTextWidget {
             rotation_pos: 0,
+            rotation_direction: RotationDirection::Left,
             max_width,
             dynamic_width,
-            rotation_interval: interval,
-            rotation_speed: speed,
+            rotation_interval: Duration::from_secs(10),
+            rotation_speed: Duration::from_millis(500),
             next_rotation: None,
             content: String::new(),
             icon: None,
             state: State::Idle,
+            name: None,
             rendered: json!({
                 "full_text": "",
                 "separator": false,
@@ -50,11 +60,30 @@ impl RotatingTextWidget {
                 "color": "#000000"
             }),
             cached_output: None,
+            blink_rendered: None,
             config,
             rotating: false,
         }
     }
 
+    /// Sets how long the text stays in place before it starts scrolling again.
+    pub fn with_rotation_interval(mut self, interval: Duration) -> Self {
+        self.rotation_interval = interval;
+        self
+    }
+
+    /// Sets how long each scrolling step of a single character takes.
+    pub fn with_rotation_speed(mut self, speed: Duration) -> Self {
+        self.rotation_speed = speed;
+        self
+    }
+
+    /// Sets which way the text scrolls.
+    pub fn with_rotation_direction(mut self, direction: RotationDirection) -> Self {
+        self.rotation_direction = direction;
+        self
+    }
+
     pub fn with_icon(mut self, name: &str) -> Self {
         self.icon = self.config.icons.get(name).cloned();
         self.update();
@@ -67,9 +96,16 @@ impl RotatingTextWidget {
         self
     }
 
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(String::from(name));
+        self.update();
+        self
+    }
+
     pub fn with_text(mut self, content: &str) -> Self {
         self.content = String::from(content);
         self.rotation_pos = 0;
+        self.rotating = false;
         if self.content.chars().count() > self.max_width {
             self.next_rotation = Some(Instant::now() + self.rotation_interval);
         } else {
@@ -93,6 +129,7 @@ impl RotatingTextWidget {
         if self.content != content {
             self.content = content;
             self.rotation_pos = 0;
+            self.rotating = false;
             if self.content.chars().count() > self.max_width {
                 self.next_rotation = Some(Instant::now() + self.rotation_interval);
             } else {
@@ -138,9 +175,9 @@ impl RotatingTextWidget {
         let icon = self.icon.clone().unwrap_or_else(|| String::from(" "));
 
         self.rendered = json!({
-            "full_text": format!("{}{} ",
-                                icon,
-                                self.get_rotated_content()),
+            "full_text": self.config.icon_position.render(
+                &icon,
+                &self.get_rotated_content()),
             "separator": false,
             "separator_block_width": 0,
             "min_width":
@@ -160,26 +197,43 @@ impl RotatingTextWidget {
             "color": key_fg
         });
 
+        if let Some(ref name) = self.name {
+            self.rendered["name"] = json!(name);
+        }
+        if let Some(border) = self.state.border(&self.config) {
+            self.rendered["border"] = json!(border);
+        }
+        if self.state.urgent(&self.config) {
+            self.rendered["urgent"] = json!(true);
+        }
+
+        self.blink_rendered = self.state.blink(&self.rendered, &self.config);
         self.cached_output = Some(self.rendered.to_string());
     }
 
+    /// Advances the rotation if it is due. Returns whether the text was rotated and when the
+    /// next rotation is due, which is never the case for text that fits into `max_width`.
     pub fn next(&mut self) -> Result<(bool, Option<Duration>)> {
         if let Some(next_rotation) = self.next_rotation {
             let now = Instant::now();
             if next_rotation > now {
                 Ok((false, Some(next_rotation - now)))
             } else if self.rotating {
-                if self.rotation_pos < self.content.chars().count() {
-                    self.rotation_pos += 1;
-                    self.next_rotation = Some(now + self.rotation_speed);
-                    self.update();
-                    Ok((true, Some(self.rotation_speed)))
-                } else {
-                    self.rotation_pos = 0;
+                // The text and the separator after it are one cycle, which ends where it began
+                let cycle = self.content.chars().count() + 1;
+                self.rotation_pos = match self.rotation_direction {
+                    RotationDirection::Left => (self.rotation_pos + 1) % cycle,
+                    RotationDirection::Right => (self.rotation_pos + cycle - 1) % cycle,
+                };
+                if self.rotation_pos == 0 {
                     self.rotating = false;
                     self.next_rotation = Some(now + self.rotation_interval);
                     self.update();
                     Ok((true, Some(self.rotation_interval)))
+                } else {
+                    self.next_rotation = Some(now + self.rotation_speed);
+                    self.update();
+                    Ok((true, Some(self.rotation_speed)))
                 }
             } else {
                 self.rotating = true;
@@ -201,4 +255,44 @@ impl I3BarWidget for RotatingTextWidget {
     fn get_rendered(&self) -> &Value {
         &self.rendered
     }
+
+    fn get_blink_rendered(&self) -> Option<&Value> {
+        self.blink_rendered.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rotates a widget without delays and collects the visible text after every step.
+    fn rotate(direction: RotationDirection) -> Vec<String> {
+        let mut widget = RotatingTextWidget::new(3, false, Config::default())
+            .with_rotation_interval(Duration::from_secs(0))
+            .with_rotation_speed(Duration::from_secs(0))
+            .with_rotation_direction(direction)
+            .with_text("abcd");
+        // The first step only starts the rotation
+        widget.next().unwrap();
+        let mut contents = Vec::new();
+        loop {
+            widget.next().unwrap();
+            contents.push(widget.get_rotated_content());
+            if !widget.rotating {
+                return contents;
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotation_direction() {
+        assert_eq!(
+            rotate(RotationDirection::Left),
+            vec!["bcd", "cd|", "d|a", "|ab", "abc"]
+        );
+        assert_eq!(
+            rotate(RotationDirection::Right),
+            vec!["|ab", "d|a", "cd|", "bcd", "abc"]
+        );
+    }
 }
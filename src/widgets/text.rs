@@ -2,15 +2,18 @@ use serde_json::value::Value;
 
 use super::super::widget::I3BarWidget;
 use crate::config::Config;
-use crate::widget::State;
+use crate::widget::{MinWidth, State};
 
 #[derive(Clone, Debug)]
 pub struct TextWidget {
     content: Option<String>,
     icon: Option<String>,
     state: State,
+    color: Option<String>,
+    min_width: Option<MinWidth>,
     rendered: Value,
     cached_output: Option<String>,
+    blink_rendered: Option<Value>,
     config: Config,
 }
 
@@ -20,6 +23,8 @@ impl TextWidget {
             content: None,
             icon: None,
             state: State::Idle,
+            color: None,
+            min_width: None,
             rendered: json!({
                 "full_text": "",
                 "separator": false,
@@ -29,6 +34,7 @@ impl TextWidget {
             }),
             config,
             cached_output: None,
+            blink_rendered: None,
         }
     }
 
@@ -50,6 +56,12 @@ impl TextWidget {
         self
     }
 
+    pub fn with_min_width(mut self, min_width: Option<MinWidth>) -> Self {
+        self.min_width = min_width;
+        self.update();
+        self
+    }
+
     pub fn set_text(&mut self, content: String) {
         self.content = Some(content);
         self.update();
@@ -65,19 +77,36 @@ impl TextWidget {
         self.update();
     }
 
+    /// Overrides the foreground color of the state, until it is reset with `None`.
+    pub fn set_color(&mut self, color: Option<String>) {
+        self.color = color;
+        self.update();
+    }
+
     fn update(&mut self) {
         let (key_bg, key_fg) = self.state.theme_keys(&self.config.theme);
 
         self.rendered = json!({
-            "full_text": format!("{}{} ",
-                                self.icon.clone().unwrap_or_else(|| String::from(" ")),
-                                self.content.clone().unwrap_or_else(|| String::from(""))),
+            "full_text": self.config.icon_position.render(
+                self.icon.as_deref().unwrap_or(" "),
+                self.content.as_deref().unwrap_or("")),
             "separator": false,
             "separator_block_width": 0,
             "background": key_bg.to_owned(),
-            "color": key_fg.to_owned()
+            "color": self.color.clone().unwrap_or_else(|| key_fg.to_owned())
         });
+        if let Some(ref min_width) = self.min_width {
+            let icon = self.icon.clone().unwrap_or_else(|| String::from(" "));
+            self.rendered["min_width"] = json!(min_width.render(&icon));
+        }
+        if let Some(border) = self.state.border(&self.config) {
+            self.rendered["border"] = json!(border);
+        }
+        if self.state.urgent(&self.config) {
+            self.rendered["urgent"] = json!(true);
+        }
 
+        self.blink_rendered = self.state.blink(&self.rendered, &self.config);
         self.cached_output = Some(self.rendered.to_string());
     }
 }
@@ -92,4 +121,8 @@ impl I3BarWidget for TextWidget {
     fn get_rendered(&self) -> &Value {
         &self.rendered
     }
+
+    fn get_blink_rendered(&self) -> Option<&Value> {
+        self.blink_rendered.as_ref()
+    }
 }